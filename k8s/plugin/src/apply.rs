@@ -0,0 +1,103 @@
+//! `kubectl openebs apply -f -`: reconciles a small declarative manifest
+//! of desired cluster state idempotently, for simple GitOps-ish batch
+//! workflows that don't justify running a full operator. Currently only
+//! understands node cordon state -- snapshots and QoS policies aren't
+//! implemented by any other command in this plugin yet, so there's
+//! nothing for this one to reconcile idempotently; add more `Manifest`
+//! fields here as those commands exist.
+//!
+//! Reconciliations run through [`crate::bulk`]: `--concurrency` entries
+//! at a time, with `--continue-on-error` keeping later entries going
+//! after one fails instead of the default fail-fast.
+
+use std::io::Read;
+
+use k8s_openapi::api::core::v1::Node;
+use kube::api::{Api, Patch, PatchParams};
+use serde::Deserialize;
+
+use crate::bulk;
+use crate::resources::Context;
+
+#[derive(clap::Args)]
+pub struct ApplyArgs {
+    /// Path to the manifest, or `-` to read it from stdin.
+    #[arg(short = 'f', long = "filename")]
+    pub filename: String,
+
+    /// Reconcile at most this many manifest entries concurrently.
+    #[arg(long, default_value_t = 4)]
+    pub concurrency: usize,
+
+    /// Keep reconciling the remaining entries after one fails, instead
+    /// of the default of not starting any more once the first one does.
+    #[arg(long, conflicts_with = "fail_fast")]
+    pub continue_on_error: bool,
+
+    /// Stop starting new reconciliations once the first one fails. This
+    /// is already the default; the flag exists so scripts can pin the
+    /// behavior explicitly instead of relying on it.
+    #[arg(long, conflicts_with = "continue_on_error")]
+    pub fail_fast: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct Manifest {
+    #[serde(default)]
+    cordons: Vec<CordonEntry>,
+}
+
+#[derive(Deserialize)]
+struct CordonEntry {
+    node: String,
+    cordoned: bool,
+}
+
+pub async fn run(ctx: &Context, args: ApplyArgs) -> anyhow::Result<()> {
+    let contents = read_manifest(&args.filename)?;
+    let manifest: Manifest = serde_yaml::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("parsing manifest {:?}: {e}", args.filename))?;
+
+    let nodes: Api<Node> = Api::all(ctx.client.clone());
+    let results = bulk::run(
+        manifest.cordons,
+        args.concurrency,
+        !args.continue_on_error,
+        |entry| entry.node.clone(),
+        move |entry| {
+            let nodes = nodes.clone();
+            async move { reconcile_cordon(&nodes, &entry).await }
+        },
+    )
+    .await;
+
+    if bulk::print_summary(&results) {
+        anyhow::bail!("one or more manifest entries failed to reconcile");
+    }
+    Ok(())
+}
+
+fn read_manifest(filename: &str) -> anyhow::Result<String> {
+    if filename == "-" {
+        let mut contents = String::new();
+        std::io::stdin().read_to_string(&mut contents)?;
+        Ok(contents)
+    } else {
+        Ok(std::fs::read_to_string(filename)
+            .map_err(|e| anyhow::anyhow!("reading {filename:?}: {e}"))?)
+    }
+}
+
+/// Patches `entry.node`'s `spec.unschedulable` only if it doesn't already
+/// match the manifest, so re-applying the same manifest is a no-op.
+async fn reconcile_cordon(nodes: &Api<Node>, entry: &CordonEntry) -> anyhow::Result<String> {
+    let node = nodes.get(&entry.node).await?;
+    let current = node.spec.as_ref().and_then(|s| s.unschedulable).unwrap_or(false);
+    if current == entry.cordoned {
+        return Ok(format!("unchanged ({})", if entry.cordoned { "cordoned" } else { "schedulable" }));
+    }
+
+    let patch = serde_json::json!({ "spec": { "unschedulable": entry.cordoned } });
+    nodes.patch(&entry.node, &PatchParams::default(), &Patch::Merge(patch)).await?;
+    Ok(if entry.cordoned { "cordoned".to_string() } else { "uncordoned".to_string() })
+}
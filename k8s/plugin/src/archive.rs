@@ -0,0 +1,151 @@
+//! Packs a directory into a `.tar.gz` or `.zip` archive. Used both for
+//! staged support bundles and (via [`tar_gz_bytes`]) for packaging a
+//! local Helm chart directory for air-gapped upgrades.
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use flate2::{write::GzEncoder, Compression};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Gzip-compresses `dir`'s contents into an in-memory tarball. Archive
+/// entry names always use forward slashes (tar's on-disk convention)
+/// regardless of the host path separator, so archives created on Windows
+/// extract cleanly on Linux and vice versa.
+pub fn tar_gz_bytes(dir: &Path) -> anyhow::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    {
+        let encoder = GzEncoder::new(&mut buf, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        builder.append_dir_all(".", dir)?;
+        builder.finish()?;
+    }
+    Ok(buf)
+}
+
+/// Writes `dir`'s contents into a gzip-compressed tarball at `output`.
+pub fn write_tar_gz(dir: &Path, output: &Path) -> anyhow::Result<()> {
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(output, tar_gz_bytes(dir)?)?;
+    Ok(())
+}
+
+/// Zips up `dir`'s contents into an in-memory archive, for Windows-based
+/// support engineers who don't have a `tar` handy. Entry names use
+/// forward slashes for the same reason [`tar_gz_bytes`]'s do.
+pub fn zip_bytes(dir: &Path) -> anyhow::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    {
+        let mut writer = ZipWriter::new(std::io::Cursor::new(&mut buf));
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for entry in walkdir::WalkDir::new(dir) {
+            let entry = entry?;
+            let relative = entry.path().strip_prefix(dir)?;
+            if relative.as_os_str().is_empty() {
+                continue;
+            }
+            let name = relative.to_string_lossy().replace(std::path::MAIN_SEPARATOR, "/");
+            if entry.file_type().is_dir() {
+                writer.add_directory(format!("{name}/"), options)?;
+            } else {
+                writer.start_file(name, options)?;
+                writer.write_all(&std::fs::read(entry.path())?)?;
+            }
+        }
+        writer.finish()?;
+    }
+    Ok(buf)
+}
+
+/// Writes `dir`'s contents into a zip archive at `output`.
+pub fn write_zip(dir: &Path, output: &Path) -> anyhow::Result<()> {
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(output, zip_bytes(dir)?)?;
+    Ok(())
+}
+
+/// Encrypts `data` for `recipient` by shelling out to `gpg`, the same way
+/// [`crate::upgrade::helm`] shells out to the `helm` CLI for operations
+/// this plugin has no native client for -- there's no OpenPGP crate in
+/// this plugin's dependency tree, and a support bundle is encrypted once
+/// per dump, not often enough to justify adding one. `recipient` is
+/// passed straight to `gpg --recipient`, so it accepts anything `gpg`
+/// does: a key ID, fingerprint, or email address already present in the
+/// caller's keyring.
+pub fn encrypt_for_recipient(data: &[u8], recipient: &str) -> anyhow::Result<Vec<u8>> {
+    let mut child = Command::new("gpg")
+        .args(["--batch", "--yes", "--trust-model", "always", "--recipient", recipient, "--encrypt"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("running gpg (is it installed?): {e}"))?;
+
+    // A multi-gigabyte archive can fill gpg's stdout pipe buffer before
+    // we're done writing its whole input; writing stdin and draining
+    // stdout/stderr (via wait_with_output, below) have to happen
+    // concurrently or both sides deadlock once that buffer's full.
+    let mut stdin = child.stdin.take().expect("stdin was piped");
+    let data = data.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&data));
+    let output = child.wait_with_output()?;
+    writer.join().expect("gpg stdin writer thread panicked")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "gpg --recipient {recipient} --encrypt failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(output.stdout)
+}
+
+/// PUTs `data` to `url`, for nodes a dump is collected on that have
+/// little local disk and no easy way to move a multi-gigabyte archive
+/// off-box otherwise -- a presigned upload URL from whatever ticketing
+/// or object storage system the operator already uses.
+pub async fn upload_to_url(data: Vec<u8>, url: &str) -> anyhow::Result<()> {
+    let response = reqwest::Client::new().put(url).body(data).send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("PUT {url} returned {status}: {}", response.text().await.unwrap_or_default());
+    }
+    Ok(())
+}
+
+/// Uploads `data` to `s3://bucket/key` by shelling out to the `aws` CLI,
+/// the same way [`encrypt_for_recipient`] shells out to `gpg`: this
+/// plugin has no AWS SDK dependency, and a bundle upload is infrequent
+/// enough not to justify one. Credentials and region come from whatever
+/// the `aws` CLI itself is configured with (env vars, `~/.aws/config`,
+/// an instance profile), not from this plugin.
+pub fn upload_to_s3(data: &[u8], bucket: &str, key: &str) -> anyhow::Result<()> {
+    let destination = format!("s3://{bucket}/{key}");
+    let mut child = Command::new("aws")
+        .args(["s3", "cp", "-", &destination])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("running aws (is the AWS CLI installed?): {e}"))?;
+
+    // Same pipe-buffer deadlock as `encrypt_for_recipient`: a
+    // multi-gigabyte archive needs stdin written and stdout/stderr
+    // drained concurrently, not stdin-then-wait.
+    let mut stdin = child.stdin.take().expect("stdin was piped");
+    let data = data.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&data));
+    let output = child.wait_with_output()?;
+    writer.join().expect("aws stdin writer thread panicked")?;
+    if !output.status.success() {
+        anyhow::bail!("aws s3 cp - {destination} failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    Ok(())
+}
+
+
@@ -0,0 +1,199 @@
+//! Guardrail rules shared by `kubectl openebs webhook serve` (enforced
+//! live, via a ValidatingWebhookConfiguration) and any offline audit
+//! command that wants the same checks. Each rule inspects a single
+//! object in isolation so it can run equally well against a live
+//! AdmissionReview request or a file loaded from disk.
+
+use kube::api::DynamicObject;
+use serde_json::Value;
+
+/// A single guardrail violation found on an object.
+pub struct Violation {
+    pub rule: &'static str,
+    pub message: String,
+}
+
+/// Namespaces treated as "prod" for the purposes of [`single_replica_sc`];
+/// until per-cluster configuration exists this is the convention most
+/// openebs deployments already follow.
+const PROD_NAMESPACE_PREFIXES: &[&str] = &["prod", "production"];
+
+/// Topology keys every openebs StorageClass's `allowedTopologies` should
+/// include, so volumes aren't scheduled blind to zone/rack failure
+/// domains.
+const REQUIRED_TOPOLOGY_KEYS: &[&str] = &["kubernetes.io/hostname"];
+
+/// hostPath base directories that must never be used for LocalPV-hostpath
+/// StorageClasses, since they overlap with node or container runtime state.
+const FORBIDDEN_HOSTPATH_BASE_DIRS: &[&str] = &["/", "/etc", "/var/lib/kubelet", "/var/lib/docker"];
+
+/// Runs every guardrail rule against `obj` and returns all violations
+/// found (empty if `obj` is clean).
+pub fn check(obj: &DynamicObject) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    violations.extend(single_replica_sc(obj));
+    violations.extend(required_topology_keys(obj));
+    violations.extend(forbidden_hostpath_base_dir(obj));
+    violations
+}
+
+fn is_storage_class(obj: &DynamicObject) -> bool {
+    obj.types.as_ref().map(|t| t.kind.as_str()) == Some("StorageClass")
+}
+
+fn parameters(obj: &DynamicObject) -> Option<&serde_json::Map<String, Value>> {
+    obj.data.get("parameters")?.as_object()
+}
+
+fn single_replica_sc(obj: &DynamicObject) -> Option<Violation> {
+    if !is_storage_class(obj) {
+        return None;
+    }
+    let ns = obj.metadata.namespace.as_deref().unwrap_or("");
+    let is_prod = PROD_NAMESPACE_PREFIXES.iter().any(|p| ns.starts_with(p));
+    let replica_count = parameters(obj)
+        .and_then(|p| p.get("replicaCount"))
+        .and_then(Value::as_str)
+        .and_then(|s| s.parse::<u32>().ok());
+
+    if is_prod && replica_count == Some(1) {
+        Some(Violation {
+            rule: "single-replica-sc-in-prod",
+            message: format!(
+                "StorageClass {:?} has replicaCount=1 but is used in prod namespace {ns:?}",
+                obj.metadata.name
+            ),
+        })
+    } else {
+        None
+    }
+}
+
+fn required_topology_keys(obj: &DynamicObject) -> Option<Violation> {
+    if !is_storage_class(obj) {
+        return None;
+    }
+    let topologies = obj.data.get("allowedTopologies")?.as_array()?;
+    let keys: Vec<&str> = topologies
+        .iter()
+        .filter_map(|t| t.get("matchLabelExpressions"))
+        .filter_map(Value::as_array)
+        .flatten()
+        .filter_map(|e| e.get("key"))
+        .filter_map(Value::as_str)
+        .collect();
+
+    let missing: Vec<&str> = REQUIRED_TOPOLOGY_KEYS
+        .iter()
+        .filter(|k| !keys.contains(k))
+        .copied()
+        .collect();
+    if missing.is_empty() {
+        None
+    } else {
+        Some(Violation {
+            rule: "required-topology-keys",
+            message: format!(
+                "StorageClass {:?} is missing required topology key(s): {}",
+                obj.metadata.name,
+                missing.join(", ")
+            ),
+        })
+    }
+}
+
+fn forbidden_hostpath_base_dir(obj: &DynamicObject) -> Option<Violation> {
+    if !is_storage_class(obj) {
+        return None;
+    }
+    let base_dir = parameters(obj)?.get("BasePath")?.as_str()?;
+    if FORBIDDEN_HOSTPATH_BASE_DIRS.contains(&base_dir) {
+        Some(Violation {
+            rule: "forbidden-hostpath-base-dir",
+            message: format!(
+                "StorageClass {:?} uses forbidden hostpath base dir {base_dir:?}",
+                obj.metadata.name
+            ),
+        })
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn storage_class(namespace: &str, extra: serde_json::Value) -> DynamicObject {
+        let mut value = json!({
+            "apiVersion": "storage.k8s.io/v1",
+            "kind": "StorageClass",
+            "metadata": {"name": "test-sc", "namespace": namespace},
+        });
+        for (k, v) in extra.as_object().unwrap() {
+            value.as_object_mut().unwrap().insert(k.clone(), v.clone());
+        }
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn single_replica_sc_flags_single_replica_in_prod() {
+        let obj = storage_class("prod-db", json!({"parameters": {"replicaCount": "1"}}));
+        assert!(single_replica_sc(&obj).is_some());
+    }
+
+    #[test]
+    fn single_replica_sc_allows_single_replica_outside_prod() {
+        let obj = storage_class("staging", json!({"parameters": {"replicaCount": "1"}}));
+        assert!(single_replica_sc(&obj).is_none());
+    }
+
+    #[test]
+    fn single_replica_sc_allows_multi_replica_in_prod() {
+        let obj = storage_class("prod-db", json!({"parameters": {"replicaCount": "3"}}));
+        assert!(single_replica_sc(&obj).is_none());
+    }
+
+    #[test]
+    fn required_topology_keys_flags_missing_hostname_key() {
+        let obj = storage_class(
+            "default",
+            json!({"allowedTopologies": [{"matchLabelExpressions": [{"key": "topology.kubernetes.io/zone"}]}]}),
+        );
+        assert!(required_topology_keys(&obj).is_some());
+    }
+
+    #[test]
+    fn required_topology_keys_allows_hostname_key_present() {
+        let obj = storage_class(
+            "default",
+            json!({"allowedTopologies": [{"matchLabelExpressions": [{"key": "kubernetes.io/hostname"}]}]}),
+        );
+        assert!(required_topology_keys(&obj).is_none());
+    }
+
+    #[test]
+    fn forbidden_hostpath_base_dir_flags_etc() {
+        let obj = storage_class("default", json!({"parameters": {"BasePath": "/etc"}}));
+        assert!(forbidden_hostpath_base_dir(&obj).is_some());
+    }
+
+    #[test]
+    fn forbidden_hostpath_base_dir_allows_dedicated_dir() {
+        let obj = storage_class("default", json!({"parameters": {"BasePath": "/data/openebs"}}));
+        assert!(forbidden_hostpath_base_dir(&obj).is_none());
+    }
+
+    #[test]
+    fn check_ignores_non_storage_class_objects() {
+        let obj: DynamicObject = serde_json::from_value(json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": {"name": "unrelated", "namespace": "prod"},
+            "parameters": {"replicaCount": "1"},
+        }))
+        .unwrap();
+        assert!(check(&obj).is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+//! Shared bulk-execution engine for commands that apply the same
+//! operation across many independent objects (currently `apply`'s
+//! cordon reconciliation). Runs with bounded concurrency and records a
+//! per-item success/failure instead of bailing out of the whole batch
+//! on the first error, returning results in input order so a caller can
+//! print a summary table.
+//!
+//! `fail_fast` stops *starting* new items once one has failed; items
+//! already in flight still run to completion rather than being aborted
+//! mid-way.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use futures::stream::{self, StreamExt};
+
+/// Outcome of running the operation against a single item, keyed by
+/// `label` (typically the item's name) for the summary table.
+pub struct ItemResult {
+    pub label: String,
+    pub outcome: Result<String, String>,
+}
+
+/// Runs `op(item)` for every item in `items`, `concurrency` at a time.
+/// `label` extracts the name used to identify each item in
+/// [`ItemResult`]s and progress output.
+pub async fn run<T, F, Fut>(
+    items: Vec<T>,
+    concurrency: usize,
+    fail_fast: bool,
+    label: impl Fn(&T) -> String,
+    op: F,
+) -> Vec<ItemResult>
+where
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = anyhow::Result<String>>,
+{
+    let halted = Arc::new(AtomicBool::new(false));
+    let op = &op;
+    let mut results: Vec<(usize, ItemResult)> = stream::iter(items.into_iter().enumerate())
+        .map(|(index, item)| {
+            let item_label = label(&item);
+            let halted = Arc::clone(&halted);
+            async move {
+                if fail_fast && halted.load(Ordering::Relaxed) {
+                    return (
+                        index,
+                        ItemResult {
+                            label: item_label,
+                            outcome: Err("skipped (--fail-fast, an earlier item failed)".to_string()),
+                        },
+                    );
+                }
+                let outcome = op(item).await.map_err(|e| e.to_string());
+                if outcome.is_err() {
+                    halted.store(true, Ordering::Relaxed);
+                }
+                (index, ItemResult { label: item_label, outcome })
+            }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Prints one line per item plus a final `N succeeded, M failed` count,
+/// and returns whether any item failed so the caller can decide its
+/// exit status.
+pub fn print_summary(results: &[ItemResult]) -> bool {
+    let failed = results.iter().filter(|r| r.outcome.is_err()).count();
+    for result in results {
+        match &result.outcome {
+            Ok(message) => println!("  {} ok: {message}", result.label),
+            Err(e) => println!("  {} FAILED: {e}", result.label),
+        }
+    }
+    println!("{} succeeded, {failed} failed, {} total", results.len() - failed, results.len());
+    failed > 0
+}
@@ -0,0 +1,43 @@
+//! Constants shared across subcommands. Kept in one place (rather than
+//! duplicated per module, as `HTTP_DATA_PAGE_SIZE` used to be between the
+//! upgrade and resources code) so a tuning change only has to happen once.
+
+/// Default page size used for both Kubernetes list calls and REST volume
+/// listings. 500 balances a reasonable number of round-trips against not
+/// overwhelming a slow apiserver with a single huge response; `--page-size`
+/// overrides it per-invocation for clusters at either extreme.
+pub const DEFAULT_PAGE_SIZE: u32 = 500;
+
+/// Default repository for the upgrade Job's image; overridden by
+/// `--upgrade-image-repo`, or bypassed entirely by `--upgrade-image` for
+/// mirrored/air-gapped registries with a different path layout.
+pub const UPGRADE_JOB_IMAGE_REPO: &str = "openebs/upgrade-job";
+
+/// How long a finished upgrade Job (and, via cascading deletion, the
+/// ConfigMaps/ServiceAccount it owns) is kept around before the
+/// `ttlSecondsAfterFinished` controller cleans it up. An hour is enough
+/// to read its logs/status after the fact without leaving clutter behind
+/// indefinitely; overridden by `--job-ttl-seconds`.
+pub const UPGRADE_JOB_TTL_SECONDS_AFTER_FINISHED: i32 = 3600;
+
+/// How long `dump system` waits for a single collector (e.g. an
+/// engine's CRD listing) before giving up on it: an unreachable CRD API
+/// used to hang the whole bundle collection indefinitely. Overridden by
+/// `--collector-timeout-seconds`.
+pub const DUMP_COLLECTOR_TIMEOUT_SECONDS: u64 = 30;
+
+/// Default cap on how many `dump system`/`dump incident` collectors run
+/// at once. Collectors already run concurrently rather than one after
+/// another, but letting every engine's collectors (plus events, Helm
+/// history, etcd, ...) all fire simultaneously against the apiserver and
+/// each engine's own API adds up on a large cluster; overridden by
+/// `--max-concurrent-collectors`.
+pub const DUMP_MAX_CONCURRENT_COLLECTORS: usize = 8;
+
+/// Default Helm chart repository index consulted by `version
+/// --check-updates`, matching the repository OpenEBS's own install
+/// instructions add via `helm repo add`.
+pub const DEFAULT_CHART_REPO_INDEX_URL: &str = "https://openebs.github.io/charts/index.yaml";
+
+/// Default chart name looked up in that index.
+pub const DEFAULT_CHART_NAME: &str = "openebs";
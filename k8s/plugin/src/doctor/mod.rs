@@ -0,0 +1,167 @@
+//! `kubectl openebs doctor`: a read-only, run-anytime health check --
+//! prints per-node OS/kernel facts and, where node-feature-discovery
+//! labels are present, whether each known engine's kernel module
+//! requirements are met. Complements the preflight validations
+//! `upgrade apply` runs (see `crate::upgrade::validations`), which block
+//! an upgrade; this is meant for routine troubleshooting, not gating one.
+//!
+//! `--checks-file` additionally runs user-supplied checks (see
+//! [`checks`]) against each node's facts, for org-specific policies the
+//! built-in engine compatibility verdicts don't cover.
+//!
+//! `--report-format sarif|junit` renders the same verdicts/checks for a
+//! CI dashboard or code-scanning UI instead of printing them line by
+//! line; see [`crate::report`].
+
+pub mod checks;
+mod trust;
+
+use std::path::PathBuf;
+
+use k8s_openapi::api::core::v1::Node;
+use kube::api::Api;
+
+use crate::node_facts::{self, Verdict};
+use crate::report::{self, CheckOutcome, ReportFormat};
+use crate::resources::{list_all, Context};
+use trust::CertSecretRef;
+
+/// Engines checked for kernel module compatibility; mirrors the engines
+/// `kubectl openebs dump` collects CRs for. Also used by `setup` to
+/// report the same per-node compatibility ahead of a first install.
+pub(crate) const ENGINES: &[&str] = &["zfs", "lvm", "mayastor"];
+
+#[derive(clap::Args)]
+pub struct DoctorArgs {
+    /// Path to a YAML file of user-supplied checks, evaluated against
+    /// each node's facts in addition to the built-in engine
+    /// compatibility verdicts. See [`checks::CheckDefinition`].
+    #[arg(long)]
+    pub checks_file: Option<PathBuf>,
+
+    /// Render engine compatibility verdicts and check results as SARIF or
+    /// JUnit XML instead of plain text, for ingestion by CI dashboards
+    /// and code-scanning UIs.
+    #[arg(long, value_enum, default_value = "text")]
+    pub report_format: ReportFormat,
+
+    /// Maximum allowed skew, in seconds, between a node's last-reported
+    /// `Ready` heartbeat and this plugin's own wall clock before it's
+    /// flagged. kubelet renews this heartbeat every `node-monitor-period`
+    /// (10s by default) regardless of the node's actual readiness.
+    #[arg(long, default_value_t = 30)]
+    pub max_clock_skew_seconds: i64,
+
+    /// `namespace/name` of a Secret holding a `tls.crt` data key (a CSI
+    /// webhook's serving certificate, or any other cert this cluster
+    /// depends on) to check for upcoming expiry. Repeatable; there's no
+    /// fixed naming convention for these secrets, so none are checked
+    /// unless named explicitly.
+    #[arg(long = "cert-secret")]
+    pub cert_secrets: Vec<CertSecretRef>,
+
+    /// Flag a certificate as expiring once it has fewer than this many
+    /// days of validity left.
+    #[arg(long, default_value_t = 30)]
+    pub cert_expiry_warning_days: i64,
+}
+
+pub async fn run(ctx: &Context, args: DoctorArgs) -> anyhow::Result<()> {
+    let user_checks = match &args.checks_file {
+        Some(path) => checks::load_checks_file(path)?,
+        None => Vec::new(),
+    };
+    let text = matches!(args.report_format, ReportFormat::Text);
+    let mut outcomes = Vec::new();
+
+    let nodes: Api<Node> = Api::all(ctx.client.clone());
+    for node in list_all(&nodes, ctx.page_size).await? {
+        let facts = node_facts::node_facts(&node);
+        if text {
+            println!(
+                "{}: kernel={} os={} container-runtime={} cgroup-mode={}",
+                facts.name,
+                display(&facts.kernel_version),
+                display(&facts.os_image),
+                display(&facts.container_runtime_version),
+                facts.cgroup_mode.as_deref().unwrap_or("unknown"),
+            );
+            println!(
+                "  selinux: {}",
+                match facts.selinux_enforcing {
+                    Some(true) => "enforcing",
+                    Some(false) => "permissive/disabled",
+                    None => "unknown (no node-feature-discovery data)",
+                }
+            );
+        }
+        let skew_outcome = trust::clock_skew_check(&node, args.max_clock_skew_seconds);
+        if text {
+            println!("  clock-skew: {}", skew_outcome.message);
+        }
+        outcomes.push(skew_outcome);
+
+        for engine in ENGINES {
+            let verdict = node_facts::engine_compatibility(&facts, engine);
+            let (passed, message) = match &verdict {
+                Verdict::Compatible => (true, "compatible".to_string()),
+                Verdict::Incompatible(missing) => {
+                    (false, format!("INCOMPATIBLE (missing kernel module(s): {})", missing.join(", ")))
+                }
+                Verdict::Unknown(modules) => {
+                    (true, format!("unknown (no node-feature-discovery data for: {})", modules.join(", ")))
+                }
+            };
+            if text {
+                println!("  {engine}: {message}");
+            }
+            outcomes.push(CheckOutcome {
+                suite: facts.name.clone(),
+                name: engine.to_string(),
+                passed,
+                message,
+            });
+        }
+
+        if !user_checks.is_empty() {
+            let doc = serde_json::to_value(&facts)?;
+            for result in checks::run_checks(&doc, &user_checks) {
+                if text {
+                    println!(
+                        "  check {:?}: {} ({})",
+                        result.name,
+                        if result.passed { "PASS" } else { "FAIL" },
+                        result.detail
+                    );
+                }
+                outcomes.push(CheckOutcome {
+                    suite: facts.name.clone(),
+                    name: result.name,
+                    passed: result.passed,
+                    message: result.detail,
+                });
+            }
+        }
+    }
+
+    for cert_secret in &args.cert_secrets {
+        let outcome = trust::cert_expiry_check(ctx, cert_secret, args.cert_expiry_warning_days).await;
+        if text {
+            println!("{}: {}", outcome.suite, outcome.message);
+        }
+        outcomes.push(outcome);
+    }
+
+    if !text {
+        println!("{}", report::render(args.report_format, "doctor", &outcomes));
+    }
+    Ok(())
+}
+
+fn display(s: &str) -> &str {
+    if s.is_empty() {
+        "unknown"
+    } else {
+        s
+    }
+}
@@ -0,0 +1,131 @@
+//! Clock skew and certificate expiry checks: both cause storage failures
+//! that are hard to trace back to their actual cause (a CSI webhook
+//! silently rejecting every admission request once its serving cert
+//! expires, or replica rebuild timestamps disagreeing across nodes
+//! whose clocks have drifted) and neither shows up in the engine
+//! compatibility verdicts [`super`] already runs.
+//!
+//! There's no fixed convention in this repo (or upstream) for which
+//! Secret holds a given webhook's or the REST API's TLS cert -- see
+//! `crate::webhook`'s own doc comment -- so certificate checks are
+//! opt-in via explicit `--cert-secret namespace/name` arguments rather
+//! than guessed at.
+
+use k8s_openapi::api::core::v1::{Node, Secret};
+use k8s_openapi::chrono::Utc;
+use kube::api::Api;
+
+use crate::report::CheckOutcome;
+use crate::resources::Context;
+
+/// One `--cert-secret namespace/name` argument.
+#[derive(Clone)]
+pub struct CertSecretRef {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl std::str::FromStr for CertSecretRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (namespace, name) = s.split_once('/').ok_or_else(|| {
+            format!("{s:?} must be in the form namespace/name")
+        })?;
+        Ok(CertSecretRef { namespace: namespace.to_string(), name: name.to_string() })
+    }
+}
+
+impl std::fmt::Display for CertSecretRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.namespace, self.name)
+    }
+}
+
+/// Compares each Node's last-reported heartbeat time (the `Ready`
+/// condition, which kubelet renews every `node-monitor-period`
+/// regardless of the condition's actual status) against this plugin's
+/// own wall clock. A large skew means either the node's clock or this
+/// machine's clock has drifted, which is otherwise invisible until it
+/// corrupts replica rebuild ordering or certificate validity checks.
+pub fn clock_skew_check(node: &Node, max_skew_seconds: i64) -> CheckOutcome {
+    let name = node.metadata.name.clone().unwrap_or_default();
+    let heartbeat = node
+        .status
+        .as_ref()
+        .and_then(|status| status.conditions.as_ref())
+        .and_then(|conditions| conditions.iter().find(|c| c.type_ == "Ready"))
+        .and_then(|ready| ready.last_heartbeat_time.as_ref());
+
+    let (passed, message) = match heartbeat {
+        Some(heartbeat) => {
+            let skew_seconds = (Utc::now() - heartbeat.0).num_seconds().abs();
+            if skew_seconds > max_skew_seconds {
+                (
+                    false,
+                    format!(
+                        "clock skew of {skew_seconds}s exceeds --max-clock-skew-seconds={max_skew_seconds} \
+                         (node's last Ready heartbeat: {})",
+                        heartbeat.0.to_rfc3339()
+                    ),
+                )
+            } else {
+                (true, format!("clock skew {skew_seconds}s within {max_skew_seconds}s"))
+            }
+        }
+        None => (true, "no Ready condition reported yet".to_string()),
+    };
+
+    CheckOutcome { suite: name, name: "clock-skew".to_string(), passed, message }
+}
+
+/// Fetches `cert_secret`'s `tls.crt` data key, parses it as a PEM X.509
+/// certificate, and checks its `notAfter` against `warning_days`.
+pub async fn cert_expiry_check(
+    ctx: &Context,
+    cert_secret: &CertSecretRef,
+    warning_days: i64,
+) -> CheckOutcome {
+    let name = format!("cert-expiry:{cert_secret}");
+    match cert_expiry_days_remaining(ctx, cert_secret).await {
+        Ok(days_remaining) => {
+            let passed = days_remaining > warning_days;
+            let message = if passed {
+                format!("{cert_secret} expires in {days_remaining}d")
+            } else {
+                format!(
+                    "{cert_secret} expires in {days_remaining}d, at or below --cert-expiry-warning-days={warning_days}"
+                )
+            };
+            CheckOutcome { suite: cert_secret.to_string(), name, passed, message }
+        }
+        Err(e) => CheckOutcome {
+            suite: cert_secret.to_string(),
+            name,
+            passed: false,
+            message: format!("could not check certificate expiry: {e}"),
+        },
+    }
+}
+
+async fn cert_expiry_days_remaining(
+    ctx: &Context,
+    cert_secret: &CertSecretRef,
+) -> anyhow::Result<i64> {
+    let secrets: Api<Secret> = Api::namespaced(ctx.client.clone(), &cert_secret.namespace);
+    let secret = secrets.get(&cert_secret.name).await?;
+    let tls_crt = secret
+        .data
+        .and_then(|mut data| data.remove("tls.crt"))
+        .ok_or_else(|| anyhow::anyhow!("Secret has no `tls.crt` data key"))?;
+
+    let (_, pem) = x509_parser::pem::parse_x509_pem(&tls_crt.0)
+        .map_err(|e| anyhow::anyhow!("parsing PEM: {e}"))?;
+    let certificate = pem
+        .parse_x509()
+        .map_err(|e| anyhow::anyhow!("parsing X.509 certificate: {e}"))?;
+
+    let not_after = certificate.validity().not_after.timestamp();
+    let now = Utc::now().timestamp();
+    Ok((not_after - now) / (24 * 60 * 60))
+}
@@ -0,0 +1,622 @@
+//! Individual pieces of a support bundle. Each [`Collector`] is
+//! independent and is expected to degrade gracefully (returning
+//! [`CollectorOutcome::Skipped`] with a reason) rather than fail the
+//! whole dump when it hits something it can't handle, such as a node
+//! running an unsupported OS.
+
+use std::path::Path;
+
+use async_trait::async_trait;
+// Re-exported by k8s-openapi rather than pulled in as a separate
+// dependency, so the `Time`/`MicroTime` fields on Event and this
+// collector's own window comparison always agree on the same chrono
+// version.
+use k8s_openapi::chrono::{DateTime, Utc};
+
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{Event, Node, Pod};
+use k8s_openapi::api::storage::v1::StorageClass;
+use kube::api::{Api, DynamicObject, GroupVersionKind, ListParams, LogParams};
+use kube::discovery::ApiResource;
+
+use crate::node_facts;
+use crate::resources::{list_all, Context};
+use crate::upgrade::helm;
+
+use super::etcd::EtcdSpecsCollector;
+
+pub enum CollectorOutcome {
+    Collected,
+    Skipped(String),
+}
+
+/// True if `e` is a Kubernetes 403 Forbidden response, so a collector can
+/// record "skipped: insufficient permissions for <resource>" instead of
+/// failing the whole dump over one missing RBAC rule -- see
+/// `kubectl openebs dump check-rbac` for a pre-check that reports these
+/// up front instead of mid-collection.
+fn is_forbidden(e: &kube::Error) -> bool {
+    matches!(e, kube::Error::Api(resp) if resp.code == 403)
+}
+
+#[async_trait]
+pub trait Collector: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn collect(&self, ctx: &Context, dir: &Path) -> anyhow::Result<CollectorOutcome>;
+}
+
+/// A half-open-free, inclusive time range `kubectl openebs dump incident`
+/// filters time-series data (currently just Events) down to.
+#[derive(Clone, Copy)]
+pub struct TimeWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, t: DateTime<Utc>) -> bool {
+        t >= self.start && t <= self.end
+    }
+}
+
+/// Engine names accepted by `dump system --engines`.
+pub const KNOWN_ENGINES: &[&str] = &["zfs", "lvm", "mayastor", "hostpath"];
+
+/// Builds the standard collector set. `window` narrows the
+/// [`EventsCollector`] to a specific incident window (see
+/// `kubectl openebs dump incident`); `None` collects every Event, as
+/// `dump system` always has. `engines`, if given, restricts the
+/// engine-specific collectors (zfs/lvm/mayastor/hostpath) to just those named,
+/// skipping the others' collection attempts (and their error noise on
+/// clusters that don't run them) entirely rather than collecting and
+/// then discarding them; collectors that aren't engine-specific (nodes,
+/// Helm history, metrics, Events) always run regardless. `etcd_endpoint` overrides
+/// the Mayastor etcd collector's in-cluster Service DNS guess (see
+/// [`EtcdSpecsCollector::new`]).
+pub fn default_collectors(
+    window: Option<TimeWindow>,
+    engines: Option<&[String]>,
+    etcd_endpoint: Option<String>,
+) -> Vec<Box<dyn Collector>> {
+    let wants = |engine: &str| engines.map(|selected| selected.iter().any(|e| e == engine)).unwrap_or(true);
+
+    let mut collectors: Vec<Box<dyn Collector>> = vec![Box::new(NodeCollector)];
+    if wants("zfs") {
+        collectors.push(Box::new(EngineCrCollector::zfs()));
+    }
+    if wants("lvm") {
+        collectors.push(Box::new(EngineCrCollector::lvm()));
+    }
+    if wants("hostpath") {
+        collectors.push(Box::new(HostpathCollector));
+    }
+    collectors.push(Box::new(HelmHistoryCollector));
+    collectors.push(Box::new(MetricsCollector));
+    if wants("mayastor") {
+        collectors.push(Box::new(EtcdSpecsCollector::new(etcd_endpoint.clone())));
+    }
+    collectors.push(match window {
+        Some(window) => Box::new(EventsCollector::windowed(window)),
+        None => Box::new(EventsCollector::all()),
+    });
+    collectors
+}
+
+/// Collects per-node diagnostics: the Node object's own status, plus the
+/// OS/kernel/engine-compatibility facts `kubectl openebs doctor` prints
+/// (see [`crate::node_facts`]), and data-plane agent exec output. Node
+/// agent exec commands assume a Linux shell, so nodes reporting a
+/// non-Linux OS are skipped with a recorded reason instead of being
+/// attempted and failing partway through.
+pub struct NodeCollector;
+
+#[async_trait]
+impl Collector for NodeCollector {
+    fn name(&self) -> &'static str {
+        "nodes"
+    }
+
+    async fn collect(&self, ctx: &Context, dir: &Path) -> anyhow::Result<CollectorOutcome> {
+        let nodes: Api<Node> = Api::all(ctx.client.clone());
+        let nodes_dir = dir.join("nodes");
+        std::fs::create_dir_all(&nodes_dir)?;
+
+        let node_list = match list_all(&nodes, ctx.page_size).await {
+            Ok(nodes) => nodes,
+            Err(e) if is_forbidden(&e) => return Ok(CollectorOutcome::Skipped("insufficient permissions for nodes".to_string())),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut skipped = Vec::new();
+        for node in node_list {
+            let name = node.metadata.name.clone().unwrap_or_default();
+            let os = node
+                .status
+                .as_ref()
+                .and_then(|s| s.node_info.as_ref())
+                .map(|info| info.operating_system.as_str())
+                .unwrap_or("");
+
+            if os != "linux" {
+                skipped.push(format!("{name} (unsupported OS: {os})"));
+                continue;
+            }
+
+            let summary = serde_json::to_string_pretty(&node.status)?;
+            std::fs::write(nodes_dir.join(format!("{name}.json")), summary)?;
+
+            let facts = node_facts::node_facts(&node);
+            std::fs::write(
+                nodes_dir.join(format!("{name}-facts.json")),
+                serde_json::to_string_pretty(&facts)?,
+            )?;
+        }
+
+        if skipped.is_empty() {
+            Ok(CollectorOutcome::Collected)
+        } else {
+            Ok(CollectorOutcome::Skipped(format!(
+                "node exec unsupported on: {}",
+                skipped.join(", ")
+            )))
+        }
+    }
+}
+
+/// Dumps an engine's custom resources (ZFS's `ZFSVolume`/`ZFSSnapshot`/
+/// `ZFSBackup`/`ZFSRestore`, LVM's `LVMVolume`/`LVMSnapshot`) verbatim as
+/// JSON, one file per object.
+/// Used to be two separate `zfs_dump`/`lvm_dump` functions; unified into
+/// one collector parameterized by the engine's API group/kinds since the
+/// two only ever differed in which CRDs they listed.
+pub struct EngineCrCollector {
+    name: &'static str,
+    group: &'static str,
+    version: &'static str,
+    kinds: &'static [&'static str],
+}
+
+impl EngineCrCollector {
+    pub fn zfs() -> Self {
+        Self {
+            name: "zfs",
+            group: "zfs.csi.openebs.io",
+            version: "v1",
+            kinds: &["ZFSVolume", "ZFSSnapshot", "ZFSBackup", "ZFSRestore"],
+        }
+    }
+
+    pub fn lvm() -> Self {
+        Self {
+            name: "lvm",
+            group: "local.openebs.io",
+            version: "v1alpha1",
+            kinds: &["LVMVolume", "LVMSnapshot"],
+        }
+    }
+}
+
+#[async_trait]
+impl Collector for EngineCrCollector {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn collect(&self, ctx: &Context, dir: &Path) -> anyhow::Result<CollectorOutcome> {
+        let engine_dir = dir.join(self.name);
+        std::fs::create_dir_all(&engine_dir)?;
+
+        let mut missing = Vec::new();
+        for kind in self.kinds {
+            let gvk = GroupVersionKind::gvk(self.group, self.version, kind);
+            let resource = ApiResource::from_gvk(&gvk);
+            let api: Api<DynamicObject> = Api::all_with(ctx.client.clone(), &resource);
+
+            let objects = match list_all(&api, ctx.page_size).await {
+                Ok(objects) => objects,
+                Err(e) if is_forbidden(&e) => {
+                    return Ok(CollectorOutcome::Skipped(format!("insufficient permissions for {kind}")));
+                }
+                // The CRD isn't installed, e.g. this engine isn't in use
+                // on this cluster -- not a failure, just nothing to dump.
+                Err(_) => {
+                    missing.push(*kind);
+                    continue;
+                }
+            };
+            for obj in objects {
+                let name = obj.metadata.name.clone().unwrap_or_default();
+                let namespace = obj.metadata.namespace.clone().unwrap_or_default();
+                let file_name = format!("{kind}-{namespace}-{name}.json");
+                std::fs::write(engine_dir.join(file_name), serde_json::to_string_pretty(&obj)?)?;
+            }
+        }
+
+        if missing.len() == self.kinds.len() {
+            Ok(CollectorOutcome::Skipped(format!("{} CRDs not installed", self.name)))
+        } else {
+            Ok(CollectorOutcome::Collected)
+        }
+    }
+}
+
+/// Dumps Hostpath LocalPV state: the provisioner Deployment's pod logs,
+/// its `openebs.io/local`-provisioned StorageClasses (so their
+/// `cas.openebs.io/config` annotation comes along for free, since it's
+/// dumped as part of the object rather than extracted specially),
+/// BlockDeviceClaims, and Events involving any PV/PVC that StorageClass
+/// provisioned. Unlike [`EngineCrCollector`], Hostpath LocalPV has no
+/// volume-level CRD of its own -- a hostpath PV is just a PV with a
+/// `local` volume source -- so there's nothing analogous to dump there.
+pub struct HostpathCollector;
+
+const HOSTPATH_PROVISIONER: &str = "openebs.io/local";
+const HOSTPATH_PROVISIONER_DEPLOYMENT: &str = "openebs-localpv-provisioner";
+
+#[async_trait]
+impl Collector for HostpathCollector {
+    fn name(&self) -> &'static str {
+        "hostpath"
+    }
+
+    async fn collect(&self, ctx: &Context, dir: &Path) -> anyhow::Result<CollectorOutcome> {
+        let hostpath_dir = dir.join(self.name());
+        std::fs::create_dir_all(&hostpath_dir)?;
+        let mut collected = false;
+
+        if collect_provisioner_logs(ctx, &hostpath_dir).await? {
+            collected = true;
+        }
+
+        let scs: Api<StorageClass> = Api::all(ctx.client.clone());
+        let storage_classes: Vec<StorageClass> = match list_all(&scs, ctx.page_size).await {
+            Ok(scs) => scs.into_iter().filter(|sc| sc.provisioner == HOSTPATH_PROVISIONER).collect(),
+            Err(e) if is_forbidden(&e) => return Ok(CollectorOutcome::Skipped("insufficient permissions for storageclasses".to_string())),
+            Err(e) => return Err(e.into()),
+        };
+        for sc in &storage_classes {
+            let name = sc.metadata.name.clone().unwrap_or_default();
+            write_json(&hostpath_dir, &format!("storageclass-{name}.json"), sc)?;
+            collected = true;
+        }
+
+        let claims_collected = collect_block_device_claims(ctx, &hostpath_dir).await?;
+        collected = collected || claims_collected;
+
+        let sc_names: Vec<String> = storage_classes.iter().filter_map(|sc| sc.metadata.name.clone()).collect();
+        if !sc_names.is_empty() {
+            collect_hostpath_events(ctx, &sc_names, &hostpath_dir).await?;
+        }
+
+        if collected {
+            Ok(CollectorOutcome::Collected)
+        } else {
+            Ok(CollectorOutcome::Skipped("no Hostpath LocalPV provisioner, StorageClass, or BlockDeviceClaim found".to_string()))
+        }
+    }
+}
+
+/// Writes the `openebs-localpv-provisioner` Deployment's pods' logs, one
+/// file per pod/container. Returns whether there was anything to collect
+/// (the Deployment may not exist on a cluster that doesn't run Hostpath
+/// LocalPV).
+async fn collect_provisioner_logs(ctx: &Context, dir: &std::path::Path) -> anyhow::Result<bool> {
+    let deployments: Api<Deployment> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let Ok(deployment) = deployments.get(HOSTPATH_PROVISIONER_DEPLOYMENT).await else {
+        return Ok(false);
+    };
+    let Some(match_labels) = deployment.spec.as_ref().and_then(|s| s.selector.match_labels.as_ref()) else {
+        return Ok(false);
+    };
+    let selector = match_labels.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join(",");
+
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let matched = pods.list(&ListParams::default().labels(&selector)).await?;
+
+    let logs_dir = dir.join("provisioner-logs");
+    let mut any = false;
+    for pod in &matched.items {
+        let name = pod.metadata.name.clone().unwrap_or_default();
+        let containers: Vec<String> =
+            pod.spec.as_ref().map(|spec| spec.containers.iter().map(|c| c.name.clone()).collect()).unwrap_or_default();
+        for container in containers {
+            let params = LogParams { container: Some(container.clone()), ..Default::default() };
+            if let Ok(logs) = pods.logs(&name, &params).await {
+                std::fs::create_dir_all(&logs_dir)?;
+                std::fs::write(logs_dir.join(format!("{name}-{container}.log")), logs)?;
+                any = true;
+            }
+        }
+    }
+    Ok(any)
+}
+
+/// Writes every `BlockDeviceClaim` (`openebs.io/v1alpha1`) verbatim as
+/// JSON, one file per object. Returns whether any were found.
+async fn collect_block_device_claims(ctx: &Context, dir: &std::path::Path) -> anyhow::Result<bool> {
+    let gvk = GroupVersionKind::gvk("openebs.io", "v1alpha1", "BlockDeviceClaim");
+    let resource = ApiResource::from_gvk(&gvk);
+    let api: Api<DynamicObject> = Api::all_with(ctx.client.clone(), &resource);
+
+    let claims = match list_all(&api, ctx.page_size).await {
+        Ok(claims) => claims,
+        // The CRD isn't installed -- not a failure, just nothing to dump.
+        Err(_) => return Ok(false),
+    };
+    if claims.is_empty() {
+        return Ok(false);
+    }
+    let claims_dir = dir.join("block-device-claims");
+    std::fs::create_dir_all(&claims_dir)?;
+    for claim in &claims {
+        let name = claim.metadata.name.clone().unwrap_or_default();
+        let namespace = claim.metadata.namespace.clone().unwrap_or_default();
+        std::fs::write(claims_dir.join(format!("{namespace}-{name}.json")), serde_json::to_string_pretty(claim)?)?;
+    }
+    Ok(true)
+}
+
+/// Dumps Events involving any PV/PVC provisioned by one of `sc_names`'
+/// StorageClasses, mirroring `dump volume`'s own `collect_events`.
+async fn collect_hostpath_events(ctx: &Context, sc_names: &[String], dir: &std::path::Path) -> anyhow::Result<()> {
+    use k8s_openapi::api::core::v1::{PersistentVolume, PersistentVolumeClaim};
+
+    let pvs: Api<PersistentVolume> = Api::all(ctx.client.clone());
+    let pv_names: Vec<String> = list_all(&pvs, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|pv| pv.spec.as_ref().and_then(|s| s.storage_class_name.as_deref()).is_some_and(|sc| sc_names.iter().any(|n| n == sc)))
+        .filter_map(|pv| pv.metadata.name)
+        .collect();
+
+    let pvcs: Api<PersistentVolumeClaim> = Api::all(ctx.client.clone());
+    let pvc_names: Vec<String> = list_all(&pvcs, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|pvc| pvc.spec.as_ref().and_then(|s| s.storage_class_name.as_deref()).is_some_and(|sc| sc_names.iter().any(|n| n == sc)))
+        .filter_map(|pvc| pvc.metadata.name)
+        .collect();
+
+    let related_names: Vec<String> = pv_names.into_iter().chain(pvc_names).collect();
+    if related_names.is_empty() {
+        return Ok(());
+    }
+
+    let events: Api<Event> = Api::all(ctx.client.clone());
+    let matched: Vec<Event> = list_all(&events, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|event| related_names.iter().any(|n| n == &event.involved_object.name.clone().unwrap_or_default()))
+        .collect();
+    if matched.is_empty() {
+        return Ok(());
+    }
+
+    let events_dir = dir.join("events");
+    std::fs::create_dir_all(&events_dir)?;
+    for event in &matched {
+        let namespace = event.metadata.namespace.clone().unwrap_or_default();
+        let name = event.metadata.name.clone().unwrap_or_default();
+        write_json(&events_dir, &format!("{namespace}-{name}.json"), event)?;
+    }
+    Ok(())
+}
+
+fn write_json<T: serde::Serialize>(dir: &std::path::Path, file_name: &str, value: &T) -> anyhow::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(dir.join(file_name), serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+/// Dumps every revision of the `openebs` chart's Helm release history
+/// (not just the currently deployed one), one file per revision, so
+/// support can see what changed across recent upgrades instead of just
+/// the current state.
+pub struct HelmHistoryCollector;
+
+#[async_trait]
+impl Collector for HelmHistoryCollector {
+    fn name(&self) -> &'static str {
+        "helm-history"
+    }
+
+    async fn collect(&self, ctx: &Context, dir: &Path) -> anyhow::Result<CollectorOutcome> {
+        let history = match helm::release_history(ctx, "openebs").await {
+            Ok(history) => history,
+            Err(e) => return Ok(CollectorOutcome::Skipped(e.to_string())),
+        };
+        if history.is_empty() {
+            return Ok(CollectorOutcome::Skipped("no helm release history found".to_string()));
+        }
+
+        let history_dir = dir.join("helm-history");
+        std::fs::create_dir_all(&history_dir)?;
+        for release in &history {
+            let file_name = format!("revision-{}.json", release.version);
+            std::fs::write(history_dir.join(file_name), serde_json::to_string_pretty(release)?)?;
+        }
+        Ok(CollectorOutcome::Collected)
+    }
+}
+
+/// Scrapes raw Prometheus exposition text off each OpenEBS metrics
+/// target's pod IP directly -- io-engine's own exporter, the
+/// `volume_exporter_prometheus` sidecars `k8s/openebs-servicemonitor.yaml`
+/// already scrapes, and the CSI controller's sidecars -- so a bundle
+/// carries the same point-in-time capacity/latency numbers Grafana would
+/// show, without requiring support to have cluster monitoring set up at
+/// all. Each pod is scraped over whichever of its container ports is
+/// named `metrics` or `exporter`, rather than a hardcoded port number,
+/// since the chart's actual port varies by engine and version.
+pub struct MetricsCollector;
+
+const POOL_METRICS_LABEL_SELECTOR: &str = "monitoring=volume_exporter_prometheus";
+const CSI_CONTROLLER_DEPLOYMENT: &str = "mayastor-csi-controller";
+const METRICS_PORT_NAMES: &[&str] = &["metrics", "exporter"];
+
+#[async_trait]
+impl Collector for MetricsCollector {
+    fn name(&self) -> &'static str {
+        "metrics"
+    }
+
+    async fn collect(&self, ctx: &Context, dir: &Path) -> anyhow::Result<CollectorOutcome> {
+        let metrics_dir = dir.join(self.name());
+
+        let mut targets: Vec<(&'static str, Pod)> = Vec::new();
+
+        let pods: Api<Pod> = Api::all(ctx.client.clone());
+        let all_pods = match list_all(&pods, ctx.page_size).await {
+            Ok(pods) => pods,
+            Err(e) if is_forbidden(&e) => return Ok(CollectorOutcome::Skipped("insufficient permissions for pods".to_string())),
+            Err(e) => return Err(e.into()),
+        };
+        targets.extend(all_pods.into_iter().filter(|pod| has_container(pod, "io-engine")).map(|pod| ("io-engine", pod)));
+
+        let namespaced: Api<Pod> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+        let pool_metrics = match namespaced.list(&ListParams::default().labels(POOL_METRICS_LABEL_SELECTOR)).await {
+            Ok(pool_metrics) => pool_metrics,
+            Err(e) if is_forbidden(&e) => return Ok(CollectorOutcome::Skipped("insufficient permissions for pods".to_string())),
+            Err(e) => return Err(e.into()),
+        };
+        targets.extend(pool_metrics.items.into_iter().map(|pod| ("pool-metrics", pod)));
+
+        targets.extend(
+            deployment_pods(ctx, CSI_CONTROLLER_DEPLOYMENT)
+                .await?
+                .into_iter()
+                .map(|pod| ("csi-controller", pod)),
+        );
+
+        let mut collected = false;
+        let mut skipped = Vec::new();
+        for (category, pod) in targets {
+            let name = pod.metadata.name.clone().unwrap_or_default();
+            let Some(ip) = pod.status.as_ref().and_then(|s| s.pod_ip.clone()) else {
+                skipped.push(format!("{category}/{name}: no pod IP"));
+                continue;
+            };
+            let Some(port) = metrics_port(&pod) else {
+                skipped.push(format!("{category}/{name}: no metrics/exporter port"));
+                continue;
+            };
+            match scrape_metrics(&ip, port).await {
+                Ok(body) => {
+                    let category_dir = metrics_dir.join(category);
+                    std::fs::create_dir_all(&category_dir)?;
+                    std::fs::write(category_dir.join(format!("{name}.prom")), body)?;
+                    collected = true;
+                }
+                Err(e) => skipped.push(format!("{category}/{name}: {e}")),
+            }
+        }
+
+        if collected {
+            Ok(CollectorOutcome::Collected)
+        } else if skipped.is_empty() {
+            Ok(CollectorOutcome::Skipped("no metrics targets found".to_string()))
+        } else {
+            Ok(CollectorOutcome::Skipped(format!("no metrics scraped: {}", skipped.join("; "))))
+        }
+    }
+}
+
+fn has_container(pod: &Pod, container: &str) -> bool {
+    pod.spec.as_ref().is_some_and(|spec| spec.containers.iter().any(|c| c.name == container))
+}
+
+/// Lists the pods of a Deployment by the Deployment's own
+/// `spec.selector.matchLabels`, the same way
+/// [`collect_provisioner_logs`] finds the Hostpath LocalPV provisioner's
+/// pods. Returns an empty list rather than an error if the Deployment
+/// doesn't exist, e.g. this cluster doesn't run Mayastor.
+async fn deployment_pods(ctx: &Context, deployment_name: &str) -> anyhow::Result<Vec<Pod>> {
+    let deployments: Api<Deployment> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let Ok(deployment) = deployments.get(deployment_name).await else {
+        return Ok(Vec::new());
+    };
+    let Some(match_labels) = deployment.spec.as_ref().and_then(|s| s.selector.match_labels.as_ref()) else {
+        return Ok(Vec::new());
+    };
+    let selector = match_labels.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join(",");
+
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    Ok(pods.list(&ListParams::default().labels(&selector)).await?.items)
+}
+
+fn metrics_port(pod: &Pod) -> Option<i32> {
+    pod.spec.as_ref()?.containers.iter().find_map(|c| {
+        c.ports
+            .as_ref()?
+            .iter()
+            .find(|p| p.name.as_deref().is_some_and(|n| METRICS_PORT_NAMES.contains(&n)))
+            .map(|p| p.container_port)
+    })
+}
+
+async fn scrape_metrics(ip: &str, port: i32) -> anyhow::Result<String> {
+    Ok(reqwest::get(format!("http://{ip}:{port}/metrics")).await?.error_for_status()?.text().await?)
+}
+
+/// Dumps cluster-wide Events, one file per object. With a [`TimeWindow`]
+/// set (`kubectl openebs dump incident`), only Events whose
+/// `lastTimestamp`/`firstTimestamp` falls inside it are collected, so an
+/// incident bundle doesn't drown a short window in a cluster's entire
+/// event history.
+pub struct EventsCollector {
+    window: Option<TimeWindow>,
+}
+
+impl EventsCollector {
+    pub fn all() -> Self {
+        Self { window: None }
+    }
+
+    pub fn windowed(window: TimeWindow) -> Self {
+        Self { window: Some(window) }
+    }
+}
+
+#[async_trait]
+impl Collector for EventsCollector {
+    fn name(&self) -> &'static str {
+        "events"
+    }
+
+    async fn collect(&self, ctx: &Context, dir: &Path) -> anyhow::Result<CollectorOutcome> {
+        let events: Api<Event> = Api::all(ctx.client.clone());
+        let event_list = match list_all(&events, ctx.page_size).await {
+            Ok(events) => events,
+            Err(e) if is_forbidden(&e) => return Ok(CollectorOutcome::Skipped("insufficient permissions for events".to_string())),
+            Err(e) => return Err(e.into()),
+        };
+        let matched: Vec<Event> = event_list
+            .into_iter()
+            .filter(|event| match &self.window {
+                None => true,
+                Some(window) => event
+                    .last_timestamp
+                    .as_ref()
+                    .or(event.first_timestamp.as_ref())
+                    .map(|t| window.contains(t.0))
+                    .unwrap_or(false),
+            })
+            .collect();
+
+        if matched.is_empty() {
+            return Ok(CollectorOutcome::Skipped("no events in range".to_string()));
+        }
+
+        let events_dir = dir.join("events");
+        std::fs::create_dir_all(&events_dir)?;
+        for event in &matched {
+            let namespace = event.metadata.namespace.clone().unwrap_or_default();
+            let name = event.metadata.name.clone().unwrap_or_default();
+            std::fs::write(
+                events_dir.join(format!("{namespace}-{name}.json")),
+                serde_json::to_string_pretty(event)?,
+            )?;
+        }
+        Ok(CollectorOutcome::Collected)
+    }
+}
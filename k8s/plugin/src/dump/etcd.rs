@@ -0,0 +1,146 @@
+//! Decodes Mayastor control-plane specs out of its etcd store for
+//! `kubectl openebs dump`, instead of leaving the bundle with raw,
+//! nearly-unreadable keys the way a generic etcd snapshot would.
+//! Mayastor's control plane persists volume/nexus/pool specs to etcd as
+//! JSON, not protobuf, so this talks to etcd's grpc-gateway JSON API
+//! directly over HTTP -- the same thin-REST-client approach [`crate::rest`]
+//! already takes for the control-plane's own REST API -- rather than
+//! pulling in a full etcd/gRPC client.
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::resources::Context;
+
+use super::collectors::{Collector, CollectorOutcome};
+
+const ETCD_GATEWAY_PORT: u16 = 2379;
+
+/// Collects Mayastor's etcd keyspace. By default it guesses the
+/// in-cluster Service DNS name etcd-operator charts use
+/// (`mayastor-etcd.<namespace>.svc.cluster.local`); pass an explicit
+/// `endpoint` (`--etcd-endpoint`, a `host:port`) for charts installed
+/// under a different release name, or an etcd reached through a
+/// port-forward from outside the cluster.
+pub struct EtcdSpecsCollector {
+    endpoint: Option<String>,
+}
+
+impl EtcdSpecsCollector {
+    pub fn new(endpoint: Option<String>) -> Self {
+        Self { endpoint }
+    }
+}
+
+#[async_trait]
+impl Collector for EtcdSpecsCollector {
+    fn name(&self) -> &'static str {
+        "etcd-specs"
+    }
+
+    async fn collect(&self, ctx: &Context, dir: &Path) -> anyhow::Result<CollectorOutcome> {
+        let base_url = match &self.endpoint {
+            Some(endpoint) => format!("http://{endpoint}"),
+            None => format!("http://mayastor-etcd.{}.svc.cluster.local:{ETCD_GATEWAY_PORT}", ctx.namespace),
+        };
+        let kvs = match fetch_all_keys(&base_url).await {
+            Ok(kvs) => kvs,
+            Err(e) => return Ok(CollectorOutcome::Skipped(format!("etcd gateway unreachable: {e}"))),
+        };
+        if kvs.is_empty() {
+            return Ok(CollectorOutcome::Skipped("no keys found in etcd".to_string()));
+        }
+
+        let etcd_dir = dir.join("etcd-specs");
+        std::fs::create_dir_all(&etcd_dir)?;
+        for kv in &kvs {
+            let key = decode_base64_string(&kv.key)?;
+            let value = decode_base64_bytes(&kv.value)?;
+            let file_name = key_to_file_name(&key);
+            std::fs::write(etcd_dir.join(file_name), decode_spec(&value))?;
+        }
+        Ok(CollectorOutcome::Collected)
+    }
+}
+
+#[derive(Deserialize)]
+struct RangeResponse {
+    #[serde(default)]
+    kvs: Vec<Kv>,
+}
+
+#[derive(Deserialize)]
+struct Kv {
+    key: String,
+    #[serde(default)]
+    value: String,
+}
+
+/// Ranges over the whole keyspace via etcd's `v3/kv/range` grpc-gateway
+/// endpoint, using `range_end` formed from incrementing the last byte of
+/// the (empty) prefix -- the standard etcd "prefix scan" trick.
+async fn fetch_all_keys(base_url: &str) -> anyhow::Result<Vec<Kv>> {
+    let body = serde_json::json!({
+        "key": encode_base64(&[0]),
+        "range_end": encode_base64(&[0]),
+    });
+    let response = reqwest::Client::new()
+        .post(format!("{base_url}/v3/kv/range"))
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<RangeResponse>()
+        .await?;
+    Ok(response.kvs)
+}
+
+/// Classifies a decoded etcd key by the Mayastor store prefix it falls
+/// under (`.../volume/...`, `.../nexus/...`, `.../pool/...`), so a support
+/// engineer can tell specs apart at a glance without opening each file.
+fn key_to_file_name(key: &str) -> String {
+    let kind = if key.contains("/volume/") {
+        "volume"
+    } else if key.contains("/nexus/") {
+        "nexus"
+    } else if key.contains("/pool/") {
+        "pool"
+    } else {
+        "other"
+    };
+    let safe_key = key.trim_start_matches('/').replace('/', "_");
+    format!("{kind}-{safe_key}.yaml")
+}
+
+/// Decodes a spec value as JSON and re-renders it as YAML for
+/// readability. Version-tolerant: an etcd payload from a control-plane
+/// version this decoder doesn't recognize still round-trips as generic
+/// JSON (any valid JSON value parses), and anything that isn't even JSON
+/// falls back to a labeled raw dump instead of failing the whole
+/// collector.
+fn decode_spec(raw: &[u8]) -> String {
+    match serde_json::from_slice::<Value>(raw) {
+        Ok(value) => serde_yaml::to_string(&value)
+            .unwrap_or_else(|_| String::from_utf8_lossy(raw).into_owned()),
+        Err(_) => format!(
+            "# not decodable as JSON by this version's decoder; raw bytes below\n{}",
+            String::from_utf8_lossy(raw)
+        ),
+    }
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+fn decode_base64_bytes(encoded: &str) -> anyhow::Result<Vec<u8>> {
+    Ok(base64::engine::general_purpose::STANDARD.decode(encoded)?)
+}
+
+fn decode_base64_string(encoded: &str) -> anyhow::Result<String> {
+    Ok(String::from_utf8(decode_base64_bytes(encoded)?)?)
+}
@@ -0,0 +1,140 @@
+//! `dump system --loki-url`/`dump incident --loki-url`: pulls log lines
+//! for a time range straight out of a Grafana Loki deployment over its
+//! HTTP query API, for the logs a cluster's own containers have already
+//! rotated away by the time someone runs `dump`. Opt-in: nothing talks
+//! to Loki unless `--loki-url` is given, since plenty of clusters this
+//! plugin runs against don't have it installed at all.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use async_trait::async_trait;
+use k8s_openapi::chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+
+use crate::resources::Context;
+
+use super::collectors::{Collector, CollectorOutcome};
+
+/// Width of each `query_range` call. A `--since`/`--until` spanning more
+/// than this is split into consecutive chunks rather than sent as one
+/// query, so a multi-day range doesn't run into Loki's own per-query
+/// result limit and silently come back truncated.
+const CHUNK_WIDTH: Duration = Duration::hours(1);
+
+/// Loki's own default `limit` is 100; raised here since a collector
+/// would rather get a large-but-complete chunk than have to guess how
+/// many chunks got cut short.
+const QUERY_LIMIT: u32 = 5000;
+
+pub struct LokiLogsCollector {
+    base_url: String,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    label_matchers: Vec<String>,
+}
+
+impl LokiLogsCollector {
+    pub fn new(base_url: String, since: DateTime<Utc>, until: DateTime<Utc>, label_matchers: Vec<String>) -> Self {
+        Self { base_url, since, until, label_matchers }
+    }
+}
+
+#[async_trait]
+impl Collector for LokiLogsCollector {
+    fn name(&self) -> &'static str {
+        "loki"
+    }
+
+    async fn collect(&self, _ctx: &Context, dir: &Path) -> anyhow::Result<CollectorOutcome> {
+        let query = build_logql_query(&self.label_matchers);
+        let loki_dir = dir.join(self.name());
+
+        let mut streams_written = 0usize;
+        for (chunk_index, (start, end)) in time_chunks(self.since, self.until).into_iter().enumerate() {
+            let response = match query_range(&self.base_url, &query, start, end).await {
+                Ok(response) => response,
+                Err(e) => return Ok(CollectorOutcome::Skipped(format!("loki unreachable: {e}"))),
+            };
+            for (stream_index, stream) in response.data.result.iter().enumerate() {
+                let labels = stream.stream.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join(",");
+                let mut body = format!("# {{{labels}}}\n");
+                for [timestamp, line] in &stream.values {
+                    body.push_str(&format!("{timestamp} {line}\n"));
+                }
+                std::fs::create_dir_all(&loki_dir)?;
+                std::fs::write(loki_dir.join(format!("chunk-{chunk_index:04}-stream-{stream_index:02}.log")), body)?;
+                streams_written += 1;
+            }
+        }
+
+        if streams_written > 0 {
+            Ok(CollectorOutcome::Collected)
+        } else {
+            Ok(CollectorOutcome::Skipped("no matching Loki streams in range".to_string()))
+        }
+    }
+}
+
+/// Builds the LogQL stream selector from `--log-label-matcher` values
+/// (e.g. `namespace="openebs"`, `app=~"mayastor.*"`), already in LogQL's
+/// own `key<op>value` syntax so they're passed straight through rather
+/// than reinterpreted. An empty list matches every stream.
+fn build_logql_query(label_matchers: &[String]) -> String {
+    if label_matchers.is_empty() {
+        r#"{namespace=~".+"}"#.to_string()
+    } else {
+        format!("{{{}}}", label_matchers.join(","))
+    }
+}
+
+fn time_chunks(since: DateTime<Utc>, until: DateTime<Utc>) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut chunks = Vec::new();
+    let mut start = since;
+    while start < until {
+        let end = (start + CHUNK_WIDTH).min(until);
+        chunks.push((start, end));
+        start = end;
+    }
+    if chunks.is_empty() {
+        chunks.push((since, until));
+    }
+    chunks
+}
+
+#[derive(Deserialize)]
+struct QueryRangeResponse {
+    data: QueryRangeData,
+}
+
+#[derive(Deserialize)]
+struct QueryRangeData {
+    result: Vec<StreamResult>,
+}
+
+#[derive(Deserialize)]
+struct StreamResult {
+    stream: BTreeMap<String, String>,
+    values: Vec<[String; 2]>,
+}
+
+async fn query_range(
+    base_url: &str,
+    query: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> anyhow::Result<QueryRangeResponse> {
+    Ok(reqwest::Client::new()
+        .get(format!("{base_url}/loki/api/v1/query_range"))
+        .query(&[
+            ("query", query.to_string()),
+            ("start", start.timestamp_nanos_opt().unwrap_or(0).to_string()),
+            ("end", end.timestamp_nanos_opt().unwrap_or(0).to_string()),
+            ("limit", QUERY_LIMIT.to_string()),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<QueryRangeResponse>()
+        .await?)
+}
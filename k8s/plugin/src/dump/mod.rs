@@ -0,0 +1,1018 @@
+//! `kubectl openebs dump`: collects a support bundle (cluster state,
+//! pod logs, node diagnostics) for offline troubleshooting. `system`'s
+//! collectors run concurrently, up to `--max-concurrent-collectors` at a
+//! time, so one hung collector -- an unreachable CRD API, say -- can't
+//! stall the rest of the bundle, without every engine's collectors also
+//! piling onto the apiserver at once on a cluster with several engines
+//! installed. `incident` narrows
+//! that same collector set to a time window, and `volume` (see
+//! [`volume`]) skips the collector framework entirely in favor of
+//! following one volume's object graph. Every bundle ships a top-level
+//! `manifest.json` listing each file it contains with a SHA-256 so
+//! support can confirm nothing was corrupted or dropped in transit,
+//! alongside per-collector pass/fail status where there is one to
+//! report.
+
+mod collectors;
+mod etcd;
+mod loki;
+mod node;
+mod rbac;
+mod volume;
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, StreamExt};
+use k8s_openapi::chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::archive;
+use crate::constants::{DUMP_COLLECTOR_TIMEOUT_SECONDS, DUMP_MAX_CONCURRENT_COLLECTORS};
+use crate::resources::Context;
+use collectors::TimeWindow;
+
+/// How the collected bundle is written to disk.
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// A single `.tar.gz` archive (the default).
+    TarGz,
+    /// A single `.zip` archive, for Windows-based support engineers
+    /// without a `tar` handy.
+    Zip,
+    /// A plain, uncompressed directory tree.
+    Directory,
+}
+
+#[derive(clap::Subcommand)]
+pub enum DumpCommand {
+    /// Collect cluster-wide diagnostics into a support bundle.
+    System {
+        /// Path to write the bundle to, or `-` to stream it to stdout
+        /// (tar-gz/zip format only). Not required with `--dry-run`.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[arg(long, value_enum, default_value = "tar-gz")]
+        format: OutputFormatArg,
+        /// How long to wait for a single collector before giving up on it.
+        #[arg(long, default_value_t = DUMP_COLLECTOR_TIMEOUT_SECONDS)]
+        collector_timeout_seconds: u64,
+        /// Only run collectors for these engines (comma-separated; one
+        /// of `zfs`, `lvm`, `mayastor`, `hostpath`), instead of every
+        /// engine this plugin knows about. Collectors that aren't
+        /// engine-specific (nodes, Helm history, events) always run.
+        /// Clusters that only run one engine skip the others' failed
+        /// collection attempts and error noise entirely.
+        #[arg(long, value_delimiter = ',')]
+        engines: Option<Vec<String>>,
+        /// Encrypt the finished archive for this `gpg` recipient (key ID,
+        /// fingerprint, or email already present in the keyring) before
+        /// it's written, so a bundle can transit a ticketing system
+        /// without exposing cluster secrets in transit. Tar-gz/zip only --
+        /// there's no single file to encrypt for a directory tree.
+        #[arg(long)]
+        encrypt_recipient: Option<String>,
+        /// PUT the finished archive to this URL (e.g. a presigned upload
+        /// URL) after collection, in addition to writing `--output`.
+        /// Combine with `--output -` on a node with little local disk to
+        /// avoid keeping a second copy on it. Tar-gz/zip only.
+        #[arg(long, conflicts_with = "s3_bucket")]
+        upload_url: Option<String>,
+        /// Upload the finished archive to this S3 bucket (via the `aws`
+        /// CLI, using its own configured credentials) after collection,
+        /// in addition to writing `--output`. Tar-gz/zip only.
+        #[arg(long, conflicts_with = "upload_url")]
+        s3_bucket: Option<String>,
+        /// Cap the gzip-compressed archive at this many bytes, dropping
+        /// the largest remaining collected files (largest first) until
+        /// it fits, so a busy cluster's bundle stays small enough to
+        /// attach to a ticket instead of failing outright. Dropped files
+        /// are recorded in `manifest.json`.
+        #[arg(long)]
+        max_archive_size: Option<u64>,
+        /// Run at most this many collectors at once, instead of firing
+        /// every collector simultaneously, so a large cluster with many
+        /// engines installed doesn't pile all of them onto the apiserver
+        /// (and each engine's own API) at the same moment.
+        #[arg(long, default_value_t = DUMP_MAX_CONCURRENT_COLLECTORS)]
+        max_concurrent_collectors: usize,
+        /// `host:port` of the Mayastor control plane's etcd, overriding
+        /// the in-cluster Service DNS name (`mayastor-etcd.<namespace>`)
+        /// this plugin otherwise assumes -- e.g. for a chart installed
+        /// under a different release name, or an etcd reached through a
+        /// port-forward from outside the cluster.
+        #[arg(long)]
+        etcd_endpoint: Option<String>,
+        /// Base URL of a Grafana Loki deployment (e.g.
+        /// `http://loki.monitoring.svc.cluster.local:3100`) to pull log
+        /// lines from, in addition to the kube-apiserver logs every
+        /// collector already fetches. Requires `--since`/`--until`, since
+        /// `system` has no incident window of its own to default to.
+        #[arg(long)]
+        loki_url: Option<String>,
+        /// RFC3339 start of the Loki query range. Required with
+        /// `--loki-url`.
+        #[arg(long, requires = "loki_url")]
+        since: Option<String>,
+        /// RFC3339 end of the Loki query range. Required with
+        /// `--loki-url`.
+        #[arg(long, requires = "loki_url")]
+        until: Option<String>,
+        /// Extra LogQL label matchers (e.g. `namespace="openebs"`,
+        /// `app=~"mayastor.*"`) narrowing the Loki query beyond the
+        /// default of every stream. Has no effect without `--loki-url`.
+        #[arg(long, value_delimiter = ',')]
+        log_label_matcher: Vec<String>,
+        /// Run every collector as usual but discard the result instead of
+        /// writing `--output`, printing the plan it would have produced
+        /// -- every resource/log target it would ship and its size --
+        /// so an operator in a regulated environment can review what a
+        /// dump would capture before actually capturing it. `--output`
+        /// is not required with `--dry-run`.
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip collectors that already finished on a previous run into
+        /// the same `--output`, instead of starting the whole collection
+        /// over. Progress is recorded in the staging directory as each
+        /// collector finishes; an interrupted run (Ctrl-C, node reboot)
+        /// leaves it behind for the next `--resume` invocation to pick up.
+        /// Has no effect the first time a given `--output` is collected.
+        #[arg(long)]
+        resume: bool,
+    },
+    /// Collect a compact bundle scoped to a specific incident window,
+    /// instead of a full `system` dump's entire event history.
+    Incident {
+        /// RFC3339 timestamp the incident window is centered on, e.g.
+        /// `2026-08-09T14:32:00Z`.
+        #[arg(long)]
+        at: String,
+        /// Width of the incident window, centered on `--at`. Accepts a
+        /// number followed by `s`, `m` or `h` (e.g. `30m`, `2h`).
+        #[arg(long, default_value = "30m")]
+        window: String,
+        /// Path to write the bundle to, or `-` to stream it to stdout
+        /// (tar-gz/zip format only). Not required with `--dry-run`.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[arg(long, value_enum, default_value = "tar-gz")]
+        format: OutputFormatArg,
+        #[arg(long, default_value_t = DUMP_COLLECTOR_TIMEOUT_SECONDS)]
+        collector_timeout_seconds: u64,
+        /// Encrypt the finished archive for this `gpg` recipient. See
+        /// `dump system --encrypt-recipient`.
+        #[arg(long)]
+        encrypt_recipient: Option<String>,
+        /// See `dump system --upload-url`.
+        #[arg(long, conflicts_with = "s3_bucket")]
+        upload_url: Option<String>,
+        /// See `dump system --s3-bucket`.
+        #[arg(long, conflicts_with = "upload_url")]
+        s3_bucket: Option<String>,
+        /// See `dump system --max-archive-size`.
+        #[arg(long)]
+        max_archive_size: Option<u64>,
+        /// See `dump system --max-concurrent-collectors`.
+        #[arg(long, default_value_t = DUMP_MAX_CONCURRENT_COLLECTORS)]
+        max_concurrent_collectors: usize,
+        /// See `dump system --etcd-endpoint`.
+        #[arg(long)]
+        etcd_endpoint: Option<String>,
+        /// See `dump system --loki-url`. Unlike `system`, `--since`/
+        /// `--until` default to `--at`/`--window`'s own range when not
+        /// given explicitly.
+        #[arg(long)]
+        loki_url: Option<String>,
+        /// See `dump system --since`. Defaults to the incident window's
+        /// start.
+        #[arg(long, requires = "loki_url")]
+        since: Option<String>,
+        /// See `dump system --until`. Defaults to the incident window's
+        /// end.
+        #[arg(long, requires = "loki_url")]
+        until: Option<String>,
+        /// See `dump system --log-label-matcher`.
+        #[arg(long, value_delimiter = ',')]
+        log_label_matcher: Vec<String>,
+        /// See `dump system --dry-run`.
+        #[arg(long)]
+        dry_run: bool,
+        /// See `dump system --resume`.
+        #[arg(long)]
+        resume: bool,
+    },
+    /// Collect a focused bundle for a single volume: its PVC, PV, engine
+    /// CR, node and mounting pods, plus events for all of the above --
+    /// the most common support request unit.
+    Volume {
+        /// PVC name (in `--namespace`) or bound PV name/volume UUID.
+        id: String,
+        /// Path to write the bundle to, or `-` to stream it to stdout
+        /// (tar-gz/zip format only).
+        #[arg(short, long)]
+        output: PathBuf,
+        #[arg(long, value_enum, default_value = "tar-gz")]
+        format: OutputFormatArg,
+        /// Encrypt the finished archive for this `gpg` recipient. See
+        /// `dump system --encrypt-recipient`.
+        #[arg(long)]
+        encrypt_recipient: Option<String>,
+        /// See `dump system --upload-url`.
+        #[arg(long, conflicts_with = "s3_bucket")]
+        upload_url: Option<String>,
+        /// See `dump system --s3-bucket`.
+        #[arg(long, conflicts_with = "upload_url")]
+        s3_bucket: Option<String>,
+        /// See `dump system --max-archive-size`.
+        #[arg(long)]
+        max_archive_size: Option<u64>,
+        /// Keep only the last this many bytes of each mounting/node-agent
+        /// pod's logs, so one pod stuck in a noisy crash loop doesn't
+        /// dominate the bundle's size. Applies per container; logs that
+        /// fit under the cap are written in full.
+        #[arg(long)]
+        max_log_bytes_per_pod: Option<u64>,
+    },
+    /// Collect a focused bundle for a single storage node: its engine
+    /// node CR, data-plane pod logs, and events -- the node-scoped
+    /// counterpart to `dump volume`, for troubleshooting one
+    /// misbehaving node.
+    Node {
+        /// Node name.
+        node_name: String,
+        /// Path to write the bundle to, or `-` to stream it to stdout
+        /// (tar-gz/zip format only).
+        #[arg(short, long)]
+        output: PathBuf,
+        #[arg(long, value_enum, default_value = "tar-gz")]
+        format: OutputFormatArg,
+        /// Encrypt the finished archive for this `gpg` recipient. See
+        /// `dump system --encrypt-recipient`.
+        #[arg(long)]
+        encrypt_recipient: Option<String>,
+        /// See `dump system --upload-url`.
+        #[arg(long, conflicts_with = "s3_bucket")]
+        upload_url: Option<String>,
+        /// See `dump system --s3-bucket`.
+        #[arg(long, conflicts_with = "upload_url")]
+        s3_bucket: Option<String>,
+        /// See `dump system --max-archive-size`.
+        #[arg(long)]
+        max_archive_size: Option<u64>,
+        /// See `dump volume --max-log-bytes-per-pod`.
+        #[arg(long)]
+        max_log_bytes_per_pod: Option<u64>,
+    },
+    /// Reports which permissions `dump system`/`dump incident`'s
+    /// collectors need that the current ServiceAccount/user is missing,
+    /// instead of finding out one collector at a time partway through a
+    /// real dump.
+    CheckRbac,
+}
+
+/// One collector's outcome, recorded into `manifest.json` alongside the
+/// bundle so a reader can tell what's missing without re-running the dump.
+/// Also the unit `--resume` persists mid-run (see [`run_collectors`]), so
+/// it round-trips through JSON rather than just being written once at the
+/// end.
+#[derive(Serialize, Deserialize, Clone)]
+struct ManifestEntry {
+    name: String,
+    outcome: String,
+    duration_ms: u128,
+}
+
+/// `manifest.json`'s top-level shape: the collectors that ran (empty for
+/// `volume`/`node` bundles, which have no collector framework of their
+/// own), every file the bundle actually ships with its checksum, plus
+/// whatever [`enforce_max_archive_size`] had to drop to stay under
+/// `--max-archive-size`, so a reader can tell the difference between
+/// "this collector failed" and "this collector succeeded but got cut for
+/// space" without diffing bundle sizes across runs, and can verify the
+/// bundle wasn't corrupted or truncated in transit before digging in.
+#[derive(Serialize)]
+struct Manifest {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    collectors: Vec<ManifestEntry>,
+    files: Vec<ManifestFileEntry>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    dropped_for_size: Vec<DroppedEntry>,
+}
+
+/// One file shipped in the bundle: its path relative to the archive
+/// root, a SHA-256 of its contents so support can confirm nothing got
+/// corrupted or edited in transit, and the collector that wrote it --
+/// derived from the file's top-level directory, which every collector in
+/// [`collectors::default_collectors`] names after itself; `None` for
+/// `volume`/`node` bundles, which write files directly rather than
+/// through a named collector.
+#[derive(Serialize)]
+struct ManifestFileEntry {
+    path: String,
+    collector: Option<&'static str>,
+    sha256: String,
+    size_bytes: u64,
+    collected_at: DateTime<Utc>,
+}
+
+/// One file [`enforce_max_archive_size`] deleted from the staged bundle,
+/// largest first, to bring the archive under `--max-archive-size`.
+#[derive(Serialize)]
+struct DroppedEntry {
+    path: String,
+    size_bytes: u64,
+}
+
+// clap::ValueEnum needs Default/Display-friendly variants with stable
+// kebab-case names; re-exported as OutputFormat for the rest of the dump
+// subsystem so command wiring doesn't leak clap-specific naming.
+pub use OutputFormat as OutputFormatArg;
+
+/// `--output -`, streaming the bundle to stdout instead of writing it to a
+/// path, so it can be piped straight into e.g. `ssh backup-host 'cat >
+/// bundle.tar'` without needing local disk space for a second copy of the
+/// finished archive.
+fn is_stdout_output(output: &std::path::Path) -> bool {
+    output == std::path::Path::new("-")
+}
+
+/// Post-processing applied to the finished tar-gz archive, in addition to
+/// writing it to `--output`: encrypted for a recipient, and/or uploaded
+/// somewhere a disk-constrained node can't easily reach otherwise.
+/// Bundled together since every dump subcommand takes the same three
+/// flags and passes them straight through to [`finalize_bundle`].
+#[derive(Default)]
+struct ArchiveDestination {
+    encrypt_recipient: Option<String>,
+    upload_url: Option<String>,
+    s3_bucket: Option<String>,
+}
+
+impl ArchiveDestination {
+    /// None of these flags have anywhere to point at a directory tree:
+    /// `gpg`/`aws s3 cp`/an HTTP PUT all need one file, not a tree of
+    /// them, so reject the combination with `--format directory` up
+    /// front rather than silently leaving the tree as-is.
+    fn require_archive_file(&self, format: OutputFormat) -> anyhow::Result<()> {
+        if matches!(format, OutputFormat::Directory) {
+            if self.encrypt_recipient.is_some() {
+                anyhow::bail!("--encrypt-recipient only supports --format tar-gz/zip, not a directory tree");
+            }
+            if self.upload_url.is_some() || self.s3_bucket.is_some() {
+                anyhow::bail!("--upload-url/--s3-bucket only support --format tar-gz/zip, not a directory tree");
+            }
+        }
+        Ok(())
+    }
+
+    /// Uploads `data` (the finished, already-encrypted archive bytes) to
+    /// whichever destination was requested, if any, printing where it
+    /// ended up. `archive_name` is used as the S3 object key.
+    async fn upload(&self, data: &[u8], archive_name: &str) -> anyhow::Result<()> {
+        if let Some(url) = &self.upload_url {
+            archive::upload_to_url(data.to_vec(), url).await?;
+            println!("uploaded archive to {url}");
+        }
+        if let Some(bucket) = &self.s3_bucket {
+            archive::upload_to_s3(data, bucket, archive_name)?;
+            println!("uploaded archive to s3://{bucket}/{archive_name}");
+        }
+        Ok(())
+    }
+}
+
+pub async fn run(ctx: &Context, cmd: DumpCommand) -> anyhow::Result<()> {
+    match cmd {
+        DumpCommand::System {
+            output,
+            format,
+            collector_timeout_seconds,
+            engines,
+            encrypt_recipient,
+            upload_url,
+            s3_bucket,
+            max_archive_size,
+            max_concurrent_collectors,
+            etcd_endpoint,
+            loki_url,
+            since,
+            until,
+            log_label_matcher,
+            dry_run,
+            resume,
+        } => {
+            if let Some(engines) = &engines {
+                validate_engines(engines)?;
+            }
+            let mut collectors = collectors::default_collectors(None, engines.as_deref(), etcd_endpoint);
+            if let Some(loki_url) = loki_url {
+                let since = parse_rfc3339(since.as_deref().ok_or_else(|| anyhow::anyhow!("--since is required with --loki-url"))?)?;
+                let until = parse_rfc3339(until.as_deref().ok_or_else(|| anyhow::anyhow!("--until is required with --loki-url"))?)?;
+                collectors.push(Box::new(loki::LokiLogsCollector::new(loki_url, since, until, log_label_matcher)));
+            }
+            let limits = CollectionLimits { collector_timeout_seconds, max_archive_size, max_concurrent_collectors, resume };
+            if dry_run {
+                return plan_bundle(ctx, &collectors, limits).await;
+            }
+            let output = output.ok_or_else(|| anyhow::anyhow!("--output is required without --dry-run"))?;
+            require_streamable(&output, format)?;
+            let destination = ArchiveDestination { encrypt_recipient, upload_url, s3_bucket };
+            destination.require_archive_file(format)?;
+            collect_bundle(ctx, &collectors, &output, format, &destination, limits).await
+        }
+        DumpCommand::Incident {
+            at,
+            window,
+            output,
+            format,
+            collector_timeout_seconds,
+            encrypt_recipient,
+            upload_url,
+            s3_bucket,
+            max_archive_size,
+            max_concurrent_collectors,
+            etcd_endpoint,
+            loki_url,
+            since,
+            until,
+            log_label_matcher,
+            dry_run,
+            resume,
+        } => {
+            let window = parse_window(&at, &window)?;
+            let mut collectors = collectors::default_collectors(Some(window), None, etcd_endpoint);
+            if let Some(loki_url) = loki_url {
+                let since = since.as_deref().map(parse_rfc3339).transpose()?.unwrap_or(window.start);
+                let until = until.as_deref().map(parse_rfc3339).transpose()?.unwrap_or(window.end);
+                collectors.push(Box::new(loki::LokiLogsCollector::new(loki_url, since, until, log_label_matcher)));
+            }
+            let limits = CollectionLimits { collector_timeout_seconds, max_archive_size, max_concurrent_collectors, resume };
+            if dry_run {
+                return plan_bundle(ctx, &collectors, limits).await;
+            }
+            let output = output.ok_or_else(|| anyhow::anyhow!("--output is required without --dry-run"))?;
+            require_streamable(&output, format)?;
+            let destination = ArchiveDestination { encrypt_recipient, upload_url, s3_bucket };
+            destination.require_archive_file(format)?;
+            collect_bundle(ctx, &collectors, &output, format, &destination, limits).await
+        }
+        DumpCommand::Volume {
+            id,
+            output,
+            format,
+            encrypt_recipient,
+            upload_url,
+            s3_bucket,
+            max_archive_size,
+            max_log_bytes_per_pod,
+        } => {
+            require_streamable(&output, format)?;
+            let destination = ArchiveDestination { encrypt_recipient, upload_url, s3_bucket };
+            destination.require_archive_file(format)?;
+            let staging = staging_dir(&output, format)?;
+            volume::collect(ctx, &id, &staging, max_log_bytes_per_pod).await?;
+            let dropped_for_size = enforce_max_archive_size(&staging, max_archive_size)?;
+            report_dropped_for_size(&dropped_for_size);
+            write_file_manifest(&staging, dropped_for_size)?;
+            finalize_bundle(&staging, &output, format, &destination, "volume bundle").await
+        }
+        DumpCommand::Node {
+            node_name,
+            output,
+            format,
+            encrypt_recipient,
+            upload_url,
+            s3_bucket,
+            max_archive_size,
+            max_log_bytes_per_pod,
+        } => {
+            require_streamable(&output, format)?;
+            let destination = ArchiveDestination { encrypt_recipient, upload_url, s3_bucket };
+            destination.require_archive_file(format)?;
+            let staging = staging_dir(&output, format)?;
+            node::collect(ctx, &node_name, &staging, max_log_bytes_per_pod).await?;
+            let dropped_for_size = enforce_max_archive_size(&staging, max_archive_size)?;
+            report_dropped_for_size(&dropped_for_size);
+            write_file_manifest(&staging, dropped_for_size)?;
+            finalize_bundle(&staging, &output, format, &destination, "node bundle").await
+        }
+        DumpCommand::CheckRbac => rbac::run(ctx).await,
+    }
+}
+
+/// Prints what [`enforce_max_archive_size`] dropped for `volume`/`node`
+/// bundles, in addition to the same entries [`write_file_manifest`]
+/// records into `manifest.json`, so it's visible immediately rather than
+/// only on a later read of the bundle.
+fn report_dropped_for_size(dropped: &[DroppedEntry]) {
+    for entry in dropped {
+        println!("dropped {} ({} bytes) to stay under --max-archive-size", entry.path, entry.size_bytes);
+    }
+}
+
+/// `--output -` only makes sense for a single streamable archive, not a
+/// directory tree, so reject that combination up front instead of writing
+/// a directory's worth of files to a name literally called `-`.
+fn require_streamable(output: &std::path::Path, format: OutputFormat) -> anyhow::Result<()> {
+    if is_stdout_output(output) && matches!(format, OutputFormat::Directory) {
+        anyhow::bail!("--output - only supports --format tar-gz/zip, not a directory tree");
+    }
+    Ok(())
+}
+
+/// The S3 object key / upload-destination name an archive is identified
+/// by, falling back to a fixed name for `--output -` where there's no
+/// path to derive one from.
+fn archive_name(output: &std::path::Path, format: OutputFormat) -> String {
+    let default_name = || format!("bundle.{}", archive_extension(format));
+    if is_stdout_output(output) {
+        return default_name();
+    }
+    output.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_else(default_name)
+}
+
+/// The filename extension `archive_name`/the `manifest.json` writer use
+/// for `format`. Only meaningful for the single-file formats; `Directory`
+/// has no archive file of its own.
+fn archive_extension(format: OutputFormat) -> &'static str {
+    match format {
+        OutputFormat::TarGz => "tar.gz",
+        OutputFormat::Zip => "zip",
+        OutputFormat::Directory => "",
+    }
+}
+
+/// Writes `staging` out as `output` (archiving and removing the staging
+/// directory for tar-gz/zip; leaving it in place for a directory tree),
+/// applying `destination`'s encryption/upload first if requested. Shared
+/// by `volume` and `node`, which don't run through [`collect_bundle`]'s
+/// collector loop but still finish the same way `system`/`incident` do.
+async fn finalize_bundle(
+    staging: &std::path::Path,
+    output: &std::path::Path,
+    format: OutputFormat,
+    destination: &ArchiveDestination,
+    kind: &str,
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Directory => {
+            println!("wrote {kind} to {}", staging.display());
+        }
+        OutputFormat::TarGz | OutputFormat::Zip if is_stdout_output(output) => {
+            write_archive_to_stdout(staging, output, format, destination).await?;
+        }
+        OutputFormat::TarGz | OutputFormat::Zip => {
+            write_archive_to_path(staging, output, format, destination).await?;
+            println!("wrote {kind} to {}", output.display());
+        }
+    }
+    Ok(())
+}
+
+/// Rejects `--engines` names this plugin doesn't recognize up front,
+/// rather than silently collecting nothing for a typo'd engine name.
+fn validate_engines(engines: &[String]) -> anyhow::Result<()> {
+    for engine in engines {
+        if !collectors::KNOWN_ENGINES.contains(&engine.as_str()) {
+            anyhow::bail!(
+                "unknown --engines value {engine:?}; expected one of: {}",
+                collectors::KNOWN_ENGINES.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Archives `staging` (tar-gz or zip, per `format`) and writes it to
+/// stdout, then removes the staging directory. Collectors still write
+/// into a local temp directory first (the collector framework only
+/// knows how to write into one); what streaming to stdout saves is the
+/// *second* copy a normal run would write to `--output`, and it's what
+/// makes piping the result elsewhere possible in the first place.
+async fn write_archive_to_stdout(
+    staging: &std::path::Path,
+    output: &std::path::Path,
+    format: OutputFormat,
+    destination: &ArchiveDestination,
+) -> anyhow::Result<()> {
+    use std::io::Write;
+    let bytes = archive_bytes(staging, format, &archive_name(output, format), destination).await?;
+    std::fs::remove_dir_all(staging)?;
+    std::io::stdout().write_all(&bytes)?;
+    Ok(())
+}
+
+/// Archives `staging` (tar-gz or zip, per `format`) and writes it to
+/// `output`, then removes the staging directory, uploading the same
+/// bytes first if `destination` asks for it.
+async fn write_archive_to_path(
+    staging: &std::path::Path,
+    output: &std::path::Path,
+    format: OutputFormat,
+    destination: &ArchiveDestination,
+) -> anyhow::Result<()> {
+    if destination.encrypt_recipient.is_none() && destination.upload_url.is_none() && destination.s3_bucket.is_none() {
+        match format {
+            OutputFormat::TarGz => archive::write_tar_gz(staging, output)?,
+            OutputFormat::Zip => archive::write_zip(staging, output)?,
+            OutputFormat::Directory => unreachable!("Directory doesn't produce a single archive file"),
+        }
+    } else {
+        let bytes = archive_bytes(staging, format, &archive_name(output, format), destination).await?;
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(output, bytes)?;
+    }
+    std::fs::remove_dir_all(staging)?;
+    Ok(())
+}
+
+/// Archives `staging` (tar-gz or zip, per `format`), encrypting the
+/// result if requested, and uploading it (as `archive_name`) if
+/// requested, before returning the same bytes written to `--output`.
+async fn archive_bytes(
+    staging: &std::path::Path,
+    format: OutputFormat,
+    archive_name: &str,
+    destination: &ArchiveDestination,
+) -> anyhow::Result<Vec<u8>> {
+    let bytes = match format {
+        OutputFormat::TarGz => archive::tar_gz_bytes(staging)?,
+        OutputFormat::Zip => archive::zip_bytes(staging)?,
+        OutputFormat::Directory => unreachable!("Directory doesn't produce a single archive file"),
+    };
+    let bytes = match &destination.encrypt_recipient {
+        Some(recipient) => archive::encrypt_for_recipient(&bytes, recipient)?,
+        None => bytes,
+    };
+    destination.upload(&bytes, archive_name).await?;
+    Ok(bytes)
+}
+
+/// Parses `--at`/`--window` into the [`TimeWindow`] collectors filter
+/// time-series data (currently just Events) down to, centered on `at`.
+fn parse_window(at: &str, window: &str) -> anyhow::Result<TimeWindow> {
+    let at = parse_rfc3339(at)?;
+    let half = parse_duration(window)? / 2;
+    Ok(TimeWindow { start: at - half, end: at + half })
+}
+
+/// Parses an RFC3339 timestamp, as given to `--at`/`--since`/`--until`.
+fn parse_rfc3339(s: &str) -> anyhow::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| anyhow::anyhow!("{s:?} is not a valid RFC3339 timestamp: {e}"))
+}
+
+/// Parses a `30m`/`2h`/`90s`-style duration.
+fn parse_duration(s: &str) -> anyhow::Result<k8s_openapi::chrono::Duration> {
+    let (value, unit) = s.split_at(s.len().saturating_sub(1));
+    let value: i64 = value
+        .parse()
+        .map_err(|_| anyhow::anyhow!("--window {s:?} is not a valid duration (expected e.g. \"30m\", \"1h\", \"90s\")"))?;
+    match unit {
+        "s" => Ok(k8s_openapi::chrono::Duration::seconds(value)),
+        "m" => Ok(k8s_openapi::chrono::Duration::minutes(value)),
+        "h" => Ok(k8s_openapi::chrono::Duration::hours(value)),
+        other => anyhow::bail!("--window {s:?} has an unknown unit {other:?} (expected s, m or h)"),
+    }
+}
+
+/// Per-run tuning for [`collect_bundle`], grouped into one struct purely
+/// to keep that function's argument count down -- these aren't related
+/// enough to deserve their own named concept otherwise.
+#[derive(Clone, Copy)]
+struct CollectionLimits {
+    collector_timeout_seconds: u64,
+    max_archive_size: Option<u64>,
+    max_concurrent_collectors: usize,
+    /// `--resume`: skip collectors [`run_collectors`] already finished on
+    /// a previous run into the same staging directory.
+    resume: bool,
+}
+
+async fn collect_bundle(
+    ctx: &Context,
+    collectors: &[Box<dyn collectors::Collector>],
+    output: &std::path::Path,
+    format: OutputFormat,
+    destination: &ArchiveDestination,
+    limits: CollectionLimits,
+) -> anyhow::Result<()> {
+    let staging = staging_dir(output, format)?;
+    if !limits.resume && staging.exists() {
+        // A fresh (non-`--resume`) run starts from a clean staging
+        // directory, so files left behind by a collector that was killed
+        // mid-write on a previous attempt at the same `--output` don't
+        // end up mixed into this run's bundle.
+        std::fs::remove_dir_all(&staging)?;
+    }
+    std::fs::create_dir_all(&staging)?;
+
+    // Progress lines go to stderr when the archive itself is going to
+    // stdout, so they don't end up interleaved into the tar stream.
+    let to_stdout = is_stdout_output(output);
+    run_collectors(ctx, collectors, &staging, limits, to_stdout).await?;
+
+    finalize_bundle(&staging, output, format, destination, "support bundle").await
+}
+
+/// `--dry-run`: runs every collector exactly as [`collect_bundle`] would,
+/// into a throwaway staging directory instead of one derived from
+/// `--output`, then prints the plan it produced -- every collector's
+/// outcome and every file it would have shipped, with its size -- and
+/// deletes the staging directory instead of archiving it, so an operator
+/// in a regulated environment can review what a dump would capture
+/// before actually capturing it.
+async fn plan_bundle(ctx: &Context, collectors: &[Box<dyn collectors::Collector>], limits: CollectionLimits) -> anyhow::Result<()> {
+    let staging = dry_run_staging_dir();
+    std::fs::create_dir_all(&staging)?;
+    let result = run_collectors(ctx, collectors, &staging, limits, false).await;
+    let outcome = result.map(|manifest| print_plan(&manifest));
+    std::fs::remove_dir_all(&staging)?;
+    outcome
+}
+
+/// File [`run_collectors`] writes its progress to as each collector
+/// finishes, so a `--resume` of an interrupted run can tell which
+/// collectors don't need to run again. Deleted once a run finishes
+/// cleanly, before `manifest.json` is built, so it's never itself shipped
+/// as a bundle file.
+const RESUME_STATE_FILE: &str = ".dump-progress.json";
+
+/// Runs every collector into `staging`, dropping files to stay under
+/// `--max-archive-size` and writing `manifest.json`, exactly as a real
+/// dump would -- shared by [`collect_bundle`] and `--dry-run`'s
+/// [`plan_bundle`], which only differ in what happens to `staging` once
+/// collection finishes. With `limits.resume`, collectors already recorded
+/// in [`RESUME_STATE_FILE`] from a previous, interrupted run into the same
+/// `staging` are skipped entirely rather than re-run.
+async fn run_collectors(
+    ctx: &Context,
+    collectors: &[Box<dyn collectors::Collector>],
+    staging: &std::path::Path,
+    limits: CollectionLimits,
+    progress_to_stderr: bool,
+) -> anyhow::Result<Manifest> {
+    let CollectionLimits { collector_timeout_seconds, max_archive_size, max_concurrent_collectors, resume } = limits;
+    let progress = |msg: String| {
+        if progress_to_stderr {
+            eprintln!("{msg}");
+        } else {
+            println!("{msg}");
+        }
+    };
+
+    let state_path = staging.join(RESUME_STATE_FILE);
+    let previous = if resume { read_resume_state(&state_path) } else { Vec::new() };
+    let (already_done, to_retry): (Vec<ManifestEntry>, Vec<ManifestEntry>) =
+        previous.into_iter().partition(|entry| is_done_outcome(&entry.outcome));
+    let done_names: std::collections::HashSet<String> = already_done.iter().map(|entry| entry.name.clone()).collect();
+    for entry in &already_done {
+        progress(format!("resume: {} already {} -- skipping", entry.name, entry.outcome));
+    }
+    for entry in &to_retry {
+        progress(format!("resume: {} previously {} -- retrying", entry.name, entry.outcome));
+    }
+
+    let state = std::sync::Mutex::new(already_done);
+    let timeout = Duration::from_secs(collector_timeout_seconds);
+    stream::iter(collectors.iter().filter(|collector| !done_names.contains(collector.name())))
+        .map(|collector| async {
+            let start = Instant::now();
+            let outcome = match tokio::time::timeout(timeout, collector.collect(ctx, staging)).await {
+                Ok(Ok(collectors::CollectorOutcome::Collected)) => {
+                    progress(format!("collected {}", collector.name()));
+                    "collected".to_string()
+                }
+                Ok(Ok(collectors::CollectorOutcome::Skipped(reason))) => {
+                    progress(format!("skipped {}: {reason}", collector.name()));
+                    format!("skipped: {reason}")
+                }
+                Ok(Err(e)) => {
+                    eprintln!("{} failed: {e:#}", collector.name());
+                    format!("failed: {e:#}")
+                }
+                Err(_) => {
+                    eprintln!("{} timed out after {}s", collector.name(), collector_timeout_seconds);
+                    "timed out".to_string()
+                }
+            };
+            let entry = ManifestEntry {
+                name: collector.name().to_string(),
+                outcome,
+                duration_ms: start.elapsed().as_millis(),
+            };
+            // Persisted immediately rather than batched at the end, so a
+            // `--resume` after this process is killed partway through
+            // still has every collector that finished before the kill.
+            let mut state = state.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+            state.push(entry);
+            write_resume_state(&state_path, &state).ok();
+        })
+        .buffer_unordered(max_concurrent_collectors.max(1))
+        .for_each(|()| async {})
+        .await;
+    let manifest = state.into_inner().unwrap_or_else(std::sync::PoisonError::into_inner);
+
+    // The run finished (as opposed to being interrupted), so there's
+    // nothing left to resume -- remove the state file before building
+    // `manifest.json` so it's never itself listed as a shipped file.
+    std::fs::remove_file(&state_path).ok();
+
+    let dropped_for_size = enforce_max_archive_size(staging, max_archive_size)?;
+    for entry in &dropped_for_size {
+        progress(format!(
+            "dropped {} ({} bytes) to stay under --max-archive-size",
+            entry.path, entry.size_bytes
+        ));
+    }
+    let collector_names: Vec<&'static str> = collectors.iter().map(|c| c.name()).collect();
+    let files = build_file_manifest(staging, &collector_names)?;
+    let manifest = Manifest { collectors: manifest, files, dropped_for_size };
+    std::fs::write(staging.join("manifest.json"), serde_json::to_string_pretty(&manifest)?)?;
+    Ok(manifest)
+}
+
+/// Whether a [`ManifestEntry::outcome`] counts as genuinely finished for
+/// `--resume` purposes. `"failed: ..."` and `"timed out"` aren't --
+/// retrying them is the whole point of resuming after e.g. a node reboot
+/// kills the process mid-collector, and treating them as done would mean
+/// a collector that failed on the interrupted run never gets another
+/// chance.
+fn is_done_outcome(outcome: &str) -> bool {
+    outcome == "collected" || outcome.starts_with("skipped: ")
+}
+
+/// Reads [`RESUME_STATE_FILE`] back for `--resume`, treating a missing or
+/// unreadable file as "nothing completed yet" rather than an error -- the
+/// first `--resume` of a staging directory that was never interrupted, or
+/// one left over from a version of this plugin that predates `--resume`,
+/// should just run every collector.
+fn read_resume_state(state_path: &std::path::Path) -> Vec<ManifestEntry> {
+    std::fs::read(state_path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+/// Overwrites [`RESUME_STATE_FILE`] with `entries`, the full set of
+/// collectors that have finished so far.
+fn write_resume_state(state_path: &std::path::Path, entries: &[ManifestEntry]) -> anyhow::Result<()> {
+    std::fs::write(state_path, serde_json::to_string(entries)?)?;
+    Ok(())
+}
+
+/// Prints the plan [`plan_bundle`] staged: every collector's outcome,
+/// every file it would have shipped with its size, and a running total,
+/// matching `manifest.json`'s own shape rather than inventing a second
+/// report format for the same data.
+fn print_plan(manifest: &Manifest) {
+    println!("dry run: nothing was written; this is what `dump` would collect");
+    for entry in &manifest.collectors {
+        println!("  {}: {} ({}ms)", entry.name, entry.outcome, entry.duration_ms);
+    }
+    let mut total_bytes = 0u64;
+    for file in &manifest.files {
+        println!("    {} ({} bytes)", file.path, file.size_bytes);
+        total_bytes += file.size_bytes;
+    }
+    println!("estimated bundle size: {total_bytes} bytes across {} files", manifest.files.len());
+}
+
+/// Directory `--dry-run` stages its throwaway collection run into --
+/// unlike [`staging_dir`], not derived from `--output`, since `--dry-run`
+/// doesn't require `--output` at all.
+fn dry_run_staging_dir() -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("kubectl-openebs-dump-dry-run-{}", std::process::id()));
+    dir
+}
+
+/// Deletes the largest remaining file under `staging` (skipping
+/// `manifest.json` itself) repeatedly until the gzip-compressed archive
+/// would fit under `max_size`, so a busy cluster's bundle stays small
+/// enough to attach to a ticket instead of failing outright. Returns what
+/// was dropped, largest first, for the caller to record/report. A no-op
+/// when `max_size` is `None`.
+fn enforce_max_archive_size(staging: &std::path::Path, max_size: Option<u64>) -> anyhow::Result<Vec<DroppedEntry>> {
+    let Some(max_size) = max_size else {
+        return Ok(Vec::new());
+    };
+
+    let mut dropped = Vec::new();
+    loop {
+        let archive_len = archive::tar_gz_bytes(staging)?.len() as u64;
+        if archive_len <= max_size {
+            return Ok(dropped);
+        }
+        let Some((path, size_bytes)) = largest_droppable_file(staging)? else {
+            anyhow::bail!(
+                "bundle is {archive_len} bytes compressed even with nothing left to drop, \
+                 over the --max-archive-size {max_size} byte cap"
+            );
+        };
+        std::fs::remove_file(&path)?;
+        dropped.push(DroppedEntry {
+            path: path.strip_prefix(staging).unwrap_or(&path).to_string_lossy().into_owned(),
+            size_bytes,
+        });
+    }
+}
+
+/// The largest file under `dir` (recursively), excluding `manifest.json`,
+/// along with its size in bytes.
+fn largest_droppable_file(dir: &std::path::Path) -> anyhow::Result<Option<(PathBuf, u64)>> {
+    let mut largest: Option<(PathBuf, u64)> = None;
+    for entry in walk_files(dir)? {
+        if entry.file_name().and_then(|n| n.to_str()) == Some("manifest.json") {
+            continue;
+        }
+        let size = entry.metadata()?.len();
+        if largest.as_ref().map(|(_, largest_size)| size > *largest_size).unwrap_or(true) {
+            largest = Some((entry, size));
+        }
+    }
+    Ok(largest)
+}
+
+/// Every regular file under `dir`, recursively.
+fn walk_files(dir: &std::path::Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            files.extend(walk_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Builds the `files` entries for `manifest.json`: every file under
+/// `staging` except `manifest.json` itself, with its SHA-256 and the
+/// collector (if any of `collector_names` matches the file's top-level
+/// directory) that produced it. Called after [`enforce_max_archive_size`]
+/// has already dropped what it's going to drop, so the manifest only
+/// ever lists files that actually ship in the bundle.
+fn build_file_manifest(staging: &std::path::Path, collector_names: &[&'static str]) -> anyhow::Result<Vec<ManifestFileEntry>> {
+    let mut files = Vec::new();
+    for path in walk_files(staging)? {
+        if path.file_name().and_then(|n| n.to_str()) == Some("manifest.json") {
+            continue;
+        }
+        let relative = path.strip_prefix(staging).unwrap_or(&path);
+        let top_dir = relative.components().next().and_then(|c| c.as_os_str().to_str());
+        let collector = collector_names.iter().copied().find(|name| top_dir == Some(*name));
+
+        let bytes = std::fs::read(&path)?;
+        let mut hasher = Sha256::new();
+        hasher.update(&bytes);
+        let metadata = std::fs::metadata(&path)?;
+
+        files.push(ManifestFileEntry {
+            path: relative.to_string_lossy().into_owned(),
+            collector,
+            sha256: format!("{:x}", hasher.finalize()),
+            size_bytes: bytes.len() as u64,
+            collected_at: metadata.modified()?.into(),
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Writes `manifest.json` for a `volume`/`node` bundle: the file
+/// checksums and whatever [`enforce_max_archive_size`] dropped, but no
+/// per-collector status, since those bundles collect directly rather
+/// than through named [`collectors::Collector`]s.
+fn write_file_manifest(staging: &std::path::Path, dropped_for_size: Vec<DroppedEntry>) -> anyhow::Result<()> {
+    let files = build_file_manifest(staging, &[])?;
+    let manifest = Manifest { collectors: Vec::new(), files, dropped_for_size };
+    std::fs::write(staging.join("manifest.json"), serde_json::to_string_pretty(&manifest)?)?;
+    Ok(())
+}
+
+/// Directory data is first staged into before being archived (or left in
+/// place, for [`OutputFormat::Directory`]).
+fn staging_dir(output: &std::path::Path, format: OutputFormat) -> anyhow::Result<PathBuf> {
+    Ok(match format {
+        OutputFormat::Directory => output.to_path_buf(),
+        OutputFormat::TarGz | OutputFormat::Zip => {
+            let mut dir = std::env::temp_dir();
+            dir.push(format!(
+                "kubectl-openebs-dump-{}",
+                output
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "bundle".into())
+            ));
+            dir
+        }
+    })
+}
+
@@ -0,0 +1,222 @@
+//! `kubectl openebs dump node`: collects everything relating to one
+//! storage node -- its engine node CRs, data-plane pod logs, and Events
+//! and conditions -- the node-scoped counterpart to [`super::volume`]'s
+//! volume-scoped bundle, for a misbehaving node rather than a broken
+//! volume.
+
+use std::path::Path;
+
+use k8s_openapi::api::core::v1::{Event, Node, Pod};
+use kube::api::{Api, DynamicObject, GroupVersionKind, LogParams};
+use kube::discovery::ApiResource;
+use serde::Serialize;
+
+use crate::node_facts;
+use crate::resources::{list_all, Context};
+use crate::upgrade::validations::DATA_PLANE_CONTAINERS;
+
+/// Per-node engine CRDs, keyed by the name a matching data-plane pod's
+/// container name contains (see [`DATA_PLANE_CONTAINERS`]). The CR's
+/// name is assumed to match the node's name, the convention both
+/// engines use for their node-scoped CRs.
+const NODE_CRDS: &[(&str, &str, &str, &str)] = &[
+    ("openebs-zfs-plugin", "zfs.csi.openebs.io", "v1", "ZFSNode"),
+    ("openebs-lvm-plugin", "local.openebs.io", "v1alpha1", "LVMNode"),
+];
+
+/// Collects a focused bundle for a single node into `dir`: the Node
+/// object and its doctor facts, any engine node CR that matches a
+/// data-plane container running on it, that pod's logs, and Events
+/// involving the node.
+pub async fn collect(
+    ctx: &Context,
+    node_name: &str,
+    dir: &Path,
+    max_log_bytes: Option<u64>,
+) -> anyhow::Result<()> {
+    let nodes: Api<Node> = Api::all(ctx.client.clone());
+    let node = nodes.get(node_name).await?;
+    write_json(dir, "node.json", &node)?;
+
+    let facts = node_facts::node_facts(&node);
+    write_json(dir, "node-facts.json", &facts)?;
+
+    let data_plane_pods = data_plane_pods_on_node(ctx, node_name).await?;
+    if data_plane_pods.is_empty() {
+        println!("no data-plane pods found on node {node_name}; skipping engine node CR and logs");
+    }
+    for pod in &data_plane_pods {
+        let pod_name = pod.metadata.name.clone().unwrap_or_default();
+        write_json(&dir.join("pods"), &format!("{pod_name}.json"), pod)?;
+        write_pod_logs(ctx, pod, &dir.join("logs"), max_log_bytes).await.ok();
+
+        let containers: Vec<&str> = pod
+            .spec
+            .as_ref()
+            .map(|spec| spec.containers.iter().map(|c| c.name.as_str()).collect())
+            .unwrap_or_default();
+        if let Some((_, group, version, kind)) =
+            NODE_CRDS.iter().find(|(container, ..)| containers.contains(container))
+        {
+            collect_node_cr(ctx, node_name, group, version, kind, dir).await.ok();
+        }
+    }
+
+    collect_events(ctx, node_name, dir).await?;
+    Ok(())
+}
+
+/// Finds the data-plane pods (see [`DATA_PLANE_CONTAINERS`]) running on
+/// `node_name`, i.e. the engines' `csi-node` and `io-engine` pods.
+async fn data_plane_pods_on_node(ctx: &Context, node_name: &str) -> anyhow::Result<Vec<Pod>> {
+    let pods: Api<Pod> = Api::all(ctx.client.clone());
+    Ok(list_all(&pods, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|pod| {
+            pod.spec
+                .as_ref()
+                .map(|spec| {
+                    spec.node_name.as_deref() == Some(node_name)
+                        && spec.containers.iter().any(|c| DATA_PLANE_CONTAINERS.contains(&c.name.as_str()))
+                })
+                .unwrap_or(false)
+        })
+        .collect())
+}
+
+async fn write_pod_logs(ctx: &Context, pod: &Pod, dir: &Path, max_log_bytes: Option<u64>) -> anyhow::Result<()> {
+    let namespace = pod.metadata.namespace.clone().unwrap_or_default();
+    let name = pod.metadata.name.clone().unwrap_or_default();
+    let containers: Vec<String> = pod
+        .spec
+        .as_ref()
+        .map(|spec| spec.containers.iter().map(|c| c.name.clone()).collect())
+        .unwrap_or_default();
+
+    std::fs::create_dir_all(dir)?;
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), &namespace);
+    for container in containers {
+        let params = LogParams { container: Some(container.clone()), ..Default::default() };
+        if let Ok(logs) = pods.logs(&name, &params).await {
+            let logs = truncate_log_tail(logs, max_log_bytes);
+            std::fs::write(dir.join(format!("{name}-{container}.log")), logs)?;
+        }
+    }
+
+    let restarts = restart_summary(pod);
+    if !restarts.is_empty() {
+        for restart in &restarts {
+            let params = LogParams { container: Some(restart.container.clone()), previous: true, ..Default::default() };
+            if let Ok(logs) = pods.logs(&name, &params).await {
+                let logs = truncate_log_tail(logs, max_log_bytes);
+                std::fs::write(dir.join(format!("{name}-{}-previous.log", restart.container)), logs)?;
+            }
+        }
+        std::fs::write(dir.join(format!("{name}-restarts.json")), serde_json::to_string_pretty(&restarts)?)?;
+    }
+    Ok(())
+}
+
+/// Per-container restart count and last termination details, for
+/// containers that have restarted at least once. Built straight from the
+/// Pod's own `status.containerStatuses` -- no extra API calls needed.
+/// Mirrors `dump/volume.rs`'s own `RestartInfo`.
+#[derive(Serialize)]
+struct RestartInfo {
+    container: String,
+    restart_count: i32,
+    last_exit_code: Option<i32>,
+    last_reason: Option<String>,
+    last_finished_at: Option<k8s_openapi::apimachinery::pkg::apis::meta::v1::Time>,
+}
+
+fn restart_summary(pod: &Pod) -> Vec<RestartInfo> {
+    pod.status
+        .as_ref()
+        .and_then(|s| s.container_statuses.as_ref())
+        .map(|statuses| {
+            statuses
+                .iter()
+                .filter(|s| s.restart_count > 0)
+                .map(|s| {
+                    let terminated = s.last_state.as_ref().and_then(|st| st.terminated.as_ref());
+                    RestartInfo {
+                        container: s.name.clone(),
+                        restart_count: s.restart_count,
+                        last_exit_code: terminated.map(|t| t.exit_code),
+                        last_reason: terminated.and_then(|t| t.reason.clone()),
+                        last_finished_at: terminated.and_then(|t| t.finished_at.clone()),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Keeps only the last `max_bytes` bytes of `logs` (rounded outward to a
+/// UTF-8 character boundary), prefixed with a marker noting how much was
+/// cut, so a truncated log is never mistaken for a complete one.
+fn truncate_log_tail(logs: String, max_bytes: Option<u64>) -> String {
+    let Some(max_bytes) = max_bytes.and_then(|b| usize::try_from(b).ok()) else {
+        return logs;
+    };
+    if logs.len() <= max_bytes {
+        return logs;
+    }
+    let mut start = logs.len() - max_bytes;
+    while !logs.is_char_boundary(start) {
+        start += 1;
+    }
+    format!(
+        "... truncated, kept last {} of {} bytes (--max-log-bytes-per-pod) ...\n{}",
+        logs.len() - start,
+        logs.len(),
+        &logs[start..]
+    )
+}
+
+async fn collect_node_cr(
+    ctx: &Context,
+    name: &str,
+    group: &str,
+    version: &str,
+    kind: &str,
+    dir: &Path,
+) -> anyhow::Result<()> {
+    let gvk = GroupVersionKind::gvk(group, version, kind);
+    let resource = ApiResource::from_gvk(&gvk);
+    let api: Api<DynamicObject> = Api::all_with(ctx.client.clone(), &resource);
+    let obj = api.get(name).await?;
+    write_json(dir, &format!("{kind}.json"), &obj)
+}
+
+/// Dumps every Event whose `involvedObject.name` is the node itself, the
+/// same per-object filter `kubectl openebs dump volume` uses for a PVC's
+/// related objects.
+async fn collect_events(ctx: &Context, node_name: &str, dir: &Path) -> anyhow::Result<()> {
+    let events: Api<Event> = Api::all(ctx.client.clone());
+    let matched: Vec<Event> = list_all(&events, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|event| event.involved_object.name.as_deref() == Some(node_name))
+        .collect();
+
+    if matched.is_empty() {
+        return Ok(());
+    }
+    let events_dir = dir.join("events");
+    std::fs::create_dir_all(&events_dir)?;
+    for event in &matched {
+        let namespace = event.metadata.namespace.clone().unwrap_or_default();
+        let name = event.metadata.name.clone().unwrap_or_default();
+        write_json(&events_dir, &format!("{namespace}-{name}.json"), event)?;
+    }
+    Ok(())
+}
+
+fn write_json<T: serde::Serialize>(dir: &Path, file_name: &str, value: &T) -> anyhow::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(dir.join(file_name), serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
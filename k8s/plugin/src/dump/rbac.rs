@@ -0,0 +1,97 @@
+//! `kubectl openebs dump check-rbac`: reports which permissions `dump
+//! system`/`dump incident`'s collectors need before anyone runs an actual
+//! dump and finds out one of them is missing a third of the way through
+//! a multi-GB collection. Each check is a `SelfSubjectAccessReview`
+//! (a dry-run "would this be allowed" query the apiserver itself
+//! evaluates, not a real read), so this never touches cluster state --
+//! unlike the collectors it mirrors, which skip gracefully on a 403 they
+//! hit mid-collection (see [`super::collectors::is_forbidden`]) rather
+//! than failing the whole bundle.
+
+use k8s_openapi::api::authorization::v1::{ResourceAttributes, SelfSubjectAccessReview, SelfSubjectAccessReviewSpec};
+use kube::api::{Api, PostParams};
+
+use crate::resources::Context;
+
+struct PermissionCheck {
+    verb: &'static str,
+    group: &'static str,
+    resource: &'static str,
+}
+
+/// One entry per resource a [`super::collectors::Collector`] reads (or,
+/// for `pods/log`, reads from) to build a `system`/`incident` bundle.
+const CHECKS: &[PermissionCheck] = &[
+    PermissionCheck { verb: "list", group: "", resource: "nodes" },
+    PermissionCheck { verb: "list", group: "", resource: "events" },
+    PermissionCheck { verb: "list", group: "", resource: "pods" },
+    PermissionCheck { verb: "get", group: "", resource: "pods/log" },
+    PermissionCheck { verb: "get", group: "apps", resource: "deployments" },
+    PermissionCheck { verb: "list", group: "", resource: "configmaps" },
+    PermissionCheck { verb: "list", group: "storage.k8s.io", resource: "storageclasses" },
+    PermissionCheck { verb: "list", group: "", resource: "persistentvolumes" },
+    PermissionCheck { verb: "list", group: "", resource: "persistentvolumeclaims" },
+    PermissionCheck { verb: "list", group: "openebs.io", resource: "blockdeviceclaims" },
+    PermissionCheck { verb: "list", group: "zfs.csi.openebs.io", resource: "zfsvolumes" },
+    PermissionCheck { verb: "list", group: "zfs.csi.openebs.io", resource: "zfssnapshots" },
+    PermissionCheck { verb: "list", group: "local.openebs.io", resource: "lvmvolumes" },
+    PermissionCheck { verb: "list", group: "local.openebs.io", resource: "lvmsnapshots" },
+];
+
+pub async fn run(ctx: &Context) -> anyhow::Result<()> {
+    let reviews: Api<SelfSubjectAccessReview> = Api::all(ctx.client.clone());
+
+    let mut missing = Vec::new();
+    for check in CHECKS {
+        let allowed = is_allowed(&reviews, ctx, check).await?;
+        let label = format!("{} {}", check.verb, qualified_resource(check));
+        if allowed {
+            println!("ok      {label}");
+        } else {
+            println!("missing {label}");
+            missing.push(label);
+        }
+    }
+
+    if missing.is_empty() {
+        println!("every permission `dump system`/`dump incident` needs is present");
+        Ok(())
+    } else {
+        anyhow::bail!(
+            "missing {} of {} permission(s) dump needs: {}",
+            missing.len(),
+            CHECKS.len(),
+            missing.join(", ")
+        );
+    }
+}
+
+async fn is_allowed(
+    reviews: &Api<SelfSubjectAccessReview>,
+    ctx: &Context,
+    check: &PermissionCheck,
+) -> anyhow::Result<bool> {
+    let review = SelfSubjectAccessReview {
+        spec: SelfSubjectAccessReviewSpec {
+            resource_attributes: Some(ResourceAttributes {
+                group: Some(check.group.to_string()),
+                resource: Some(check.resource.to_string()),
+                verb: Some(check.verb.to_string()),
+                namespace: Some(ctx.namespace.clone()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result = reviews.create(&PostParams::default(), &review).await?;
+    Ok(result.status.map(|status| status.allowed).unwrap_or(false))
+}
+
+fn qualified_resource(check: &PermissionCheck) -> String {
+    if check.group.is_empty() {
+        check.resource.to_string()
+    } else {
+        format!("{}.{}", check.resource, check.group)
+    }
+}
@@ -0,0 +1,325 @@
+//! `kubectl openebs dump volume`: follows the volume graph
+//! (PVC -> PV -> engine CR -> node -> pods) and collects just that
+//! volume's slice of cluster state -- the most common support request is
+//! "this one volume is broken", not "dump the whole cluster".
+
+use std::path::Path;
+
+use k8s_openapi::api::core::v1::{Event, Node, PersistentVolume, PersistentVolumeClaim, Pod};
+use kube::api::{Api, DynamicObject, GroupVersionKind, LogParams};
+use kube::discovery::ApiResource;
+use serde::Serialize;
+
+use crate::node_facts;
+use crate::resources::{list_all, Context};
+use crate::upgrade::validations::DATA_PLANE_CONTAINERS;
+
+/// Engine CRDs a PV's `spec.csi.driver` is matched against (by substring,
+/// since this tree has seen more than one driver name used for the same
+/// engine -- see `crate::upgrade::validations::OPENEBS_PROVISIONERS`),
+/// along with the group/version the CR is addressed under. The CR's name
+/// is assumed to match the PV's name, the convention both engines use.
+const ENGINE_CRDS: &[(&str, &str, &str, &str)] = &[
+    ("zfs", "zfs.csi.openebs.io", "v1", "ZFSVolume"),
+    ("lvm", "local.openebs.io", "v1alpha1", "LVMVolume"),
+    ("mayastor", "openebs.io", "v1beta2", "MayastorVolume"),
+];
+
+/// Collects a focused bundle for a single volume into `dir`, identified
+/// by either its PVC name (looked up in `ctx`'s namespace) or its bound
+/// PV's name (searched cluster-wide, covering the common case of a
+/// support ticket that only has the volume UUID, which OpenEBS uses as
+/// the PV name).
+pub async fn collect(ctx: &Context, id: &str, dir: &Path, max_log_bytes: Option<u64>) -> anyhow::Result<()> {
+    let pvc = resolve_pvc(ctx, id).await?;
+    let pvc_name = pvc.metadata.name.clone().unwrap_or_default();
+    let pvc_namespace = pvc.metadata.namespace.clone().unwrap_or_default();
+    write_json(dir, "pvc.json", &pvc)?;
+
+    let pv_name = pvc
+        .spec
+        .as_ref()
+        .and_then(|s| s.volume_name.clone())
+        .ok_or_else(|| anyhow::anyhow!("PVC {pvc_namespace}/{pvc_name} is not yet bound to a PV"))?;
+
+    let pvs: Api<PersistentVolume> = Api::all(ctx.client.clone());
+    let pv = pvs.get(&pv_name).await?;
+    write_json(dir, "pv.json", &pv)?;
+
+    let driver = pv
+        .spec
+        .as_ref()
+        .and_then(|s| s.csi.as_ref())
+        .map(|csi| csi.driver.as_str())
+        .unwrap_or("");
+    if let Some((engine, group, version, kind)) =
+        ENGINE_CRDS.iter().find(|(engine, ..)| driver.contains(engine))
+    {
+        collect_engine_cr(ctx, &pv_name, group, version, kind, dir).await.ok();
+        println!("matched engine {engine} from CSI driver {driver:?}");
+    } else {
+        println!("could not match a known engine to CSI driver {driver:?}; skipping engine CR");
+    }
+
+    let node_name = node_affinity_hostname(&pv);
+    if let Some(node_name) = &node_name {
+        let nodes: Api<Node> = Api::all(ctx.client.clone());
+        if let Ok(node) = nodes.get(node_name).await {
+            write_json(dir, "node.json", &node)?;
+            let facts = node_facts::node_facts(&node);
+            write_json(dir, "node-facts.json", &facts)?;
+        }
+    }
+
+    let pods = pods_mounting(ctx, &pvc_namespace, &pvc_name).await?;
+    if !pods.is_empty() {
+        let pods_dir = dir.join("pods");
+        let logs_dir = dir.join("logs");
+        std::fs::create_dir_all(&pods_dir)?;
+        for pod in &pods {
+            let name = pod.metadata.name.clone().unwrap_or_default();
+            write_json(&pods_dir, &format!("{name}.json"), pod)?;
+            write_pod_logs(ctx, pod, &logs_dir, max_log_bytes).await.ok();
+        }
+    }
+
+    if let Some(node_name) = &node_name {
+        let agent_pods = data_plane_pods_on_node(ctx, node_name).await?;
+        if !agent_pods.is_empty() {
+            let logs_dir = dir.join("node-agent-logs");
+            for pod in &agent_pods {
+                write_pod_logs(ctx, pod, &logs_dir, max_log_bytes).await.ok();
+            }
+        }
+    }
+
+    let pod_names: Vec<String> = pods.iter().filter_map(|p| p.metadata.name.clone()).collect();
+    let related_names = [&[pvc_name.clone(), pv_name.clone()][..], &pod_names[..]].concat();
+    collect_events(ctx, &related_names, dir).await?;
+
+    Ok(())
+}
+
+/// Writes each of `pod`'s containers' logs to `<dir>/<pod>-<container>.log`,
+/// skipping (rather than failing the whole collection over) a container
+/// that has no logs yet or has since been removed. `max_log_bytes`, if
+/// given, keeps only the tail of each container's log -- the most
+/// relevant part for diagnosing why a pod is currently unhealthy -- so
+/// one container stuck logging in a crash loop doesn't dominate the
+/// bundle's size.
+///
+/// A crash-looping container's *current* log is often empty or just
+/// started, so any container that has restarted also gets its `previous:
+/// true` log fetched to `<dir>/<pod>-<container>-previous.log`, plus a
+/// `<dir>/<pod>-restarts.json` summarizing each restarted container's
+/// count and last termination reason/exit code -- the two things a
+/// support engineer actually needs before digging into the previous log.
+async fn write_pod_logs(ctx: &Context, pod: &Pod, dir: &Path, max_log_bytes: Option<u64>) -> anyhow::Result<()> {
+    let namespace = pod.metadata.namespace.clone().unwrap_or_default();
+    let name = pod.metadata.name.clone().unwrap_or_default();
+    let containers: Vec<String> = pod
+        .spec
+        .as_ref()
+        .map(|spec| spec.containers.iter().map(|c| c.name.clone()).collect())
+        .unwrap_or_default();
+
+    std::fs::create_dir_all(dir)?;
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), &namespace);
+    for container in containers {
+        let params = LogParams { container: Some(container.clone()), ..Default::default() };
+        if let Ok(logs) = pods.logs(&name, &params).await {
+            let logs = truncate_log_tail(logs, max_log_bytes);
+            std::fs::write(dir.join(format!("{name}-{container}.log")), logs)?;
+        }
+    }
+
+    let restarts = restart_summary(pod);
+    if !restarts.is_empty() {
+        for restart in &restarts {
+            let params = LogParams { container: Some(restart.container.clone()), previous: true, ..Default::default() };
+            if let Ok(logs) = pods.logs(&name, &params).await {
+                let logs = truncate_log_tail(logs, max_log_bytes);
+                std::fs::write(dir.join(format!("{name}-{}-previous.log", restart.container)), logs)?;
+            }
+        }
+        std::fs::write(dir.join(format!("{name}-restarts.json")), serde_json::to_string_pretty(&restarts)?)?;
+    }
+    Ok(())
+}
+
+/// Per-container restart count and last termination details, for
+/// containers that have restarted at least once. Built straight from the
+/// Pod's own `status.containerStatuses` -- no extra API calls needed.
+#[derive(Serialize)]
+struct RestartInfo {
+    container: String,
+    restart_count: i32,
+    last_exit_code: Option<i32>,
+    last_reason: Option<String>,
+    last_finished_at: Option<k8s_openapi::apimachinery::pkg::apis::meta::v1::Time>,
+}
+
+fn restart_summary(pod: &Pod) -> Vec<RestartInfo> {
+    pod.status
+        .as_ref()
+        .and_then(|s| s.container_statuses.as_ref())
+        .map(|statuses| {
+            statuses
+                .iter()
+                .filter(|s| s.restart_count > 0)
+                .map(|s| {
+                    let terminated = s.last_state.as_ref().and_then(|st| st.terminated.as_ref());
+                    RestartInfo {
+                        container: s.name.clone(),
+                        restart_count: s.restart_count,
+                        last_exit_code: terminated.map(|t| t.exit_code),
+                        last_reason: terminated.and_then(|t| t.reason.clone()),
+                        last_finished_at: terminated.and_then(|t| t.finished_at.clone()),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Keeps only the last `max_bytes` bytes of `logs` (rounded outward to a
+/// UTF-8 character boundary), prefixed with a marker noting how much was
+/// cut, so a truncated log is never mistaken for a complete one.
+fn truncate_log_tail(logs: String, max_bytes: Option<u64>) -> String {
+    let Some(max_bytes) = max_bytes.and_then(|b| usize::try_from(b).ok()) else {
+        return logs;
+    };
+    if logs.len() <= max_bytes {
+        return logs;
+    }
+    let mut start = logs.len() - max_bytes;
+    while !logs.is_char_boundary(start) {
+        start += 1;
+    }
+    format!(
+        "... truncated, kept last {} of {} bytes (--max-log-bytes-per-pod) ...\n{}",
+        logs.len() - start,
+        logs.len(),
+        &logs[start..]
+    )
+}
+
+/// Finds the node agent/io-engine pod(s) (see
+/// `crate::upgrade::validations::DATA_PLANE_CONTAINERS`) running on
+/// `node_name`, the node hosting the volume -- the most relevant logs
+/// for diagnosing an engine-side problem, as opposed to the mounting
+/// pods' own application logs.
+async fn data_plane_pods_on_node(ctx: &Context, node_name: &str) -> anyhow::Result<Vec<Pod>> {
+    let pods: Api<Pod> = Api::all(ctx.client.clone());
+    Ok(list_all(&pods, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|pod| {
+            pod.spec
+                .as_ref()
+                .map(|spec| {
+                    spec.node_name.as_deref() == Some(node_name)
+                        && spec.containers.iter().any(|c| DATA_PLANE_CONTAINERS.contains(&c.name.as_str()))
+                })
+                .unwrap_or(false)
+        })
+        .collect())
+}
+
+async fn resolve_pvc(ctx: &Context, id: &str) -> anyhow::Result<PersistentVolumeClaim> {
+    let pvcs: Api<PersistentVolumeClaim> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    if let Ok(pvc) = pvcs.get(id).await {
+        return Ok(pvc);
+    }
+
+    let all_pvcs: Api<PersistentVolumeClaim> = Api::all(ctx.client.clone());
+    list_all(&all_pvcs, ctx.page_size)
+        .await?
+        .into_iter()
+        .find(|pvc| pvc.spec.as_ref().and_then(|s| s.volume_name.as_deref()) == Some(id))
+        .ok_or_else(|| anyhow::anyhow!("no PVC or volume found for {id:?}"))
+}
+
+async fn collect_engine_cr(
+    ctx: &Context,
+    name: &str,
+    group: &str,
+    version: &str,
+    kind: &str,
+    dir: &Path,
+) -> anyhow::Result<()> {
+    let gvk = GroupVersionKind::gvk(group, version, kind);
+    let resource = ApiResource::from_gvk(&gvk);
+    let api: Api<DynamicObject> = Api::all_with(ctx.client.clone(), &resource);
+    let obj = api.get(name).await?;
+    write_json(dir, &format!("{kind}.json"), &obj)
+}
+
+fn node_affinity_hostname(pv: &PersistentVolume) -> Option<String> {
+    pv.spec
+        .as_ref()?
+        .node_affinity
+        .as_ref()?
+        .required
+        .as_ref()?
+        .node_selector_terms
+        .first()?
+        .match_expressions
+        .as_ref()?
+        .iter()
+        .find(|e| e.key == "kubernetes.io/hostname")?
+        .values
+        .as_ref()?
+        .first()
+        .cloned()
+}
+
+async fn pods_mounting(ctx: &Context, namespace: &str, pvc_name: &str) -> anyhow::Result<Vec<Pod>> {
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), namespace);
+    Ok(list_all(&pods, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|pod| {
+            pod.spec
+                .as_ref()
+                .map(|spec| {
+                    spec.volumes.iter().flatten().any(|v| {
+                        v.persistent_volume_claim
+                            .as_ref()
+                            .is_some_and(|pvc| pvc.claim_name == pvc_name)
+                    })
+                })
+                .unwrap_or(false)
+        })
+        .collect())
+}
+
+/// Dumps every Event whose `involvedObject.name` is one of `names`
+/// (the PVC, PV and mounting pods), rather than the cluster-wide sweep
+/// `kubectl openebs dump system`'s `EventsCollector` does.
+async fn collect_events(ctx: &Context, names: &[String], dir: &Path) -> anyhow::Result<()> {
+    let events: Api<Event> = Api::all(ctx.client.clone());
+    let matched: Vec<Event> = list_all(&events, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|event| names.iter().any(|n| n == &event.involved_object.name.clone().unwrap_or_default()))
+        .collect();
+
+    if matched.is_empty() {
+        return Ok(());
+    }
+    let events_dir = dir.join("events");
+    std::fs::create_dir_all(&events_dir)?;
+    for event in &matched {
+        let namespace = event.metadata.namespace.clone().unwrap_or_default();
+        let name = event.metadata.name.clone().unwrap_or_default();
+        write_json(&events_dir, &format!("{namespace}-{name}.json"), event)?;
+    }
+    Ok(())
+}
+
+fn write_json<T: serde::Serialize>(dir: &Path, file_name: &str, value: &T) -> anyhow::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(dir.join(file_name), serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
@@ -0,0 +1,195 @@
+//! `kubectl openebs export` / `import`: render the live OpenEBS inventory
+//! (engine custom resources and StorageClasses) as a tree of normalized
+//! YAML files suitable for committing to Git, and compare such a tree
+//! back against a live cluster.
+
+use std::path::{Path, PathBuf};
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::{
+    api::{Api, DynamicObject, GroupVersionKind},
+    discovery::ApiResource,
+};
+use serde_json::Value;
+
+use crate::resources::{list_all, Context};
+
+/// Engine CRD kinds and the StorageClasses that bind to them; this spans
+/// cStor, LVM-LocalPV and ZFS-LocalPV so that `export state` captures the
+/// full inventory regardless of which engines are installed.
+const RESOURCES: &[(&str, &str, &str)] = &[
+    ("storage.k8s.io", "v1", "StorageClass"),
+    ("cstor.openebs.io", "v1", "CStorPoolCluster"),
+    ("cstor.openebs.io", "v1", "CStorVolumeConfig"),
+    ("zfs.openebs.io", "v1", "ZFSVolume"),
+    ("local.openebs.io", "v1alpha1", "LVMVolume"),
+];
+
+#[derive(clap::Subcommand)]
+pub enum ExportCommand {
+    /// Write one YAML file per engine CR and StorageClass under `--output`.
+    State {
+        /// Directory to write the exported tree to.
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+}
+
+#[derive(clap::Args)]
+pub struct ImportArgs {
+    /// Directory previously produced by `export state`.
+    dir: PathBuf,
+
+    /// Report drift between `dir` and the live cluster instead of applying it.
+    #[arg(long, default_value_t = false)]
+    diff: bool,
+}
+
+pub async fn run(ctx: &Context, cmd: ExportCommand) -> anyhow::Result<()> {
+    match cmd {
+        ExportCommand::State { output } => {
+            let count = export_state(ctx, &output).await?;
+            println!("exported {count} object(s) to {}", output.display());
+            Ok(())
+        }
+    }
+}
+
+async fn export_state(ctx: &Context, dir: &Path) -> anyhow::Result<usize> {
+    let mut count = 0;
+    for (group, version, kind) in RESOURCES {
+        let gvk = GroupVersionKind::gvk(group, version, kind);
+        let resource = ApiResource::from_gvk(&gvk);
+        let api: Api<DynamicObject> = Api::all_with(ctx.client.clone(), &resource);
+        for obj in list_all(&api, ctx.page_size).await? {
+            let normalized = normalize(obj);
+            write_object(dir, &normalized)?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Strips status, managedFields and other server-assigned metadata noise
+/// from `obj` so the exported tree is stable across reconciles.
+fn normalize(mut obj: DynamicObject) -> DynamicObject {
+    if let Some(map) = obj.data.as_object_mut() {
+        map.remove("status");
+    }
+    obj.metadata.managed_fields = None;
+    obj.metadata.resource_version = None;
+    obj.metadata.uid = None;
+    obj.metadata.generation = None;
+    obj.metadata.creation_timestamp = None::<Time>;
+    if let Some(annotations) = obj.metadata.annotations.as_mut() {
+        annotations.remove("kubectl.kubernetes.io/last-applied-configuration");
+    }
+    obj
+}
+
+/// Returns the on-disk location an object is written to:
+/// `dir/<group>/<kind>/[<namespace>/]<name>.yaml`.
+fn object_path(dir: &Path, obj: &DynamicObject) -> PathBuf {
+    let gvk = obj.types.as_ref().and_then(|t| t.api_version.split_once('/'));
+    let group = gvk.map(|(g, _)| g.replace('.', "_")).unwrap_or_else(|| "core".into());
+    let kind = obj
+        .types
+        .as_ref()
+        .map(|t| t.kind.clone())
+        .unwrap_or_default();
+
+    let mut path = dir.join(group).join(kind);
+    if let Some(ns) = &obj.metadata.namespace {
+        path = path.join(ns);
+    }
+    path.join(format!(
+        "{}.yaml",
+        obj.metadata.name.clone().unwrap_or_default()
+    ))
+}
+
+fn write_object(dir: &Path, obj: &DynamicObject) -> anyhow::Result<()> {
+    let path = object_path(dir, obj);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let value: Value = serde_json::to_value(obj)?;
+    std::fs::write(path, serde_yaml::to_string(&value)?)?;
+    Ok(())
+}
+
+/// A single object whose Git-tracked definition no longer matches what is
+/// running on the cluster.
+struct Drift {
+    path: PathBuf,
+    reason: &'static str, // "added", "removed" or "changed"
+}
+
+pub async fn import(ctx: &Context, args: ImportArgs) -> anyhow::Result<()> {
+    if !args.diff {
+        anyhow::bail!("import currently only supports --diff; apply is not yet implemented");
+    }
+
+    let mut drifts = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+
+    for (group, version, kind) in RESOURCES {
+        let gvk = GroupVersionKind::gvk(group, version, kind);
+        let resource = ApiResource::from_gvk(&gvk);
+        let api: Api<DynamicObject> = Api::all_with(ctx.client.clone(), &resource);
+        let live = list_all(&api, ctx.page_size).await?;
+
+        for obj in live {
+            let normalized = normalize(obj);
+            let path = object_path(&args.dir, &normalized);
+            seen.insert(path.clone());
+
+            match std::fs::read_to_string(&path) {
+                Ok(contents) => {
+                    let wanted: Value = serde_yaml::from_str(&contents)?;
+                    let got: Value = serde_json::to_value(&normalized)?;
+                    if wanted != got {
+                        drifts.push(Drift { path, reason: "changed" });
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    drifts.push(Drift { path, reason: "added" });
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    for entry in walk_yaml_files(&args.dir)? {
+        if !seen.contains(&entry) {
+            drifts.push(Drift { path: entry, reason: "removed" });
+        }
+    }
+
+    if drifts.is_empty() {
+        println!("no drift: cluster matches {}", args.dir.display());
+        return Ok(());
+    }
+    for drift in &drifts {
+        println!("{}\t{}", drift.reason, drift.path.display());
+    }
+    anyhow::bail!(
+        "{} object(s) differ between {} and the live cluster",
+        drifts.len(),
+        args.dir.display()
+    )
+}
+
+fn walk_yaml_files(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut out = Vec::new();
+    if !dir.exists() {
+        return Ok(out);
+    }
+    for entry in walkdir::WalkDir::new(dir) {
+        let entry = entry?;
+        if entry.file_type().is_file() && entry.path().extension().is_some_and(|e| e == "yaml") {
+            out.push(entry.path().to_path_buf());
+        }
+    }
+    Ok(out)
+}
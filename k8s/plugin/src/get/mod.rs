@@ -0,0 +1,180 @@
+//! `kubectl openebs get`: inspect live Mayastor volumes and pools.
+//!
+//! Both subcommands prefer the Mayastor REST API (richer output, includes
+//! live rebuild/rebalance state) but fall back to reading the
+//! DiskPool/MayastorVolume custom resources directly when the api-rest
+//! pod is down -- exactly when operators most need visibility. Rows
+//! sourced from a CR are marked stale, since they reflect etcd-backed
+//! control-plane state rather than the live data plane.
+//!
+//! `--output openmetrics` emits a one-shot OpenMetrics text exposition
+//! instead of the table, so a cron job can drop it straight into a
+//! node_exporter textfile collector directory without this plugin having
+//! to run anything like a long-lived `/metrics` server itself. It's only
+//! available off the REST API path: the CR fallback doesn't carry enough
+//! numeric fields (size, capacity, used) for a meaningful exposition.
+
+use kube::api::{Api, DynamicObject, GroupVersionKind};
+use kube::discovery::ApiResource;
+
+use crate::rest::{PoolInfo, RestClient, VolumeInfo};
+use crate::resources::{list_all, Context};
+
+#[derive(clap::Subcommand)]
+pub enum GetCommand {
+    /// List Mayastor volumes.
+    Volumes(GetArgs),
+    /// List Mayastor pools.
+    Pools(GetArgs),
+}
+
+#[derive(clap::Args)]
+pub struct GetArgs {
+    /// Output format.
+    #[arg(long, value_enum, default_value = "text")]
+    pub output: OutputFormat,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Openmetrics,
+}
+
+pub async fn run(ctx: &Context, cmd: GetCommand) -> anyhow::Result<()> {
+    match cmd {
+        GetCommand::Volumes(args) => get_volumes(ctx, args.output).await,
+        GetCommand::Pools(args) => get_pools(ctx, args.output).await,
+    }
+}
+
+async fn get_volumes(ctx: &Context, output: OutputFormat) -> anyhow::Result<()> {
+    match RestClient::for_context(ctx).list_volumes().await {
+        Ok(volumes) => {
+            match output {
+                OutputFormat::Text => {
+                    println!("{:<38} {:>12} {:<10} {:>8}  SOURCE", "UUID", "SIZE", "STATE", "REPLICAS");
+                    for v in volumes {
+                        println!(
+                            "{:<38} {:>12} {:<10} {:>8}  rest",
+                            v.uuid, v.size, v.state, v.num_replicas
+                        );
+                    }
+                }
+                OutputFormat::Openmetrics => print_volumes_openmetrics(&volumes),
+            }
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("warning: api-rest unreachable ({e}), falling back to MayastorVolume CRs");
+            get_volumes_from_cr(ctx).await
+        }
+    }
+}
+
+fn print_volumes_openmetrics(volumes: &[VolumeInfo]) {
+    println!("# TYPE openebs_volume_size_bytes gauge");
+    for v in volumes {
+        println!("openebs_volume_size_bytes{{uuid=\"{}\"}} {}", v.uuid, v.size);
+    }
+    println!("# TYPE openebs_volume_replicas gauge");
+    for v in volumes {
+        println!("openebs_volume_replicas{{uuid=\"{}\"}} {}", v.uuid, v.num_replicas);
+    }
+    println!("# TYPE openebs_volume_state info");
+    for v in volumes {
+        println!(
+            "openebs_volume_state{{uuid=\"{}\",state=\"{}\"}} 1",
+            v.uuid, v.state
+        );
+    }
+    println!("# EOF");
+}
+
+async fn get_volumes_from_cr(ctx: &Context) -> anyhow::Result<()> {
+    let gvk = GroupVersionKind::gvk("openebs.io", "v1beta2", "MayastorVolume");
+    let resource = ApiResource::from_gvk(&gvk);
+    let api: Api<DynamicObject> =
+        Api::namespaced_with(ctx.client.clone(), &ctx.namespace, &resource);
+
+    println!("{:<38} {:<10}  SOURCE", "UUID", "STATE");
+    for obj in list_all(&api, ctx.page_size).await? {
+        println!(
+            "{:<38} {:<10}  cr (stale/control-plane-only)",
+            obj.metadata.name.clone().unwrap_or_default(),
+            status_field(&obj, "state"),
+        );
+    }
+    Ok(())
+}
+
+async fn get_pools(ctx: &Context, output: OutputFormat) -> anyhow::Result<()> {
+    match RestClient::for_context(ctx).list_pools().await {
+        Ok(pools) => {
+            match output {
+                OutputFormat::Text => {
+                    println!("{:<20} {:<20} {:>12} {:>12}  SOURCE", "NAME", "NODE", "CAPACITY", "USED");
+                    for p in pools {
+                        println!(
+                            "{:<20} {:<20} {:>12} {:>12}  rest",
+                            p.name, p.node, p.capacity, p.used
+                        );
+                    }
+                }
+                OutputFormat::Openmetrics => print_pools_openmetrics(&pools),
+            }
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("warning: api-rest unreachable ({e}), falling back to DiskPool CRs");
+            get_pools_from_cr(ctx).await
+        }
+    }
+}
+
+fn print_pools_openmetrics(pools: &[PoolInfo]) {
+    println!("# TYPE openebs_pool_capacity_bytes gauge");
+    for p in pools {
+        println!(
+            "openebs_pool_capacity_bytes{{name=\"{}\",node=\"{}\"}} {}",
+            p.name, p.node, p.capacity
+        );
+    }
+    println!("# TYPE openebs_pool_used_bytes gauge");
+    for p in pools {
+        println!(
+            "openebs_pool_used_bytes{{name=\"{}\",node=\"{}\"}} {}",
+            p.name, p.node, p.used
+        );
+    }
+    println!("# EOF");
+}
+
+async fn get_pools_from_cr(ctx: &Context) -> anyhow::Result<()> {
+    let gvk = GroupVersionKind::gvk("openebs.io", "v1beta2", "DiskPool");
+    let resource = ApiResource::from_gvk(&gvk);
+    let api: Api<DynamicObject> =
+        Api::namespaced_with(ctx.client.clone(), &ctx.namespace, &resource);
+
+    println!("{:<20} {:<10}  SOURCE", "NAME", "STATE");
+    for obj in list_all(&api, ctx.page_size).await? {
+        println!(
+            "{:<20} {:<10}  cr (stale/control-plane-only)",
+            obj.metadata.name.clone().unwrap_or_default(),
+            status_field(&obj, "state"),
+        );
+    }
+    Ok(())
+}
+
+/// Best-effort read of `status.<field>` off a CR, since the exact status
+/// shape isn't validated client-side the way the REST API's is.
+fn status_field(obj: &DynamicObject, field: &str) -> String {
+    obj.data
+        .get("status")
+        .and_then(|s| s.get(field))
+        .and_then(|s| s.as_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
@@ -0,0 +1,57 @@
+//! `kubectl openebs localpv-zfs backup`: lists `ZFSBackup` CRs (created by
+//! velero-plugin-for-openebs) with the progress, remote target and error
+//! columns operators need to track a transfer, instead of forcing a
+//! `kubectl get zfsbackups -o yaml`.
+
+use kube::api::{Api, DynamicObject, GroupVersionKind};
+use kube::discovery::ApiResource;
+
+use crate::resources::{list_all, Context};
+
+use super::{spec_field, status_field};
+
+/// Group/version/kind for ZFS LocalPV's `ZFSBackup` CRD. Mirrors
+/// [`crate::dump::collectors::EngineCrCollector::zfs`]'s own group/version.
+const GROUP: &str = "zfs.csi.openebs.io";
+const VERSION: &str = "v1";
+const KIND: &str = "ZFSBackup";
+
+#[derive(clap::Subcommand)]
+pub enum ZfsBackupCommand {
+    /// List ZFS LocalPV backups.
+    List,
+}
+
+pub async fn run(ctx: &Context, cmd: ZfsBackupCommand) -> anyhow::Result<()> {
+    match cmd {
+        ZfsBackupCommand::List => list(ctx).await,
+    }
+}
+
+async fn list(ctx: &Context) -> anyhow::Result<()> {
+    let gvk = GroupVersionKind::gvk(GROUP, VERSION, KIND);
+    let resource = ApiResource::from_gvk(&gvk);
+    let api: Api<DynamicObject> = Api::all_with(ctx.client.clone(), &resource);
+    let backups = list_all(&api, ctx.page_size).await?;
+
+    print_default(&backups);
+    Ok(())
+}
+
+fn print_default(backups: &[DynamicObject]) {
+    println!(
+        "{:<38} {:<38} {:>8} {:<20} {:<10}  ERROR",
+        "NAME", "VOLUME", "PROGRESS", "TARGET", "STATE"
+    );
+    for obj in backups {
+        println!(
+            "{:<38} {:<38} {:>8} {:<20} {:<10}  {}",
+            obj.metadata.name.clone().unwrap_or_default(),
+            spec_field(obj, "volume"),
+            status_field(obj, "backupProgress"),
+            spec_field(obj, "backupDest"),
+            status_field(obj, "status"),
+            status_field(obj, "error"),
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! `kubectl openebs localpv-zfs`: inspect ZFS LocalPV (`zfs.csi.openebs.io`)
+//! volumes directly from their `ZFSVolume` CRs. `get` (see [`crate::get`])
+//! only covers Mayastor's own REST API/CRs, so a cluster running ZFS
+//! LocalPV instead has nothing but `kubectl get zfsvolumes -o yaml` to
+//! fall back on without this.
+
+mod backup;
+mod pool;
+mod restore;
+mod volume;
+
+use kube::api::DynamicObject;
+
+use crate::resources::Context;
+
+#[derive(clap::Subcommand)]
+pub enum LocalpvZfsCommand {
+    /// Inspect ZFS LocalPV volumes.
+    #[command(subcommand)]
+    Volume(volume::ZfsVolumeCommand),
+    /// Report per-node ZFS pool capacity and health.
+    #[command(subcommand)]
+    Pool(pool::ZfsPoolCommand),
+    /// Inspect ZFS LocalPV backups (the velero-plugin-for-openebs
+    /// `ZFSBackup` CRs).
+    #[command(subcommand)]
+    Backup(backup::ZfsBackupCommand),
+    /// Inspect ZFS LocalPV restores (the velero-plugin-for-openebs
+    /// `ZFSRestore` CRs).
+    #[command(subcommand)]
+    Restore(restore::ZfsRestoreCommand),
+}
+
+pub async fn run(ctx: &Context, cmd: LocalpvZfsCommand) -> anyhow::Result<()> {
+    match cmd {
+        LocalpvZfsCommand::Volume(cmd) => volume::run(ctx, cmd).await,
+        LocalpvZfsCommand::Pool(cmd) => pool::run(ctx, cmd).await,
+        LocalpvZfsCommand::Backup(cmd) => backup::run(ctx, cmd).await,
+        LocalpvZfsCommand::Restore(cmd) => restore::run(ctx, cmd).await,
+    }
+}
+
+/// Best-effort read of `spec.<field>` off a CR, since the exact schema
+/// isn't validated client-side. Shared by `volume`/`backup`/`restore`;
+/// mirrors `get`'s own `status_field`.
+fn spec_field(obj: &DynamicObject, field: &str) -> String {
+    obj.data
+        .get("spec")
+        .and_then(|s| s.get(field))
+        .and_then(|s| s.as_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Best-effort read of `status.<field>` off a CR.
+fn status_field(obj: &DynamicObject, field: &str) -> String {
+    obj.data
+        .get("status")
+        .and_then(|s| s.get(field))
+        .and_then(|s| s.as_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
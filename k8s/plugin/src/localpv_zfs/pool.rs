@@ -0,0 +1,119 @@
+//! `kubectl openebs localpv-zfs pool`: aggregates per-node ZFS pool
+//! capacity and health from `ZFSNode` CRs, with cluster-wide totals, so
+//! capacity planning doesn't require SSHing into nodes to run
+//! `zpool list`. Mirrors `dump node`'s own `NODE_CRDS` entry for ZFS's
+//! per-node CR.
+
+use kube::api::{Api, DynamicObject, GroupVersionKind};
+use kube::discovery::ApiResource;
+
+use crate::resources::{list_all_with_selector, Context};
+
+const GROUP: &str = "zfs.csi.openebs.io";
+const VERSION: &str = "v1";
+const KIND: &str = "ZFSNode";
+
+#[derive(clap::Subcommand)]
+pub enum ZfsPoolCommand {
+    /// List ZFS pools across all nodes, with cluster-wide totals.
+    List(ZfsPoolListArgs),
+}
+
+#[derive(clap::Args)]
+pub struct ZfsPoolListArgs {
+    /// Only pools on this node.
+    #[arg(long)]
+    node: Option<String>,
+
+    /// Only pools with this name.
+    #[arg(long = "pool")]
+    pool: Option<String>,
+
+    /// Kubernetes label selector to narrow the listed `ZFSNode` CRs,
+    /// e.g. `kubernetes.io/hostname=node-a`.
+    #[arg(short = 'l', long)]
+    selector: Option<String>,
+}
+
+pub async fn run(ctx: &Context, cmd: ZfsPoolCommand) -> anyhow::Result<()> {
+    match cmd {
+        ZfsPoolCommand::List(args) => list(ctx, args).await,
+    }
+}
+
+struct PoolRow {
+    node: String,
+    pool: String,
+    free_bytes: u64,
+    used_bytes: u64,
+    health: String,
+}
+
+async fn list(ctx: &Context, args: ZfsPoolListArgs) -> anyhow::Result<()> {
+    let gvk = GroupVersionKind::gvk(GROUP, VERSION, KIND);
+    let resource = ApiResource::from_gvk(&gvk);
+    let api: Api<DynamicObject> = Api::all_with(ctx.client.clone(), &resource);
+    let nodes = list_all_with_selector(&api, ctx.page_size, args.selector.as_deref()).await?;
+    let rows: Vec<PoolRow> = nodes
+        .iter()
+        .flat_map(pool_rows)
+        .filter(|row| args.node.as_deref().is_none_or(|n| row.node == n))
+        .filter(|row| args.pool.as_deref().is_none_or(|p| row.pool == p))
+        .collect();
+
+    println!(
+        "{:<20} {:<20} {:>14} {:>14}  HEALTH",
+        "NODE", "POOL", "FREE", "USED"
+    );
+    let (mut total_free, mut total_used) = (0u64, 0u64);
+    for row in &rows {
+        println!(
+            "{:<20} {:<20} {:>14} {:>14}  {}",
+            row.node, row.pool, row.free_bytes, row.used_bytes, row.health,
+        );
+        total_free += row.free_bytes;
+        total_used += row.used_bytes;
+    }
+    println!(
+        "{:<20} {:<20} {:>14} {:>14}",
+        "TOTAL", "", total_free, total_used
+    );
+    Ok(())
+}
+
+/// Reads `spec.pools[]` off a `ZFSNode` CR, since the exact schema isn't
+/// validated client-side.
+fn pool_rows(obj: &DynamicObject) -> Vec<PoolRow> {
+    let node = obj.metadata.name.clone().unwrap_or_default();
+    obj.data
+        .get("spec")
+        .and_then(|spec| spec.get("pools"))
+        .and_then(|pools| pools.as_array())
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|pool| PoolRow {
+            node: node.clone(),
+            pool: pool
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            free_bytes: pool.get("free").and_then(parse_bytes).unwrap_or(0),
+            used_bytes: pool.get("used").and_then(parse_bytes).unwrap_or(0),
+            health: pool
+                .get("status")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+        })
+        .collect()
+}
+
+/// The CR reports pool sizes as either a JSON number or a numeric
+/// string, depending on the engine version; accept either.
+fn parse_bytes(value: &serde_json::Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
@@ -0,0 +1,224 @@
+//! `kubectl openebs localpv-zfs volume`: lists `ZFSVolume` CRs with the
+//! columns operators actually need (capacity, usage, pool, node), instead
+//! of forcing a `kubectl get zfsvolumes -o yaml` to get at the same
+//! fields. Also cross-references those CRs against PVs and PVCs to find
+//! orphans on either side (see `orphans`).
+
+use std::collections::HashSet;
+
+use k8s_openapi::api::core::v1::{PersistentVolume, PersistentVolumeClaim};
+use kube::api::{Api, DeleteParams, DynamicObject, GroupVersionKind};
+use kube::discovery::ApiResource;
+
+use crate::resources::{list_all, list_all_with_selector, Context};
+
+use super::{spec_field, status_field};
+
+/// Group/version/kind for ZFS LocalPV's `ZFSVolume` CRD. Mirrors
+/// [`crate::dump::collectors::EngineCrCollector::zfs`]'s own group/version.
+/// `GROUP` also doubles as the ZFS LocalPV CSI driver name, which is how
+/// `orphans` tells a PV it's looking at apart from other CSI drivers'.
+const GROUP: &str = "zfs.csi.openebs.io";
+const VERSION: &str = "v1";
+const KIND: &str = "ZFSVolume";
+
+#[derive(clap::Subcommand)]
+pub enum ZfsVolumeCommand {
+    /// List ZFS LocalPV volumes.
+    List(ZfsVolumeListArgs),
+    /// Cross-reference ZFSVolume CRs against PVs and PVCs, reporting
+    /// ZFSVolumes with neither a PV nor a still-Pending PVC behind them
+    /// (leaked datasets) and ZFS PVs whose ZFSVolume is missing.
+    Orphans(ZfsOrphansArgs),
+}
+
+#[derive(clap::Args)]
+pub struct ZfsOrphansArgs {
+    /// Delete the confirmed orphans (leaked ZFSVolume CRs and dangling
+    /// PVs) instead of only reporting them.
+    #[arg(long)]
+    delete: bool,
+}
+
+#[derive(clap::Args)]
+pub struct ZfsVolumeListArgs {
+    /// `wide` adds pool, compression and node columns to the default
+    /// capacity/used/state summary.
+    #[arg(short = 'o', long, value_enum, default_value = "default")]
+    output: OutputFormat,
+
+    /// Only volumes owned by this node.
+    #[arg(long)]
+    node: Option<String>,
+
+    /// Only volumes in this pool.
+    #[arg(long = "pool")]
+    pool: Option<String>,
+
+    /// Only volumes provisioned from this StorageClass.
+    #[arg(long = "storage-class")]
+    storage_class: Option<String>,
+
+    /// Kubernetes label selector to narrow the listed `ZFSVolume` CRs,
+    /// e.g. `app=postgres`.
+    #[arg(short = 'l', long)]
+    selector: Option<String>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Default)]
+pub enum OutputFormat {
+    #[default]
+    Default,
+    Wide,
+}
+
+pub async fn run(ctx: &Context, cmd: ZfsVolumeCommand) -> anyhow::Result<()> {
+    match cmd {
+        ZfsVolumeCommand::List(args) => list(ctx, args).await,
+        ZfsVolumeCommand::Orphans(args) => orphans(ctx, args).await,
+    }
+}
+
+async fn list(ctx: &Context, args: ZfsVolumeListArgs) -> anyhow::Result<()> {
+    let gvk = GroupVersionKind::gvk(GROUP, VERSION, KIND);
+    let resource = ApiResource::from_gvk(&gvk);
+    let api: Api<DynamicObject> = Api::all_with(ctx.client.clone(), &resource);
+    let volumes = list_all_with_selector(&api, ctx.page_size, args.selector.as_deref()).await?;
+    let volumes: Vec<DynamicObject> = volumes
+        .into_iter()
+        .filter(|obj| matches(obj, args.node.as_deref(), args.pool.as_deref(), args.storage_class.as_deref()))
+        .collect();
+
+    match args.output {
+        OutputFormat::Default => print_default(&volumes),
+        OutputFormat::Wide => print_wide(&volumes),
+    }
+    Ok(())
+}
+
+/// Whether `obj` passes the `--node`/`--pool`/`--storage-class` filters,
+/// each of which is skipped when not given.
+fn matches(obj: &DynamicObject, node: Option<&str>, pool: Option<&str>, storage_class: Option<&str>) -> bool {
+    node.is_none_or(|n| spec_field(obj, "ownerNodeID") == n)
+        && pool.is_none_or(|p| spec_field(obj, "poolName") == p)
+        && storage_class.is_none_or(|sc| spec_field(obj, "storageClass") == sc)
+}
+
+fn print_default(volumes: &[DynamicObject]) {
+    println!("{:<38} {:>12} {:>12}  STATE", "NAME", "CAPACITY", "USED");
+    for obj in volumes {
+        println!(
+            "{:<38} {:>12} {:>12}  {}",
+            obj.metadata.name.clone().unwrap_or_default(),
+            spec_field(obj, "capacity"),
+            status_field(obj, "used"),
+            status_field(obj, "state"),
+        );
+    }
+}
+
+fn print_wide(volumes: &[DynamicObject]) {
+    println!(
+        "{:<38} {:>12} {:>12} {:<20} {:<12} {:<20}  STATE",
+        "NAME", "CAPACITY", "USED", "POOL", "COMPRESSION", "NODE"
+    );
+    for obj in volumes {
+        println!(
+            "{:<38} {:>12} {:>12} {:<20} {:<12} {:<20}  {}",
+            obj.metadata.name.clone().unwrap_or_default(),
+            spec_field(obj, "capacity"),
+            status_field(obj, "used"),
+            spec_field(obj, "poolName"),
+            spec_field(obj, "compression"),
+            spec_field(obj, "ownerNodeID"),
+            status_field(obj, "state"),
+        );
+    }
+}
+
+async fn orphans(ctx: &Context, args: ZfsOrphansArgs) -> anyhow::Result<()> {
+    let gvk = GroupVersionKind::gvk(GROUP, VERSION, KIND);
+    let resource = ApiResource::from_gvk(&gvk);
+    let zv_api: Api<DynamicObject> = Api::all_with(ctx.client.clone(), &resource);
+    let volume_names: HashSet<String> = list_all(&zv_api, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter_map(|obj| obj.metadata.name)
+        .collect();
+
+    let pv_api: Api<PersistentVolume> = Api::all(ctx.client.clone());
+    let zfs_pvs: Vec<PersistentVolume> = list_all(&pv_api, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|pv| csi_volume_handle(pv).is_some())
+        .collect();
+    let pv_handles: HashSet<String> = zfs_pvs.iter().filter_map(csi_volume_handle).collect();
+
+    // Dynamic provisioning creates the ZFSVolume CR before the PV that
+    // will reference it, so a ZFSVolume with no matching PV yet isn't
+    // necessarily leaked -- it may just be mid-provisioning for a PVC
+    // that's still Pending. Kubernetes names a dynamically provisioned
+    // volume `pvc-<pvc-uid>` regardless of CSI driver, so a PVC whose
+    // would-be volume name matches the ZFSVolume rules it out as a false
+    // positive.
+    let pvc_api: Api<PersistentVolumeClaim> = Api::all(ctx.client.clone());
+    let pending_pvc_volume_names: HashSet<String> = list_all(&pvc_api, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|pvc| pvc.status.as_ref().and_then(|s| s.phase.as_deref()) == Some("Pending"))
+        .filter_map(|pvc| pvc.metadata.uid)
+        .map(|uid| format!("pvc-{uid}"))
+        .collect();
+
+    let mut leaked_datasets: Vec<&String> = volume_names
+        .iter()
+        .filter(|name| !pv_handles.contains(*name) && !pending_pvc_volume_names.contains(*name))
+        .collect();
+    leaked_datasets.sort();
+
+    let mut dangling_pvs: Vec<&PersistentVolume> = zfs_pvs
+        .iter()
+        .filter(|pv| csi_volume_handle(pv).is_some_and(|handle| !volume_names.contains(&handle)))
+        .collect();
+    dangling_pvs.sort_by_key(|pv| pv.metadata.name.clone().unwrap_or_default());
+
+    if leaked_datasets.is_empty() && dangling_pvs.is_empty() {
+        println!("no orphaned ZFSVolumes or PVs found");
+        return Ok(());
+    }
+
+    if !leaked_datasets.is_empty() {
+        println!("ZFSVolumes with no matching PV (leaked datasets):");
+        for name in &leaked_datasets {
+            println!("  {name}");
+        }
+    }
+    if !dangling_pvs.is_empty() {
+        println!("PVs with no matching ZFSVolume (dangling):");
+        for pv in &dangling_pvs {
+            println!("  {}", pv.metadata.name.clone().unwrap_or_default());
+        }
+    }
+
+    if !args.delete {
+        return Ok(());
+    }
+
+    for name in &leaked_datasets {
+        zv_api.delete(name, &DeleteParams::default()).await?;
+        println!("deleted ZFSVolume {name}");
+    }
+    for pv in &dangling_pvs {
+        let name = pv.metadata.name.clone().unwrap_or_default();
+        pv_api.delete(&name, &DeleteParams::default()).await?;
+        println!("deleted PV {name}");
+    }
+    Ok(())
+}
+
+/// The ZFS LocalPV CSI volume handle `pv` refers to, or `None` if `pv`
+/// wasn't provisioned by this driver.
+fn csi_volume_handle(pv: &PersistentVolume) -> Option<String> {
+    let csi = pv.spec.as_ref()?.csi.as_ref()?;
+    (csi.driver == GROUP).then(|| csi.volume_handle.clone())
+}
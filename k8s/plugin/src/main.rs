@@ -0,0 +1,175 @@
+//! `kubectl-openebs` is a kubectl plugin for operating OpenEBS: inspecting
+//! volumes and pools, running preflight checks ahead of an upgrade,
+//! driving chart upgrades and collecting support bundles.
+
+mod apply;
+mod archive;
+mod audit;
+mod bulk;
+mod constants;
+mod doctor;
+mod dump;
+mod export;
+mod get;
+mod localpv_zfs;
+mod mayastor;
+mod node_facts;
+mod ops;
+mod provenance;
+mod report;
+mod resources;
+mod rest;
+mod restart;
+mod setup;
+mod smoke_test;
+mod snapshot;
+mod suggest;
+mod updates;
+mod upgrade;
+mod verify;
+mod version;
+mod webhook;
+
+use clap::Parser;
+
+/// Top-level CLI, invoked by kubectl as `kubectl openebs <command>`.
+#[derive(Parser)]
+#[command(name = "kubectl-openebs", bin_name = "kubectl openebs")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Path to the kubeconfig file to use.
+    #[arg(long, global = true)]
+    kubeconfig: Option<String>,
+
+    /// Namespace OpenEBS components are installed into.
+    #[arg(short = 'n', long, global = true, default_value = "openebs")]
+    namespace: String,
+
+    /// Page size for Kubernetes list calls and REST volume listings;
+    /// lower it for large clusters behind a slow apiserver, raise it on
+    /// fast clusters to cut round-trips.
+    #[arg(long, global = true)]
+    page_size: Option<u32>,
+
+    /// Append a line for every Mayastor REST API call (method, path,
+    /// status, duration) this plugin makes to the given file, to debug
+    /// interop issues between this plugin and the control-plane's REST
+    /// API version without a packet capture.
+    #[arg(long, global = true)]
+    debug_http: Option<std::path::PathBuf>,
+
+    /// Also include response bodies in `--debug-http`'s log, with
+    /// likely-sensitive fields (matched by key substring, e.g. `token`,
+    /// `secret`, `password`) redacted. Has no effect without
+    /// `--debug-http`.
+    #[arg(long, global = true)]
+    debug_http_bodies: bool,
+}
+
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Reconcile a small declarative manifest of desired cluster state
+    /// (currently: node cordon state) idempotently, for batch workflows.
+    Apply(apply::ApplyArgs),
+    /// Export the OpenEBS inventory for GitOps reconciliation.
+    #[command(subcommand)]
+    Export(export::ExportCommand),
+    /// Compare a Git-tracked export tree against the live cluster.
+    Import(export::ImportArgs),
+    /// Safely move an OpenEBS installation backwards, recovering from a
+    /// bad upgrade. Distinct from `upgrade apply --skip-upgrade-path-validation`.
+    Downgrade(upgrade::downgrade::DowngradeArgs),
+    /// Print per-node OS/kernel facts and engine compatibility verdicts.
+    Doctor(doctor::DoctorArgs),
+    /// Inspect live Mayastor volumes and pools.
+    #[command(subcommand)]
+    Get(get::GetCommand),
+    /// Inspect ZFS LocalPV volumes.
+    #[command(subcommand)]
+    LocalpvZfs(localpv_zfs::LocalpvZfsCommand),
+    /// Stream live Mayastor volume/pool state transitions.
+    #[command(subcommand)]
+    Mayastor(mayastor::MayastorCommand),
+    /// Reconnect to and stream progress for a detached long-running
+    /// operation Job.
+    #[command(subcommand)]
+    Ops(ops::OpsCommand),
+    /// Run preflight validations and upgrade the OpenEBS installation.
+    #[command(subcommand)]
+    Upgrade(Box<upgrade::UpgradeCommand>),
+    /// Collect a support bundle for offline troubleshooting.
+    #[command(subcommand)]
+    Dump(dump::DumpCommand),
+    /// Rolling-restart control-plane components in a safe order.
+    #[command(subcommand)]
+    Restart(restart::RestartCommand),
+    /// Guided first-boot walkthrough for a cluster without OpenEBS
+    /// installed yet.
+    Setup(setup::SetupArgs),
+    /// Snapshot-consistent operations across the PVCs of a multi-PVC
+    /// application.
+    #[command(subcommand)]
+    Snapshot(snapshot::SnapshotCommand),
+    /// Provision, write/read, snapshot/restore, and tear down a test PVC
+    /// per engine, as a final automated gate after install/upgrade.
+    SmokeTest(smoke_test::SmokeTestArgs),
+    /// Display the provenance annotations on a resource this plugin created.
+    #[command(subcommand)]
+    Provenance(provenance::ProvenanceCommand),
+    /// Inspect cluster state and suggest the plugin commands to run next.
+    Suggest(suggest::SuggestArgs),
+    /// Check replica read-path consistency for a volume.
+    #[command(subcommand)]
+    Verify(verify::VerifyCommand),
+    /// Run or configure the OpenEBS validating-webhook server.
+    #[command(subcommand)]
+    Webhook(webhook::WebhookCommand),
+    /// Print the plugin's own version, or validate an upgrade path.
+    Version(version::VersionArgs),
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    // `webhook generate-manifests` and `version` are meant to work
+    // offline, so only commands that actually touch the cluster pay for
+    // the kubeconfig resolution.
+    match cli.command {
+        Command::Webhook(cmd) => return webhook::run(cmd).await,
+        Command::Version(args) => return version::run(args).await,
+        _ => {}
+    }
+
+    let ctx = resources::Context::new(
+        cli.kubeconfig,
+        cli.namespace,
+        cli.page_size,
+        cli.debug_http,
+        cli.debug_http_bodies,
+    )
+    .await?;
+    match cli.command {
+        Command::Apply(args) => apply::run(&ctx, args).await,
+        Command::Export(cmd) => export::run(&ctx, cmd).await,
+        Command::Import(args) => export::import(&ctx, args).await,
+        Command::Downgrade(args) => upgrade::downgrade::run(&ctx, args).await,
+        Command::Doctor(args) => doctor::run(&ctx, args).await,
+        Command::Get(cmd) => get::run(&ctx, cmd).await,
+        Command::LocalpvZfs(cmd) => localpv_zfs::run(&ctx, cmd).await,
+        Command::Mayastor(cmd) => mayastor::run(&ctx, cmd).await,
+        Command::Ops(cmd) => ops::run(&ctx, cmd).await,
+        Command::Upgrade(cmd) => upgrade::run(&ctx, *cmd).await,
+        Command::Dump(cmd) => dump::run(&ctx, cmd).await,
+        Command::Restart(cmd) => restart::run(&ctx, cmd).await,
+        Command::Setup(args) => setup::run(&ctx, args).await,
+        Command::Snapshot(cmd) => snapshot::run(&ctx, cmd).await,
+        Command::SmokeTest(args) => smoke_test::run(&ctx, args).await,
+        Command::Provenance(cmd) => provenance::run(&ctx, cmd).await,
+        Command::Suggest(args) => suggest::run(&ctx, args).await,
+        Command::Verify(cmd) => verify::run(&ctx, cmd).await,
+        Command::Webhook(_) | Command::Version(_) => unreachable!("handled above"),
+    }
+}
@@ -0,0 +1,115 @@
+//! `kubectl openebs mayastor events --follow`: streams volume/pool state
+//! transitions for alerting scripts. This plugin doesn't carry a NATS
+//! client to subscribe to the control-plane's event bus directly, so this
+//! polls the same `api-rest` [`RestClient`] that `get` already uses and
+//! emits a transition whenever a volume's or pool's `state` changes
+//! between polls -- a strictly weaker but dependency-free stand-in.
+//! Nexus state isn't modeled by [`RestClient`] yet, so only volumes and
+//! pools are covered; add a nexus listing there first if that's needed.
+//!
+//! `kubectl openebs mayastor placement-policy get|set`: see
+//! [`placement_policy`]'s module doc for what this does and, more
+//! importantly, what it honestly can't.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::resources::Context;
+use crate::rest::RestClient;
+
+mod placement_policy;
+pub use placement_policy::PlacementPolicyCommand;
+
+#[derive(clap::Subcommand)]
+pub enum MayastorCommand {
+    /// Stream volume/pool state transitions.
+    Events(EventsArgs),
+    /// View or nudge a volume's replica spread across failure domains.
+    #[command(subcommand)]
+    PlacementPolicy(PlacementPolicyCommand),
+}
+
+#[derive(clap::Args)]
+pub struct EventsArgs {
+    /// Keep polling and streaming new transitions instead of exiting
+    /// after the first poll.
+    #[arg(long)]
+    pub follow: bool,
+
+    /// How often to poll the REST API while following, in seconds.
+    #[arg(long, default_value_t = 5)]
+    pub interval_seconds: u64,
+
+    /// Emit one JSON object per line instead of plain text, for piping
+    /// into alerting scripts.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+struct Transition<'a> {
+    kind: &'a str,
+    name: &'a str,
+    from: &'a str,
+    to: &'a str,
+}
+
+pub async fn run(ctx: &Context, cmd: MayastorCommand) -> anyhow::Result<()> {
+    match cmd {
+        MayastorCommand::Events(args) => events(ctx, &args).await,
+        MayastorCommand::PlacementPolicy(cmd) => placement_policy::run(ctx, cmd).await,
+    }
+}
+
+async fn events(ctx: &Context, args: &EventsArgs) -> anyhow::Result<()> {
+    let rest = RestClient::for_context(ctx);
+    let mut volume_states: HashMap<String, String> = HashMap::new();
+    let mut known_pools: HashMap<String, ()> = HashMap::new();
+    let mut first_poll = true;
+
+    loop {
+        for v in rest.list_volumes().await? {
+            if let Some(prev) = volume_states.insert(v.uuid.clone(), v.state.clone()) {
+                if prev != v.state {
+                    print_transition(args, "volume", &v.uuid, &prev, &v.state);
+                }
+            }
+        }
+
+        // `PoolInfo` has no `state` field, only capacity/used, so the
+        // nearest available transition is the pool appearing in or
+        // dropping out of the listing entirely.
+        let mut seen_pools = HashMap::new();
+        for p in rest.list_pools().await? {
+            if !first_poll && !known_pools.contains_key(&p.name) {
+                print_transition(args, "pool", &p.name, "absent", "present");
+            }
+            seen_pools.insert(p.name.clone(), ());
+        }
+        if !first_poll {
+            for name in known_pools.keys() {
+                if !seen_pools.contains_key(name) {
+                    print_transition(args, "pool", name, "present", "absent");
+                }
+            }
+        }
+        known_pools = seen_pools;
+        first_poll = false;
+
+        if !args.follow {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_secs(args.interval_seconds)).await;
+    }
+}
+
+fn print_transition(args: &EventsArgs, kind: &str, name: &str, from: &str, to: &str) {
+    if args.json {
+        let transition = Transition { kind, name, from, to };
+        println!("{}", serde_json::to_string(&transition).unwrap_or_default());
+    } else {
+        println!("{kind}/{name} {from} -> {to}");
+    }
+}
@@ -0,0 +1,242 @@
+//! `kubectl openebs mayastor placement-policy get|set --volume <v> --spread
+//! zone`: reports how a volume's replicas are currently spread across
+//! node failure domains, and for `set`, nudges the control plane toward
+//! a better spread.
+//!
+//! Being honest about what `set` actually does: [`RestClient`] has no
+//! endpoint for a topology-spread or affinity *policy* object, because
+//! the real Mayastor REST API doesn't have one either -- replica
+//! placement is the control plane's own scheduler's job, and the only
+//! lever exposed over REST is the target replica count
+//! ([`RestClient::set_replica_count`]). So `set --spread zone` computes
+//! how many distinct zones the volume's storage nodes span, and if the
+//! replicas currently in use don't already cover that many zones, raises
+//! the replica count by one and waits for the new replica to come
+//! online -- the same "ask for one more, let the scheduler place it"
+//! lever a human operator has today, just automated and polled instead
+//! of eyeballed. It is not a persisted policy: there's nothing for `get`
+//! to read back other than the replicas' current observed placement, and
+//! nothing stops a future rebuild from landing back in an already-used
+//! zone.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use k8s_openapi::api::core::v1::Node;
+use kube::api::Api;
+use serde::Serialize;
+
+use crate::resources::{list_all, Context};
+use crate::rest::{ReplicaTopology, RestClient, VolumeDetail};
+
+/// Standard Kubernetes node label for its failure-domain zone. Mirrors
+/// `crate::node_facts`'s approach of reading well-known/NFD labels
+/// rather than inventing a plugin-specific one.
+const ZONE_LABEL: &str = "topology.kubernetes.io/zone";
+
+#[derive(clap::Subcommand)]
+pub enum PlacementPolicyCommand {
+    /// Show each replica's node, pool and zone, and whether the volume's
+    /// replicas already span distinct zones.
+    Get(PlacementPolicyArgs),
+    /// Raise the replica count by one, if needed, so the control plane
+    /// gets a chance to place a replica in a zone none of the current
+    /// replicas use yet, then wait for it to come online.
+    Set(SetPlacementPolicyArgs),
+}
+
+#[derive(clap::Args)]
+pub struct PlacementPolicyArgs {
+    /// Volume UUID.
+    #[arg(long)]
+    pub volume: String,
+
+    /// Emit JSON instead of a plain-text table.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(clap::Args)]
+pub struct SetPlacementPolicyArgs {
+    #[command(flatten)]
+    pub base: PlacementPolicyArgs,
+
+    /// Failure domain to spread replicas across. `zone` is the only
+    /// domain this plugin can resolve today (from nodes' standard
+    /// `topology.kubernetes.io/zone` label); add a region/rack variant
+    /// here if that's ever needed.
+    #[arg(long, value_enum, default_value_t = SpreadDomain::Zone)]
+    pub spread: SpreadDomain,
+
+    /// How long to wait for the new replica to come online before
+    /// giving up, in seconds.
+    #[arg(long, default_value_t = 300)]
+    pub timeout_seconds: u64,
+
+    /// How often to poll while waiting, in seconds.
+    #[arg(long, default_value_t = 5)]
+    pub interval_seconds: u64,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum SpreadDomain {
+    Zone,
+}
+
+#[derive(Serialize)]
+struct ReplicaPlacement {
+    replica: String,
+    node: String,
+    pool: String,
+    zone: String,
+    state: String,
+}
+
+pub async fn run(ctx: &Context, cmd: PlacementPolicyCommand) -> anyhow::Result<()> {
+    match cmd {
+        PlacementPolicyCommand::Get(args) => get(ctx, &args).await,
+        PlacementPolicyCommand::Set(args) => set(ctx, &args).await,
+    }
+}
+
+async fn get(ctx: &Context, args: &PlacementPolicyArgs) -> anyhow::Result<()> {
+    let rest = RestClient::for_context(ctx);
+    let zones = node_zones(ctx).await?;
+    let detail = rest.get_volume(&args.volume).await?;
+    print_placement(args, &detail, &zones);
+    Ok(())
+}
+
+async fn set(ctx: &Context, args: &SetPlacementPolicyArgs) -> anyhow::Result<()> {
+    let SpreadDomain::Zone = args.spread;
+    let rest = RestClient::for_context(ctx);
+    let zones = node_zones(ctx).await?;
+
+    let detail = rest.get_volume(&args.base.volume).await?;
+    let current_replicas = detail.state.replica_topology.len() as u32;
+    let covered_zones = distinct_zones(&detail, &zones);
+    let eligible_zones = eligible_storage_zones(&rest, &zones).await?;
+
+    if covered_zones.len() >= eligible_zones.len() || eligible_zones.is_empty() {
+        println!(
+            "volume {} already spans {} of {} available zone(s); nothing to do",
+            args.base.volume,
+            covered_zones.len(),
+            eligible_zones.len()
+        );
+        print_placement(&args.base, &detail, &zones);
+        return Ok(());
+    }
+
+    let target_replicas = current_replicas + 1;
+    println!(
+        "volume {} spans {} of {} available zone(s); raising replica count {current_replicas} -> \
+         {target_replicas} so the control plane can place one in an uncovered zone",
+        args.base.volume,
+        covered_zones.len(),
+        eligible_zones.len()
+    );
+    rest.set_replica_count(&args.base.volume, target_replicas).await?;
+
+    let deadline = std::time::Instant::now() + Duration::from_secs(args.timeout_seconds);
+    loop {
+        let detail = rest.get_volume(&args.base.volume).await?;
+        let online = detail
+            .state
+            .replica_topology
+            .values()
+            .filter(|r| r.state == "Online")
+            .count() as u32;
+        if detail.state.replica_topology.len() as u32 >= target_replicas && online >= target_replicas {
+            println!("new replica online");
+            print_placement(&args.base, &detail, &zones);
+            return Ok(());
+        }
+        if std::time::Instant::now() >= deadline {
+            anyhow::bail!(
+                "timed out after {}s waiting for volume {} to reach {target_replicas} online replicas",
+                args.timeout_seconds,
+                args.base.volume
+            );
+        }
+        tokio::time::sleep(Duration::from_secs(args.interval_seconds)).await;
+    }
+}
+
+/// Builds the current volume's replica placements and prints them,
+/// either as a plain-text table or, with `--json`, as a JSON array.
+fn print_placement(args: &PlacementPolicyArgs, detail: &VolumeDetail, zones: &BTreeMap<String, String>) {
+    let placements = placements(detail, zones);
+    if args.json {
+        println!("{}", serde_json::to_string(&placements).unwrap_or_default());
+        return;
+    }
+    for p in &placements {
+        println!("{} node={} pool={} zone={} state={}", p.replica, p.node, p.pool, p.zone, p.state);
+    }
+    let zone_count = distinct_zones(detail, zones).len();
+    println!("{} replica(s) across {zone_count} distinct zone(s)", placements.len());
+}
+
+fn placements(detail: &VolumeDetail, zones: &BTreeMap<String, String>) -> Vec<ReplicaPlacement> {
+    detail
+        .state
+        .replica_topology
+        .iter()
+        .map(|(id, topology)| ReplicaPlacement {
+            replica: id.clone(),
+            node: topology.node.clone().unwrap_or_else(|| "unknown".to_string()),
+            pool: topology.pool.clone().unwrap_or_else(|| "unknown".to_string()),
+            zone: replica_zone(topology, zones),
+            state: topology.state.clone(),
+        })
+        .collect()
+}
+
+fn distinct_zones(detail: &VolumeDetail, zones: &BTreeMap<String, String>) -> BTreeSet<String> {
+    detail
+        .state
+        .replica_topology
+        .values()
+        .map(|r| replica_zone(r, zones))
+        .filter(|z| z != "unknown")
+        .collect()
+}
+
+fn replica_zone(topology: &ReplicaTopology, zones: &BTreeMap<String, String>) -> String {
+    topology
+        .node
+        .as_deref()
+        .and_then(|node| zones.get(node))
+        .cloned()
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Zones spanned by nodes that currently host at least one Mayastor
+/// pool -- the nodes a new replica could actually land on, as opposed to
+/// every zone in the cluster (which may include nodes with no storage
+/// at all).
+async fn eligible_storage_zones(rest: &RestClient, zones: &BTreeMap<String, String>) -> anyhow::Result<BTreeSet<String>> {
+    Ok(rest
+        .list_pools()
+        .await?
+        .into_iter()
+        .filter_map(|pool| zones.get(&pool.node).cloned())
+        .collect())
+}
+
+/// Maps node name to its `topology.kubernetes.io/zone` label value,
+/// skipping nodes that don't have one (e.g. a single-zone cluster, or
+/// one not labeled by its cloud provider).
+async fn node_zones(ctx: &Context) -> anyhow::Result<BTreeMap<String, String>> {
+    let nodes: Api<Node> = Api::all(ctx.client.clone());
+    Ok(list_all(&nodes, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter_map(|node| {
+            let name = node.metadata.name.clone()?;
+            let zone = node.metadata.labels.as_ref()?.get(ZONE_LABEL)?.clone();
+            Some((name, zone))
+        })
+        .collect())
+}
@@ -0,0 +1,123 @@
+//! Per-node OS/kernel facts relevant to storage-engine compatibility,
+//! shared between `kubectl openebs doctor` and the `nodes` dump
+//! collector (see [`crate::dump::collectors::NodeCollector`]) so both
+//! read the same facts the same way instead of drifting apart.
+//!
+//! Kernel version, OS image and container runtime come straight off the
+//! Node object's own status, which every node reports unconditionally.
+//! cgroup mode, SELinux enforcement and specific kernel module
+//! availability aren't part of the Node API at all; rather than exec
+//! into nodes (this plugin has no such path), they're read from
+//! node-feature-discovery labels a cluster may optionally have applied,
+//! and left absent here instead of guessed at.
+
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::core::v1::Node;
+use serde::Serialize;
+
+/// node-feature-discovery label reporting the node's cgroup driver mode
+/// (`"unified"` for cgroup v2, `"legacy"`/`"hybrid"` for v1).
+const NFD_CGROUP_MODE_LABEL: &str = "feature.node.kubernetes.io/cgroup-mode";
+
+/// NFD label reporting whether SELinux is enforcing on the node.
+const NFD_SELINUX_LABEL: &str = "feature.node.kubernetes.io/selinux-enforcing";
+
+/// NFD label prefix for per-module availability, e.g.
+/// `feature.node.kubernetes.io/kernel-module.zfs=true`.
+const NFD_KERNEL_MODULE_LABEL_PREFIX: &str = "feature.node.kubernetes.io/kernel-module.";
+
+/// OS/kernel facts for a single node, plus whatever node-feature-discovery
+/// labels happen to be present.
+#[derive(Serialize)]
+pub struct NodeFacts {
+    pub name: String,
+    pub kernel_version: String,
+    pub os_image: String,
+    pub container_runtime_version: String,
+    pub architecture: String,
+    /// `None` when the cluster doesn't run node-feature-discovery, or
+    /// hasn't labeled this node yet.
+    pub cgroup_mode: Option<String>,
+    pub selinux_enforcing: Option<bool>,
+    /// Module name (without the NFD label prefix) to whether NFD reports
+    /// it loaded/available. A module with no entry here simply hasn't
+    /// been probed by NFD, not confirmed absent.
+    pub kernel_modules: BTreeMap<String, bool>,
+}
+
+/// Derives [`NodeFacts`] from a single `Node` object.
+pub fn node_facts(node: &Node) -> NodeFacts {
+    let info = node.status.as_ref().and_then(|s| s.node_info.as_ref());
+    let labels = node.metadata.labels.as_ref();
+
+    NodeFacts {
+        name: node.metadata.name.clone().unwrap_or_default(),
+        kernel_version: info.map(|i| i.kernel_version.clone()).unwrap_or_default(),
+        os_image: info.map(|i| i.os_image.clone()).unwrap_or_default(),
+        container_runtime_version: info
+            .map(|i| i.container_runtime_version.clone())
+            .unwrap_or_default(),
+        architecture: info.map(|i| i.architecture.clone()).unwrap_or_default(),
+        cgroup_mode: labels.and_then(|l| l.get(NFD_CGROUP_MODE_LABEL)).cloned(),
+        selinux_enforcing: labels
+            .and_then(|l| l.get(NFD_SELINUX_LABEL))
+            .and_then(|v| v.parse::<bool>().ok()),
+        kernel_modules: labels
+            .map(|l| {
+                l.iter()
+                    .filter_map(|(k, v)| {
+                        k.strip_prefix(NFD_KERNEL_MODULE_LABEL_PREFIX)
+                            .map(|module| (module.to_string(), v == "true"))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+/// Kernel modules each engine needs to actually attach/mount volumes.
+const ENGINE_REQUIRED_MODULES: &[(&str, &[&str])] = &[
+    ("zfs", &["zfs"]),
+    ("lvm", &["dm_mod", "dm_snapshot", "dm_thin_pool"]),
+    ("mayastor", &["nvme_tcp", "nvme_fabrics"]),
+];
+
+/// Whether a node meets an engine's kernel module requirements,
+/// according to whatever node-feature-discovery has reported.
+pub enum Verdict {
+    Compatible,
+    /// NFD positively reported at least one required module as absent.
+    Incompatible(Vec<String>),
+    /// NFD hasn't reported on at least one required module at all --
+    /// not a failure, since plenty of clusters don't run NFD.
+    Unknown(Vec<String>),
+}
+
+/// Checks `facts` against `engine`'s required kernel modules, returning
+/// [`Verdict::Incompatible`] only when NFD has positively reported a
+/// requirement as unmet; an absent label is [`Verdict::Unknown`] rather
+/// than treated as a pass or a failure.
+pub fn engine_compatibility(facts: &NodeFacts, engine: &str) -> Verdict {
+    let Some((_, modules)) = ENGINE_REQUIRED_MODULES.iter().find(|(name, _)| *name == engine) else {
+        return Verdict::Unknown(vec![format!("no known module requirements for engine {engine:?}")]);
+    };
+
+    let mut missing = Vec::new();
+    let mut unknown = Vec::new();
+    for module in *modules {
+        match facts.kernel_modules.get(*module) {
+            Some(true) => {}
+            Some(false) => missing.push((*module).to_string()),
+            None => unknown.push((*module).to_string()),
+        }
+    }
+
+    if !missing.is_empty() {
+        Verdict::Incompatible(missing)
+    } else if !unknown.is_empty() {
+        Verdict::Unknown(unknown)
+    } else {
+        Verdict::Compatible
+    }
+}
@@ -0,0 +1,75 @@
+//! `kubectl openebs ops attach <id>`: streams progress for a long-running
+//! operation that runs as an in-cluster Job, by tailing its pod's logs.
+//! `upgrade apply` is the only command in this plugin that currently runs
+//! as a detached Job -- it returns as soon as the Job is created, already
+//! the effect a `--detach` flag would have -- so attaching to the Job
+//! name it prints reconnects to that same run instead of leaving the
+//! operator with nothing until `upgrade status` has a final answer.
+//! `migrate`/`rebalance`/`decommission` aren't implemented as commands in
+//! this plugin yet, so there's nothing else to attach to; this reuses the
+//! same `openebs.io/upgrade-job` labeling so those commands can share it
+//! once they exist, rather than inventing a separate Job-labeling scheme
+//! now for operations that don't exist.
+
+use futures::{AsyncBufReadExt, StreamExt};
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, ListParams, LogParams};
+
+use crate::resources::Context;
+use crate::upgrade::status::UPGRADE_JOB_LABEL;
+
+#[derive(clap::Subcommand)]
+pub enum OpsCommand {
+    /// Stream an in-cluster operation Job's pod logs until it finishes.
+    Attach {
+        /// Job name, as printed when the operation was started (e.g. by
+        /// `upgrade apply`).
+        id: String,
+    },
+}
+
+pub async fn run(ctx: &Context, cmd: OpsCommand) -> anyhow::Result<()> {
+    match cmd {
+        OpsCommand::Attach { id } => attach(ctx, &id).await,
+    }
+}
+
+async fn attach(ctx: &Context, job_name: &str) -> anyhow::Result<()> {
+    let jobs: Api<Job> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let job = jobs
+        .get(job_name)
+        .await
+        .map_err(|e| anyhow::anyhow!("fetching job {job_name:?}: {e}"))?;
+    let is_recognized_operation = job
+        .metadata
+        .labels
+        .as_ref()
+        .and_then(|labels| labels.get(UPGRADE_JOB_LABEL))
+        .is_some();
+    if !is_recognized_operation {
+        anyhow::bail!(
+            "{job_name} isn't an openebs operation Job this plugin recognizes \
+             (missing the {UPGRADE_JOB_LABEL} label)"
+        );
+    }
+
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let pod_name = pods
+        .list(&ListParams::default().labels(&format!("job-name={job_name}")))
+        .await?
+        .items
+        .into_iter()
+        .next()
+        .and_then(|pod| pod.metadata.name)
+        .ok_or_else(|| anyhow::anyhow!("no pod found for job {job_name}"))?;
+
+    let mut lines = pods
+        .log_stream(&pod_name, &LogParams { follow: true, ..Default::default() })
+        .await?
+        .lines();
+    while let Some(line) = lines.next().await {
+        println!("{}", line?);
+    }
+    Ok(())
+}
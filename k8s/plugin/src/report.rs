@@ -0,0 +1,96 @@
+//! Shared `--report-format` rendering for `doctor` and `upgrade apply`'s
+//! preflight checks, so both can be ingested by CI dashboards and
+//! code-scanning UIs (SARIF, JUnit) that teams already use for
+//! gatekeeping cluster changes, instead of only being readable as plain
+//! stdout text.
+
+use serde_json::json;
+
+#[derive(Clone, Copy, clap::ValueEnum, Default)]
+pub enum ReportFormat {
+    #[default]
+    Text,
+    Sarif,
+    Junit,
+}
+
+/// One check's result, named by the suite it belongs to (e.g. a node
+/// name, or a preflight validation's own name) and the check itself
+/// (e.g. an engine, or "pending-pvc").
+pub struct CheckOutcome {
+    pub suite: String,
+    pub name: String,
+    pub passed: bool,
+    pub message: String,
+}
+
+/// Renders `outcomes` in `format`. `tool_name` identifies the producing
+/// command (`"doctor"` or `"upgrade-preflight"`) in the SARIF/JUnit
+/// output; ignored for [`ReportFormat::Text`], which callers render
+/// themselves line-by-line instead.
+pub fn render(format: ReportFormat, tool_name: &str, outcomes: &[CheckOutcome]) -> String {
+    match format {
+        ReportFormat::Text => outcomes
+            .iter()
+            .map(|o| format!("{} {}/{}: {}", if o.passed { "PASS" } else { "FAIL" }, o.suite, o.name, o.message))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        ReportFormat::Sarif => render_sarif(tool_name, outcomes),
+        ReportFormat::Junit => render_junit(tool_name, outcomes),
+    }
+}
+
+fn render_sarif(tool_name: &str, outcomes: &[CheckOutcome]) -> String {
+    let results: Vec<_> = outcomes
+        .iter()
+        .map(|o| {
+            json!({
+                "ruleId": format!("{}/{}", o.suite, o.name),
+                "level": if o.passed { "none" } else { "error" },
+                "message": { "text": o.message },
+            })
+        })
+        .collect();
+
+    let document = json!({
+        "version": "2.1.0",
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "runs": [{
+            "tool": { "driver": { "name": tool_name, "informationUri": "https://github.com/openebs/kubectl-openebs" } },
+            "results": results,
+        }],
+    });
+    serde_json::to_string_pretty(&document).unwrap_or_default()
+}
+
+fn render_junit(tool_name: &str, outcomes: &[CheckOutcome]) -> String {
+    let failures = outcomes.iter().filter(|o| !o.passed).count();
+    let mut xml = format!(
+        "<testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\">\n",
+        xml_escape(tool_name),
+        outcomes.len(),
+    );
+    for outcome in outcomes {
+        xml.push_str(&format!(
+            "  <testcase classname=\"{}\" name=\"{}\">\n",
+            xml_escape(&outcome.suite),
+            xml_escape(&outcome.name),
+        ));
+        if !outcome.passed {
+            xml.push_str(&format!(
+                "    <failure message=\"{}\"/>\n",
+                xml_escape(&outcome.message),
+            ));
+        }
+        xml.push_str("  </testcase>\n");
+    }
+    xml.push_str("</testsuite>\n");
+    xml
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,114 @@
+//! Shared cluster-access plumbing used by every subcommand.
+
+use kube::api::{Api, ListParams, Patch, PatchParams, PostParams};
+use kube::{Client, Config};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::constants::DEFAULT_PAGE_SIZE;
+
+/// Context bundles the resolved Kubernetes client and the namespace
+/// OpenEBS is installed into, so subcommands don't each re-derive it.
+pub struct Context {
+    pub client: Client,
+    pub namespace: String,
+    /// Page size used for both Kubernetes list calls and REST volume
+    /// listings; see `--page-size`.
+    pub page_size: u32,
+    /// File to append REST API call logs to; see `--debug-http`.
+    pub debug_http: Option<std::path::PathBuf>,
+    /// Whether `debug_http` logging also includes (redacted) response
+    /// bodies; see `--debug-http-bodies`.
+    pub debug_http_bodies: bool,
+}
+
+impl Context {
+    /// Builds a Context from an optional explicit kubeconfig path,
+    /// falling back to the standard kube-rs discovery (KUBECONFIG env,
+    /// `~/.kube/config`, in-cluster config) when `kubeconfig` is `None`.
+    pub async fn new(
+        kubeconfig: Option<String>,
+        namespace: String,
+        page_size: Option<u32>,
+        debug_http: Option<std::path::PathBuf>,
+        debug_http_bodies: bool,
+    ) -> anyhow::Result<Self> {
+        let client = match kubeconfig {
+            Some(path) => {
+                let kubeconfig = kube::config::Kubeconfig::read_from(path)?;
+                let config =
+                    Config::from_custom_kubeconfig(kubeconfig, &Default::default()).await?;
+                Client::try_from(config)?
+            }
+            None => Client::try_default().await?,
+        };
+        Ok(Self {
+            client,
+            namespace,
+            page_size: page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+            debug_http,
+            debug_http_bodies,
+        })
+    }
+}
+
+/// Lists every object of kind `K` across all pages, using `page_size` as
+/// the per-request `limit` so large clusters don't force a single huge
+/// apiserver response.
+pub async fn list_all<K>(api: &Api<K>, page_size: u32) -> kube::Result<Vec<K>>
+where
+    K: kube::Resource + Clone + DeserializeOwned + std::fmt::Debug,
+{
+    list_all_with_selector(api, page_size, None).await
+}
+
+/// Like [`list_all`], but narrowed to objects matching `label_selector`
+/// (standard Kubernetes label-selector syntax, e.g. `app=postgres`) when
+/// given.
+pub async fn list_all_with_selector<K>(
+    api: &Api<K>,
+    page_size: u32,
+    label_selector: Option<&str>,
+) -> kube::Result<Vec<K>>
+where
+    K: kube::Resource + Clone + DeserializeOwned + std::fmt::Debug,
+{
+    let mut items = Vec::new();
+    let mut params = ListParams::default().limit(page_size);
+    if let Some(selector) = label_selector {
+        params = params.labels(selector);
+    }
+    loop {
+        let page = api.list(&params).await?;
+        let continue_token = page.metadata.continue_.clone();
+        items.extend(page.items);
+        match continue_token {
+            Some(token) if !token.is_empty() => params = params.continue_token(&token),
+            _ => break,
+        }
+    }
+    Ok(items)
+}
+
+/// Creates `desired` under `name` if nothing by that name exists yet; if a
+/// fixed-named object created by an older run of this plugin is already
+/// there, server-side-applies `desired` over it instead of treating
+/// `AlreadyExists` as "nothing to do" -- otherwise a rerun after upgrading
+/// the plugin itself would silently keep stale rules/config in place.
+pub async fn idempotent_create_resource<K>(
+    api: &Api<K>,
+    name: &str,
+    desired: &K,
+) -> kube::Result<K>
+where
+    K: kube::Resource + Clone + DeserializeOwned + Serialize + std::fmt::Debug,
+{
+    match api.create(&PostParams::default(), desired).await {
+        Ok(created) => Ok(created),
+        Err(kube::Error::Api(e)) if e.code == 409 => {
+            let params = PatchParams::apply("kubectl-openebs").force();
+            api.patch(name, &params, &Patch::Apply(desired)).await
+        }
+        Err(e) => Err(e),
+    }
+}
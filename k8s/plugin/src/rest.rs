@@ -0,0 +1,201 @@
+//! Thin client for the Mayastor REST API (the `api-rest` Deployment),
+//! used by `get`/`describe` to show live volume/pool state. Kept separate
+//! from the kube-rs based modules since it talks HTTP, not the
+//! apiserver.
+//!
+//! `--debug-http` logs every call this client makes (method, path,
+//! status, duration) to a file, for debugging interop issues between
+//! this plugin and a control-plane REST API version it wasn't tested
+//! against -- without resorting to a packet capture.
+
+use std::io::Write;
+use std::path::Path;
+use std::time::Instant;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::resources::Context;
+
+/// Data keys whose values are replaced with `<redacted>` in
+/// `--debug-http-bodies` logging, matched as a case-insensitive
+/// substring of the key name.
+const REDACTED_KEY_SUBSTRINGS: &[&str] = &["token", "secret", "password", "authorization"];
+
+#[derive(Deserialize, Debug)]
+pub struct VolumeInfo {
+    pub uuid: String,
+    pub size: u64,
+    pub state: String,
+    pub num_replicas: u32,
+}
+
+/// The subset of `GET /v0/volumes/{id}`'s response `kubectl openebs
+/// verify volume` reads: per-replica health, keyed by replica UUID. This
+/// is the control plane's own view of replica consistency (it's the same
+/// state a rebuild is triggered from), not a client-side recomputation --
+/// this plugin has no data-plane/gRPC client to read replica extents
+/// itself.
+#[derive(Deserialize, Debug)]
+pub struct VolumeDetail {
+    pub state: VolumeDetailState,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct VolumeDetailState {
+    #[serde(default)]
+    pub replica_topology: std::collections::BTreeMap<String, ReplicaTopology>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReplicaTopology {
+    pub node: Option<String>,
+    pub pool: Option<String>,
+    pub state: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PoolInfo {
+    pub name: String,
+    pub node: String,
+    pub capacity: u64,
+    pub used: u64,
+}
+
+pub struct RestClient {
+    base_url: String,
+    client: reqwest::Client,
+    debug_http: Option<std::path::PathBuf>,
+    debug_http_bodies: bool,
+}
+
+impl RestClient {
+    /// Builds a client pointed at the in-cluster `api-rest` Service for
+    /// `ctx`'s namespace, inheriting its `--debug-http` settings.
+    pub fn for_context(ctx: &Context) -> Self {
+        let mut client = Self::new(format!(
+            "http://mayastor-api-rest.{}.svc.cluster.local:8081",
+            ctx.namespace
+        ));
+        client.debug_http = ctx.debug_http.clone();
+        client.debug_http_bodies = ctx.debug_http_bodies;
+        client
+    }
+
+    pub fn new(base_url: String) -> Self {
+        Self {
+            base_url,
+            client: reqwest::Client::new(),
+            debug_http: None,
+            debug_http_bodies: false,
+        }
+    }
+
+    pub async fn list_volumes(&self) -> anyhow::Result<Vec<VolumeInfo>> {
+        self.get_json("/v0/volumes").await
+    }
+
+    pub async fn list_pools(&self) -> anyhow::Result<Vec<PoolInfo>> {
+        self.get_json("/v0/pools").await
+    }
+
+    pub async fn get_volume(&self, uuid: &str) -> anyhow::Result<VolumeDetail> {
+        self.get_json(&format!("/v0/volumes/{uuid}")).await
+    }
+
+    /// Sets a volume's target replica count, the one write this client
+    /// supports -- `kubectl openebs mayastor placement-policy set` uses it
+    /// to nudge the control plane into placing an extra replica, since
+    /// there's no REST endpoint for topology/spread constraints
+    /// themselves (see that command's module doc).
+    pub async fn set_replica_count(&self, uuid: &str, count: u32) -> anyhow::Result<VolumeDetail> {
+        self.put_json(&format!("/v0/volumes/{uuid}/replica_count/{count}")).await
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        let started = Instant::now();
+        let response = self.client.get(format!("{}{path}", self.base_url)).send().await?;
+        let status = response.status();
+        let body = response.text().await?;
+        self.log_call("GET", path, status, started.elapsed(), &body);
+
+        if !status.is_success() {
+            anyhow::bail!("GET {path} returned {status}");
+        }
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    async fn put_json<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        let started = Instant::now();
+        let response = self.client.put(format!("{}{path}", self.base_url)).send().await?;
+        let status = response.status();
+        let body = response.text().await?;
+        self.log_call("PUT", path, status, started.elapsed(), &body);
+
+        if !status.is_success() {
+            anyhow::bail!("PUT {path} returned {status}");
+        }
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    fn log_call(
+        &self,
+        method: &str,
+        path: &str,
+        status: reqwest::StatusCode,
+        duration: std::time::Duration,
+        body: &str,
+    ) {
+        let Some(log_path) = &self.debug_http else {
+            return;
+        };
+        let mut line = format!(
+            "{method} {path} -> {status} ({:.3}s)",
+            duration.as_secs_f64()
+        );
+        if self.debug_http_bodies {
+            line.push_str(&format!(" body={}", redact_json_body(body)));
+        }
+        if let Err(e) = append_log_line(log_path, &line) {
+            eprintln!("warning: could not write --debug-http log to {}: {e}", log_path.display());
+        }
+    }
+}
+
+fn append_log_line(path: &Path, line: &str) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")
+}
+
+/// Redacts likely-sensitive fields in a JSON response body before it's
+/// written to the `--debug-http` log, rather than logging it verbatim.
+/// Falls back to logging the raw body if it isn't valid JSON (a REST
+/// error response, for instance, isn't always JSON-shaped).
+fn redact_json_body(body: &str) -> String {
+    let Ok(mut value) = serde_json::from_str::<serde_json::Value>(body) else {
+        return body.to_string();
+    };
+    redact_json_value(&mut value);
+    serde_json::to_string(&value).unwrap_or_else(|_| body.to_string())
+}
+
+fn redact_json_value(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, entry) in map.iter_mut() {
+                let key_lower = key.to_lowercase();
+                if REDACTED_KEY_SUBSTRINGS.iter().any(|needle| key_lower.contains(needle)) {
+                    *entry = serde_json::Value::String("<redacted>".to_string());
+                } else {
+                    redact_json_value(entry);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                redact_json_value(item);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,94 @@
+//! `kubectl openebs setup`: a guided first-boot walkthrough for a
+//! cluster that doesn't have OpenEBS installed yet. Rather than asking a
+//! new user to read the install docs and `doctor` output separately, it
+//! checks for an existing release up front (so it doesn't walk someone
+//! through a "first" install a second time), reports per-node kernel
+//! module compatibility the same way `doctor` does, and prints the
+//! recommended `helm install` invocation for the engines the cluster can
+//! actually run.
+
+use k8s_openapi::api::core::v1::Node;
+use kube::api::Api;
+
+use crate::constants::DEFAULT_CHART_NAME;
+use crate::doctor::ENGINES;
+use crate::node_facts::{self, Verdict};
+use crate::resources::{list_all, Context};
+use crate::upgrade::helm;
+
+#[derive(clap::Args)]
+pub struct SetupArgs {
+    /// Helm chart name to check for an existing release of, and to
+    /// recommend installing.
+    #[arg(long, default_value = DEFAULT_CHART_NAME)]
+    pub chart_name: String,
+
+    /// Skip the per-node kernel module compatibility check, e.g. on a
+    /// large cluster where it's already been run via `doctor`.
+    #[arg(long)]
+    pub skip_node_checks: bool,
+}
+
+pub async fn run(ctx: &Context, args: SetupArgs) -> anyhow::Result<()> {
+    if let Ok(release_name) = helm::helm_release_name(ctx, &args.chart_name).await {
+        println!(
+            "{} is already installed in namespace {:?} as release {release_name:?}; \
+             use `kubectl openebs upgrade` to change it, not a fresh `helm install`.",
+            args.chart_name, ctx.namespace,
+        );
+        return Ok(());
+    }
+
+    println!("no existing {:?} release found in namespace {:?} -- first install", args.chart_name, ctx.namespace);
+
+    let mut recommended = Vec::new();
+    if args.skip_node_checks {
+        println!("skipping per-node kernel module checks (--skip-node-checks)");
+    } else {
+        let nodes: Api<Node> = Api::all(ctx.client.clone());
+        let facts: Vec<_> = list_all(&nodes, ctx.page_size).await?.iter().map(node_facts::node_facts).collect();
+        if facts.is_empty() {
+            println!("no nodes found; can't assess engine compatibility");
+        } else {
+            for engine in ENGINES {
+                let mut incompatible = 0;
+                let mut unknown = 0;
+                for node in &facts {
+                    match node_facts::engine_compatibility(node, engine) {
+                        Verdict::Compatible => {}
+                        Verdict::Incompatible(_) => incompatible += 1,
+                        Verdict::Unknown(_) => unknown += 1,
+                    }
+                }
+                let compatible = facts.len() - incompatible - unknown;
+                println!(
+                    "{engine}: {compatible}/{} node(s) compatible, {incompatible} incompatible, {unknown} unknown",
+                    facts.len(),
+                );
+                if incompatible == 0 {
+                    recommended.push(*engine);
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("next steps:");
+    println!("  1. helm repo add openebs https://openebs.github.io/openebs && helm repo update");
+    if recommended.is_empty() {
+        println!(
+            "  2. helm install {} openebs/openebs -n {} --create-namespace",
+            args.chart_name, ctx.namespace,
+        );
+    } else {
+        println!(
+            "  2. helm install {} openebs/openebs -n {} --create-namespace # recommended engine(s): {}",
+            args.chart_name,
+            ctx.namespace,
+            recommended.join(", "),
+        );
+    }
+    println!("  3. once installed, run `kubectl openebs doctor` to confirm the cluster is healthy");
+
+    Ok(())
+}
@@ -0,0 +1,481 @@
+//! `kubectl openebs smoke-test`: the automated equivalent of "provision a
+//! PVC, write some data, read it back, snapshot it, restore it, clean
+//! up" that an operator would otherwise run by hand after an install or
+//! upgrade to confirm the cluster actually works end to end -- `doctor`
+//! and the upgrade preflight validations only check that the pieces
+//! *look* healthy, not that a pod can actually get a volume mounted and
+//! see its own data again. Meant to be the last step of a CI pipeline
+//! (`kubectl openebs upgrade --wait && kubectl openebs smoke-test`), so
+//! unlike `doctor` it's a gate: it exits non-zero if any engine fails.
+//!
+//! One PVC/pod/snapshot set is created and torn down per engine (or just
+//! for `--storageclass` if given), independently, so one engine's
+//! failure doesn't stop the others from being tested. Snapshot/restore
+//! is attempted best-effort per engine and reported as its own row,
+//! since not every engine's CSI driver (or every cluster) has a
+//! snapshot controller installed; `--skip-snapshot` turns it off
+//! entirely instead of reporting a row that can never pass.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use k8s_openapi::api::core::v1::{
+    Container, PersistentVolumeClaim, PersistentVolumeClaimSpec, PersistentVolumeClaimVolumeSource, Pod, PodSpec,
+    ResourceRequirements, Volume, VolumeMount,
+};
+use k8s_openapi::api::storage::v1::StorageClass;
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{Api, AttachParams, DeleteParams, DynamicObject, GroupVersionKind, PostParams};
+use kube::discovery::ApiResource;
+use tokio::io::AsyncReadExt;
+
+use crate::report::{self, CheckOutcome, ReportFormat};
+use crate::resources::{list_all, Context};
+use crate::upgrade::naming;
+
+const VOLUME_SNAPSHOT_GROUP: &str = "snapshot.storage.k8s.io";
+const VOLUME_SNAPSHOT_VERSION: &str = "v1";
+const VOLUME_SNAPSHOT_KIND: &str = "VolumeSnapshot";
+
+/// Engine name to the CSI provisioner name its StorageClasses use, for
+/// auto-detecting one StorageClass per engine. Mirrors
+/// `crate::upgrade::validations::OPENEBS_PROVISIONERS`'s approach, but
+/// keyed by engine (that list doesn't carry engine names, and doesn't
+/// include mayastor's provisioner, which that validation never needed).
+const ENGINE_PROVISIONERS: &[(&str, &str)] = &[
+    ("zfs", "zfs.csi.openebs.io"),
+    ("lvm", "lvm.csi.openebs.io"),
+    ("mayastor", "io.openebs.csi-mayastor"),
+];
+
+const TEST_IMAGE: &str = "busybox:1.36";
+const TEST_FILE: &str = "/data/smoke-test.txt";
+const TEST_CONTENT: &str = "kubectl-openebs-smoke-test";
+
+#[derive(clap::Args)]
+pub struct SmokeTestArgs {
+    /// Test only this StorageClass instead of auto-detecting one per
+    /// enabled engine. Skips engine detection entirely; the matrix
+    /// reports a single row named after the class.
+    #[arg(long)]
+    pub storageclass: Option<String>,
+
+    /// Only test these engines (comma-separated; one of zfs, lvm,
+    /// mayastor), instead of every engine this plugin knows about.
+    /// Ignored with `--storageclass`. An engine with no matching
+    /// StorageClass on the cluster is reported as skipped, not failed.
+    #[arg(long, value_delimiter = ',')]
+    pub engines: Option<Vec<String>>,
+
+    /// Size of the test PVC.
+    #[arg(long, default_value = "1Gi")]
+    pub size: String,
+
+    /// Skip the snapshot/restore phase, e.g. on a cluster with no
+    /// snapshot controller installed.
+    #[arg(long)]
+    pub skip_snapshot: bool,
+
+    /// `VolumeSnapshotClass` to snapshot against; defaults to the
+    /// cluster's default snapshot class. See `snapshot group create
+    /// --snapshot-class`.
+    #[arg(long)]
+    pub snapshot_class: Option<String>,
+
+    /// How long to wait for the PVC to bind, the pod to become ready, and
+    /// the snapshot to become ready to use, in seconds, each.
+    #[arg(long, default_value_t = 120)]
+    pub timeout_seconds: u64,
+
+    /// Render the pass/fail matrix as SARIF or JUnit XML instead of
+    /// plain text, for ingestion by a CI dashboard.
+    #[arg(long, value_enum, default_value = "text")]
+    pub report_format: ReportFormat,
+}
+
+struct Target {
+    label: String,
+    storage_class: String,
+}
+
+/// A resource created during one target's smoke test, torn down (best
+/// effort, in reverse order) once that target's checks finish,
+/// regardless of whether they passed.
+enum Resource {
+    Pod(String),
+    Pvc(String),
+    Snapshot(String),
+}
+
+pub async fn run(ctx: &Context, args: SmokeTestArgs) -> anyhow::Result<()> {
+    let targets = resolve_targets(ctx, &args).await?;
+    let text = matches!(args.report_format, ReportFormat::Text);
+    let mut outcomes = Vec::new();
+
+    for target in &targets {
+        let target_outcomes = run_target(ctx, target, &args).await;
+        if text {
+            for outcome in &target_outcomes {
+                println!(
+                    "{} {}/{}: {}",
+                    if outcome.passed { "PASS" } else { "FAIL" },
+                    outcome.suite,
+                    outcome.name,
+                    outcome.message
+                );
+            }
+        }
+        outcomes.extend(target_outcomes);
+    }
+
+    if !text {
+        println!("{}", report::render(args.report_format, "smoke-test", &outcomes));
+    }
+
+    let failed: Vec<String> = outcomes
+        .iter()
+        .filter(|o| !o.passed)
+        .map(|o| format!("{}/{}", o.suite, o.name))
+        .collect();
+    if !failed.is_empty() {
+        anyhow::bail!("smoke test failed: {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Either the single `--storageclass` the caller named, or one
+/// StorageClass auto-detected per requested (default: every known)
+/// engine. An engine with no matching StorageClass is dropped silently
+/// here and reported as skipped by its caller instead, since "not
+/// installed" isn't a smoke-test failure.
+async fn resolve_targets(ctx: &Context, args: &SmokeTestArgs) -> anyhow::Result<Vec<Target>> {
+    if let Some(storageclass) = &args.storageclass {
+        return Ok(vec![Target { label: storageclass.clone(), storage_class: storageclass.clone() }]);
+    }
+
+    let scs: Api<StorageClass> = Api::all(ctx.client.clone());
+    let storage_classes = list_all(&scs, ctx.page_size).await?;
+
+    let wanted: Vec<&str> = match &args.engines {
+        Some(engines) => engines.iter().map(String::as_str).collect(),
+        None => ENGINE_PROVISIONERS.iter().map(|(engine, _)| *engine).collect(),
+    };
+
+    let mut targets = Vec::new();
+    for engine in wanted {
+        let Some((_, provisioner)) = ENGINE_PROVISIONERS.iter().find(|(e, _)| *e == engine) else {
+            anyhow::bail!("unknown engine {engine:?}; expected one of: zfs, lvm, mayastor");
+        };
+        let storage_class = storage_classes
+            .iter()
+            .find(|sc| sc.provisioner == *provisioner)
+            .and_then(|sc| sc.metadata.name.clone());
+        match storage_class {
+            Some(storage_class) => targets.push(Target { label: engine.to_string(), storage_class }),
+            None => println!("{engine}: skipped (no StorageClass found for provisioner {provisioner:?})"),
+        }
+    }
+    Ok(targets)
+}
+
+async fn run_target(ctx: &Context, target: &Target, args: &SmokeTestArgs) -> Vec<CheckOutcome> {
+    let mut outcomes = Vec::new();
+    let mut resources = Vec::new();
+    let deadline = Instant::now() + Duration::from_secs(args.timeout_seconds);
+    let name = naming::generate_name_prefix(&["smoke-test", &target.label]);
+
+    let provision = provision(ctx, target, args, &name, deadline, &mut resources).await;
+    match provision {
+        Ok(()) => outcomes.push(pass(&target.label, "provision", "PVC bound and test pod ready")),
+        Err(e) => {
+            outcomes.push(fail(&target.label, "provision", &format!("{e:#}")));
+            teardown(ctx, &resources).await;
+            return outcomes;
+        }
+    }
+
+    match write_read_check(ctx, &name).await {
+        Ok(()) => outcomes.push(pass(&target.label, "write-read", "wrote and read back test data")),
+        Err(e) => outcomes.push(fail(&target.label, "write-read", &format!("{e:#}"))),
+    }
+
+    if !args.skip_snapshot {
+        outcomes.push(snapshot_and_restore(ctx, target, args, &name, deadline, &mut resources).await);
+    }
+
+    teardown(ctx, &resources).await;
+    outcomes
+}
+
+async fn provision(
+    ctx: &Context,
+    target: &Target,
+    args: &SmokeTestArgs,
+    name: &str,
+    deadline: Instant,
+    resources: &mut Vec<Resource>,
+) -> anyhow::Result<()> {
+    let pvcs: Api<PersistentVolumeClaim> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    pvcs.create(&PostParams::default(), &test_pvc(name, &target.storage_class, &args.size)).await?;
+    resources.push(Resource::Pvc(name.to_string()));
+    wait_for_pvc_bound(&pvcs, name, deadline).await?;
+
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    pods.create(&PostParams::default(), &test_pod(name, name)).await?;
+    resources.push(Resource::Pod(name.to_string()));
+    wait_for_pod_ready(&pods, name, deadline).await?;
+    Ok(())
+}
+
+async fn snapshot_and_restore(
+    ctx: &Context,
+    target: &Target,
+    args: &SmokeTestArgs,
+    name: &str,
+    deadline: Instant,
+    resources: &mut Vec<Resource>,
+) -> CheckOutcome {
+    match snapshot_and_restore_inner(ctx, target, args, name, deadline, resources).await {
+        Ok(()) => pass(&target.label, "snapshot-restore", "snapshotted and restored test data"),
+        Err(e) => fail(&target.label, "snapshot-restore", &format!("{e:#}")),
+    }
+}
+
+async fn snapshot_and_restore_inner(
+    ctx: &Context,
+    target: &Target,
+    args: &SmokeTestArgs,
+    name: &str,
+    deadline: Instant,
+    resources: &mut Vec<Resource>,
+) -> anyhow::Result<()> {
+    let resource = ApiResource::from_gvk(&GroupVersionKind::gvk(
+        VOLUME_SNAPSHOT_GROUP,
+        VOLUME_SNAPSHOT_VERSION,
+        VOLUME_SNAPSHOT_KIND,
+    ));
+    let snapshots: Api<DynamicObject> = Api::namespaced_with(ctx.client.clone(), &ctx.namespace, &resource);
+
+    let mut obj = DynamicObject::new(name, &resource);
+    obj.metadata = ObjectMeta { name: Some(name.to_string()), ..Default::default() };
+    let obj = obj.data(serde_json::json!({
+        "spec": {
+            "source": { "persistentVolumeClaimName": name },
+            "volumeSnapshotClassName": args.snapshot_class,
+        }
+    }));
+    snapshots.create(&PostParams::default(), &obj).await?;
+    resources.push(Resource::Snapshot(name.to_string()));
+    wait_for_snapshot_ready(&snapshots, name, deadline).await?;
+
+    let restored_name = format!("{name}-restored");
+    let pvcs: Api<PersistentVolumeClaim> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    pvcs.create(&PostParams::default(), &restored_pvc(&restored_name, &target.storage_class, &args.size, name)).await?;
+    resources.push(Resource::Pvc(restored_name.clone()));
+    wait_for_pvc_bound(&pvcs, &restored_name, deadline).await?;
+
+    let restored_pod_name = format!("{name}-restored");
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    pods.create(&PostParams::default(), &test_pod(&restored_pod_name, &restored_name)).await?;
+    resources.push(Resource::Pod(restored_pod_name.clone()));
+    wait_for_pod_ready(&pods, &restored_pod_name, deadline).await?;
+
+    verify_content(&pods, &restored_pod_name).await
+}
+
+fn test_pvc(name: &str, storage_class: &str, size: &str) -> PersistentVolumeClaim {
+    PersistentVolumeClaim {
+        metadata: ObjectMeta { name: Some(name.to_string()), ..Default::default() },
+        spec: Some(PersistentVolumeClaimSpec {
+            storage_class_name: Some(storage_class.to_string()),
+            access_modes: Some(vec!["ReadWriteOnce".to_string()]),
+            resources: Some(ResourceRequirements {
+                requests: Some(BTreeMap::from([("storage".to_string(), Quantity(size.to_string()))])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn restored_pvc(name: &str, storage_class: &str, size: &str, snapshot_name: &str) -> PersistentVolumeClaim {
+    use k8s_openapi::api::core::v1::TypedLocalObjectReference;
+
+    let mut pvc = test_pvc(name, storage_class, size);
+    if let Some(spec) = &mut pvc.spec {
+        spec.data_source = Some(TypedLocalObjectReference {
+            api_group: Some(VOLUME_SNAPSHOT_GROUP.to_string()),
+            kind: VOLUME_SNAPSHOT_KIND.to_string(),
+            name: snapshot_name.to_string(),
+        });
+    }
+    pvc
+}
+
+fn test_pod(name: &str, pvc_name: &str) -> Pod {
+    Pod {
+        metadata: ObjectMeta { name: Some(name.to_string()), ..Default::default() },
+        spec: Some(PodSpec {
+            containers: vec![Container {
+                name: "smoke-test".to_string(),
+                image: Some(TEST_IMAGE.to_string()),
+                command: Some(vec!["sleep".to_string(), "3600".to_string()]),
+                volume_mounts: Some(vec![VolumeMount {
+                    name: "data".to_string(),
+                    mount_path: "/data".to_string(),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }],
+            volumes: Some(vec![Volume {
+                name: "data".to_string(),
+                persistent_volume_claim: Some(PersistentVolumeClaimVolumeSource {
+                    claim_name: pvc_name.to_string(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }]),
+            restart_policy: Some("Never".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+async fn write_read_check(ctx: &Context, pod_name: &str) -> anyhow::Result<()> {
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let write_command = format!("echo -n {TEST_CONTENT} > {TEST_FILE} && sync");
+    exec(&pods, pod_name, &write_command).await?;
+    verify_content(&pods, pod_name).await
+}
+
+/// Reads `TEST_FILE` back and fails unless its contents are exactly
+/// `TEST_CONTENT` -- run both right after writing and again after a
+/// snapshot restore, so a restore that silently loses data is caught the
+/// same way a mount that never wrote it would be.
+async fn verify_content(pods: &Api<Pod>, pod_name: &str) -> anyhow::Result<()> {
+    let check_command = format!("grep -qF {TEST_CONTENT} {TEST_FILE}");
+    exec(pods, pod_name, &check_command)
+        .await
+        .map_err(|e| anyhow::anyhow!("test data missing or corrupted in {pod_name}: {e:#}"))
+}
+
+/// Runs `command` via `sh -c` in `pod_name`'s single container, failing
+/// unless the remote process reports success. Mirrors
+/// `crate::snapshot::quiesce::run`'s exec handling, but for a one-off
+/// command against a pod this module creates itself rather than a
+/// user-supplied hook against an existing one.
+async fn exec(pods: &Api<Pod>, pod_name: &str, command: &str) -> anyhow::Result<()> {
+    let params = AttachParams::default().stdout(true).stderr(true);
+    let mut attached = pods.exec(pod_name, vec!["sh", "-c", command], &params).await?;
+
+    let mut output = String::new();
+    if let Some(mut stdout) = attached.stdout() {
+        stdout.read_to_string(&mut output).await.ok();
+    }
+    if let Some(mut stderr) = attached.stderr() {
+        stderr.read_to_string(&mut output).await.ok();
+    }
+    let status = attached.take_status().expect("status requested exactly once");
+    attached.join().await?;
+
+    let succeeded = status.await.is_some_and(|s| s.status.as_deref() == Some("Success"));
+    if !succeeded {
+        anyhow::bail!("`{command}` in {pod_name} did not report success: {output}");
+    }
+    Ok(())
+}
+
+async fn wait_for_pvc_bound(pvcs: &Api<PersistentVolumeClaim>, name: &str, deadline: Instant) -> anyhow::Result<()> {
+    loop {
+        let pvc = pvcs.get(name).await?;
+        let phase = pvc.status.as_ref().and_then(|s| s.phase.as_deref()).unwrap_or("Pending");
+        if phase == "Bound" {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            anyhow::bail!("timed out waiting for PVC {name} to bind (still {phase})");
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+}
+
+async fn wait_for_pod_ready(pods: &Api<Pod>, name: &str, deadline: Instant) -> anyhow::Result<()> {
+    loop {
+        let pod = pods.get(name).await?;
+        let ready = pod
+            .status
+            .as_ref()
+            .and_then(|s| s.conditions.as_ref())
+            .into_iter()
+            .flatten()
+            .any(|c| c.type_ == "Ready" && c.status == "True");
+        if ready {
+            return Ok(());
+        }
+        let phase = pod.status.as_ref().and_then(|s| s.phase.as_deref()).unwrap_or("Unknown");
+        if phase == "Failed" {
+            anyhow::bail!("pod {name} failed to start");
+        }
+        if Instant::now() >= deadline {
+            anyhow::bail!("timed out waiting for pod {name} to become ready (still {phase})");
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+}
+
+async fn wait_for_snapshot_ready(snapshots: &Api<DynamicObject>, name: &str, deadline: Instant) -> anyhow::Result<()> {
+    loop {
+        let snapshot = snapshots.get(name).await?;
+        let ready = snapshot
+            .data
+            .get("status")
+            .and_then(|s| s.get("readyToUse"))
+            .and_then(|r| r.as_bool())
+            .unwrap_or(false);
+        if ready {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            anyhow::bail!("timed out waiting for snapshot {name} to become ready to use");
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+}
+
+/// Deletes every resource this target created, largest-consuming-first
+/// (pods before the PVCs/snapshots they reference) so a leftover finalizer
+/// can't block the next one's deletion. Best effort: a teardown failure
+/// is reported, not propagated, since the smoke test's own pass/fail
+/// verdict shouldn't hinge on cleanup succeeding.
+async fn teardown(ctx: &Context, resources: &[Resource]) {
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let pvcs: Api<PersistentVolumeClaim> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let resource = ApiResource::from_gvk(&GroupVersionKind::gvk(
+        VOLUME_SNAPSHOT_GROUP,
+        VOLUME_SNAPSHOT_VERSION,
+        VOLUME_SNAPSHOT_KIND,
+    ));
+    let snapshots: Api<DynamicObject> = Api::namespaced_with(ctx.client.clone(), &ctx.namespace, &resource);
+
+    for created in resources.iter().rev() {
+        let result = match created {
+            Resource::Pod(name) => pods.delete(name, &DeleteParams::default()).await.map(|_| ()),
+            Resource::Pvc(name) => pvcs.delete(name, &DeleteParams::default()).await.map(|_| ()),
+            Resource::Snapshot(name) => snapshots.delete(name, &DeleteParams::default()).await.map(|_| ()),
+        };
+        if let Err(e) = result {
+            eprintln!("warning: cleanup failed: {e}");
+        }
+    }
+}
+
+fn pass(suite: &str, name: &str, message: &str) -> CheckOutcome {
+    CheckOutcome { suite: suite.to_string(), name: name.to_string(), passed: true, message: message.to_string() }
+}
+
+fn fail(suite: &str, name: &str, message: &str) -> CheckOutcome {
+    CheckOutcome { suite: suite.to_string(), name: name.to_string(), passed: false, message: message.to_string() }
+}
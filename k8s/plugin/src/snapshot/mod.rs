@@ -0,0 +1,309 @@
+//! `kubectl openebs snapshot group`: snapshots every PVC backing a
+//! multi-PVC application (e.g. a sharded database, or separate data and
+//! WAL volumes) together, under one named group, instead of one
+//! `VolumeSnapshot` at a time with nothing recording which snapshots
+//! belong together. `create` runs an optional quiesce hook per member
+//! before taking any snapshot (so every member is quiesced before the
+//! first one is taken) and records a group manifest ConfigMap; `restore`
+//! reads that manifest back and recreates one PVC per member from its
+//! snapshot.
+//!
+//! This plugin doesn't carry the snapshot.storage.k8s.io API types --
+//! it's a separate CRD extension, not part of k8s-openapi's core group --
+//! so `VolumeSnapshot` is addressed the same way `get`/`dump volume`
+//! reach OpenEBS's own CRs: by [`GroupVersionKind`] through a
+//! [`DynamicObject`].
+//!
+//! This can only snapshot as atomically as the underlying CSI driver
+//! allows: each member's `VolumeSnapshot` is still a separate apiserver
+//! create call, one after another, not a single transaction. The quiesce
+//! hooks are what narrow the inconsistency window that leaves, not the
+//! snapshot calls themselves.
+
+mod quiesce;
+
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::core::v1::{ConfigMap, PersistentVolumeClaim};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{Api, DynamicObject, GroupVersionKind, PostParams};
+use kube::discovery::ApiResource;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::resources::Context;
+use crate::upgrade::naming;
+
+const VOLUME_SNAPSHOT_GROUP: &str = "snapshot.storage.k8s.io";
+const VOLUME_SNAPSHOT_VERSION: &str = "v1";
+const VOLUME_SNAPSHOT_KIND: &str = "VolumeSnapshot";
+
+/// Label set on every `VolumeSnapshot` a `create` run produces, so a
+/// cluster operator can find every member of a group without needing the
+/// manifest ConfigMap.
+const GROUP_LABEL: &str = "openebs.io/snapshot-group";
+
+#[derive(clap::Subcommand)]
+pub enum SnapshotCommand {
+    /// Snapshot-consistent operations across the PVCs of a multi-PVC
+    /// application.
+    #[command(subcommand)]
+    Group(GroupCommand),
+}
+
+#[derive(clap::Subcommand)]
+pub enum GroupCommand {
+    /// Snapshot every named PVC together, under one group.
+    Create(GroupCreateArgs),
+    /// Recreate a PVC per member of a previously created group, each
+    /// restored from its own snapshot.
+    Restore(GroupRestoreArgs),
+}
+
+#[derive(clap::Args)]
+pub struct GroupCreateArgs {
+    /// PVCs (in `--namespace`) to snapshot together, comma-separated.
+    #[arg(long, value_delimiter = ',', required = true)]
+    pub pvc: Vec<String>,
+
+    /// Name for the group; defaults to a name generated from the member
+    /// PVCs.
+    #[arg(long)]
+    pub group_name: Option<String>,
+
+    /// `VolumeSnapshotClass` each member snapshot is created against;
+    /// defaults to whatever the cluster's default snapshot class is.
+    #[arg(long)]
+    pub snapshot_class: Option<String>,
+
+    /// Quiesce hook to run in a mounting pod before any snapshot is
+    /// taken, as `<pvc>=<pod>/<container>:<command>` (e.g.
+    /// `data=app-0/app:fsfreeze -f /data`); repeatable, at most one per
+    /// PVC. Every `--pre-hook` runs, and must succeed, before the first
+    /// member's snapshot is created.
+    #[arg(long = "pre-hook")]
+    pub pre_hooks: Vec<String>,
+
+    /// Hook to reverse `--pre-hook` (e.g. `fsfreeze -u /data`), same
+    /// format, run once every member's snapshot has been created (or
+    /// creation failed) so the application is never left quiesced longer
+    /// than the snapshots themselves take.
+    #[arg(long = "post-hook")]
+    pub post_hooks: Vec<String>,
+}
+
+#[derive(clap::Args)]
+pub struct GroupRestoreArgs {
+    /// Group name recorded by a prior `snapshot group create`.
+    #[arg(long)]
+    pub group_name: String,
+
+    /// Suffix appended to each member's original PVC name to name its
+    /// restored PVC, so it can coexist with the still-present original.
+    #[arg(long, default_value = "-restored")]
+    pub suffix: String,
+}
+
+/// One member of a group, as recorded in the manifest ConfigMap by
+/// [`create`] and read back by [`restore`].
+#[derive(Serialize, Deserialize)]
+struct GroupMember {
+    pvc: String,
+    snapshot_name: String,
+    storage_class_name: Option<String>,
+    requested_storage: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GroupManifest {
+    group_name: String,
+    namespace: String,
+    members: Vec<GroupMember>,
+}
+
+pub async fn run(ctx: &Context, cmd: SnapshotCommand) -> anyhow::Result<()> {
+    match cmd {
+        SnapshotCommand::Group(GroupCommand::Create(args)) => create(ctx, args).await,
+        SnapshotCommand::Group(GroupCommand::Restore(args)) => restore(ctx, args).await,
+    }
+}
+
+async fn create(ctx: &Context, args: GroupCreateArgs) -> anyhow::Result<()> {
+    let group_name = args
+        .group_name
+        .unwrap_or_else(|| naming::generate_name_prefix(&[&["snapshot-group".to_string(), args.pvc.join("-")].join("-")]));
+
+    let pre_hooks: Vec<quiesce::Hook> = args.pre_hooks.iter().map(|s| quiesce::parse(s)).collect::<Result<_, _>>()?;
+    let post_hooks: Vec<quiesce::Hook> = args.post_hooks.iter().map(|s| quiesce::parse(s)).collect::<Result<_, _>>()?;
+
+    for hook in &pre_hooks {
+        quiesce::run(ctx, hook).await?;
+        println!("ran pre-hook for {}", hook.pvc);
+    }
+
+    let resource = ApiResource::from_gvk(&GroupVersionKind::gvk(
+        VOLUME_SNAPSHOT_GROUP,
+        VOLUME_SNAPSHOT_VERSION,
+        VOLUME_SNAPSHOT_KIND,
+    ));
+    let snapshots: Api<DynamicObject> = Api::namespaced_with(ctx.client.clone(), &ctx.namespace, &resource);
+    let pvcs: Api<PersistentVolumeClaim> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+
+    let mut members = Vec::new();
+    let mut create_error = None;
+    for pvc_name in &args.pvc {
+        match create_member_snapshot(&pvcs, &snapshots, &resource, &group_name, pvc_name, args.snapshot_class.as_deref())
+            .await
+        {
+            Ok(member) => {
+                println!("created snapshot {} for PVC {pvc_name}", member.snapshot_name);
+                members.push(member);
+            }
+            Err(e) => {
+                create_error = Some(e);
+                break;
+            }
+        }
+    }
+
+    for hook in &post_hooks {
+        if let Err(e) = quiesce::run(ctx, hook).await {
+            eprintln!("post-hook for {} failed: {e:#}", hook.pvc);
+        } else {
+            println!("ran post-hook for {}", hook.pvc);
+        }
+    }
+
+    if let Some(e) = create_error {
+        return Err(e.context(format!(
+            "snapshotting group {group_name:?} failed after {} of {} members",
+            members.len(),
+            args.pvc.len()
+        )));
+    }
+
+    let manifest = GroupManifest {
+        group_name: group_name.clone(),
+        namespace: ctx.namespace.clone(),
+        members,
+    };
+    write_manifest(ctx, &manifest).await?;
+    println!("recorded snapshot group {group_name} ({} members)", manifest.members.len());
+    Ok(())
+}
+
+async fn create_member_snapshot(
+    pvcs: &Api<PersistentVolumeClaim>,
+    snapshots: &Api<DynamicObject>,
+    resource: &ApiResource,
+    group_name: &str,
+    pvc_name: &str,
+    snapshot_class: Option<&str>,
+) -> anyhow::Result<GroupMember> {
+    let pvc = pvcs.get(pvc_name).await?;
+    let storage_class_name = pvc.spec.as_ref().and_then(|s| s.storage_class_name.clone());
+    let requested_storage = pvc
+        .spec
+        .as_ref()
+        .and_then(|s| s.resources.as_ref())
+        .and_then(|r| r.requests.as_ref())
+        .and_then(|r| r.get("storage"))
+        .map(|q| q.0.clone());
+
+    let mut obj = DynamicObject::new(pvc_name, resource);
+    obj.metadata = ObjectMeta {
+        generate_name: Some(format!("{}-", naming::generate_name_prefix(&[group_name, pvc_name]))),
+        labels: Some(BTreeMap::from([(GROUP_LABEL.to_string(), group_name.to_string())])),
+        ..Default::default()
+    };
+    let spec = json!({
+        "spec": {
+            "source": { "persistentVolumeClaimName": pvc_name },
+            "volumeSnapshotClassName": snapshot_class,
+        }
+    });
+    let obj = obj.data(spec);
+
+    let created = snapshots.create(&PostParams::default(), &obj).await?;
+    let snapshot_name = created.metadata.name.unwrap_or_default();
+
+    Ok(GroupMember {
+        pvc: pvc_name.to_string(),
+        snapshot_name,
+        storage_class_name,
+        requested_storage,
+    })
+}
+
+async fn write_manifest(ctx: &Context, manifest: &GroupManifest) -> anyhow::Result<()> {
+    let config_maps: Api<ConfigMap> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let data = BTreeMap::from([("manifest".to_string(), serde_json::to_string_pretty(manifest)?)]);
+    let config_map = ConfigMap {
+        metadata: ObjectMeta {
+            name: Some(manifest_config_map_name(&manifest.group_name)),
+            labels: Some(BTreeMap::from([(GROUP_LABEL.to_string(), manifest.group_name.clone())])),
+            ..Default::default()
+        },
+        data: Some(data),
+        ..Default::default()
+    };
+    config_maps.create(&PostParams::default(), &config_map).await?;
+    Ok(())
+}
+
+fn manifest_config_map_name(group_name: &str) -> String {
+    format!("{group_name}-snapshot-group")
+}
+
+async fn restore(ctx: &Context, args: GroupRestoreArgs) -> anyhow::Result<()> {
+    let config_maps: Api<ConfigMap> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let config_map = config_maps.get(&manifest_config_map_name(&args.group_name)).await?;
+    let raw = config_map
+        .data
+        .as_ref()
+        .and_then(|d| d.get("manifest"))
+        .ok_or_else(|| anyhow::anyhow!("manifest ConfigMap for group {} has no `manifest` key", args.group_name))?;
+    let manifest: GroupManifest = serde_json::from_str(raw)?;
+
+    let pvcs: Api<PersistentVolumeClaim> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    for member in &manifest.members {
+        let restored_name = format!("{}{}", member.pvc, args.suffix);
+        let pvc = restored_pvc(&restored_name, member);
+        pvcs.create(&PostParams::default(), &pvc).await?;
+        println!("restored {restored_name} from snapshot {}", member.snapshot_name);
+    }
+    println!("restored {} of {} members in group {}", manifest.members.len(), manifest.members.len(), args.group_name);
+    Ok(())
+}
+
+fn restored_pvc(name: &str, member: &GroupMember) -> PersistentVolumeClaim {
+    use k8s_openapi::api::core::v1::{PersistentVolumeClaimSpec, ResourceRequirements, TypedLocalObjectReference};
+
+    let requests = member
+        .requested_storage
+        .as_ref()
+        .map(|s| BTreeMap::from([("storage".to_string(), Quantity(s.clone()))]));
+
+    PersistentVolumeClaim {
+        metadata: ObjectMeta {
+            name: Some(name.to_string()),
+            ..Default::default()
+        },
+        spec: Some(PersistentVolumeClaimSpec {
+            storage_class_name: member.storage_class_name.clone(),
+            access_modes: Some(vec!["ReadWriteOnce".to_string()]),
+            data_source: Some(TypedLocalObjectReference {
+                api_group: Some(VOLUME_SNAPSHOT_GROUP.to_string()),
+                kind: VOLUME_SNAPSHOT_KIND.to_string(),
+                name: member.snapshot_name.clone(),
+            }),
+            resources: Some(ResourceRequirements {
+                requests,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,69 @@
+//! Runs a quiesce/unquiesce hook command inside a pod already mounting
+//! the PVC being snapshotted (e.g. `fsfreeze -f /data` before, `fsfreeze
+//! -u /data` after), the same way `kubectl exec` does -- a CSI snapshot
+//! alone doesn't know to flush an application's own in-memory state to
+//! disk first.
+
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, AttachParams};
+use tokio::io::AsyncReadExt;
+
+use crate::resources::Context;
+
+/// A `<pod>/<container>:<command>` hook spec, as passed to `--pre-hook`/
+/// `--post-hook`.
+pub struct Hook {
+    pub pvc: String,
+    pub pod: String,
+    pub container: String,
+    pub command: String,
+}
+
+/// Parses `<pvc>=<pod>/<container>:<command>`.
+pub fn parse(spec: &str) -> anyhow::Result<Hook> {
+    let (pvc, rest) = spec
+        .split_once('=')
+        .ok_or_else(|| anyhow::anyhow!("hook {spec:?} is missing a `=` separating the PVC from the hook"))?;
+    let (pod_container, command) = rest
+        .split_once(':')
+        .ok_or_else(|| anyhow::anyhow!("hook {spec:?} is missing a `:` separating pod/container from the command"))?;
+    let (pod, container) = pod_container
+        .split_once('/')
+        .ok_or_else(|| anyhow::anyhow!("hook {spec:?} is missing a `/` separating pod from container"))?;
+    Ok(Hook {
+        pvc: pvc.to_string(),
+        pod: pod.to_string(),
+        container: container.to_string(),
+        command: command.to_string(),
+    })
+}
+
+/// Runs `hook.command` via `sh -c` in `hook.container`, failing unless
+/// the remote process reports success.
+pub async fn run(ctx: &Context, hook: &Hook) -> anyhow::Result<()> {
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let params = AttachParams::default().container(&hook.container).stdout(true).stderr(true);
+    let mut attached = pods.exec(&hook.pod, vec!["sh", "-c", hook.command.as_str()], &params).await?;
+
+    let mut output = String::new();
+    if let Some(mut stdout) = attached.stdout() {
+        stdout.read_to_string(&mut output).await.ok();
+    }
+    if let Some(mut stderr) = attached.stderr() {
+        stderr.read_to_string(&mut output).await.ok();
+    }
+    let status = attached.take_status().expect("status requested exactly once");
+    attached.join().await?;
+
+    let succeeded = status.await.is_some_and(|s| s.status.as_deref() == Some("Success"));
+    if !succeeded {
+        anyhow::bail!(
+            "hook `{}` in {}/{} (for PVC {}) did not report success: {output}",
+            hook.command,
+            hook.pod,
+            hook.container,
+            hook.pvc
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,122 @@
+//! `kubectl openebs suggest`: a triage entry point for someone who's
+//! landed on a cluster and doesn't know what's wrong with it yet. It
+//! doesn't run anything new -- it reuses `get`'s volume/pool listings,
+//! `upgrade`'s pending-PVC validation and `setup`'s Helm release lookup
+//! -- and turns whatever comes back unhealthy into a specific next
+//! command to run, instead of a raw state dump the operator has to
+//! translate into an action themselves.
+
+use crate::constants::DEFAULT_CHART_NAME;
+use crate::resources::Context;
+use crate::rest::RestClient;
+use crate::upgrade::error::UpgradeError;
+use crate::upgrade::{helm, validations};
+
+#[derive(clap::Args)]
+pub struct SuggestArgs {
+    /// Helm chart name to check for a failed release of.
+    #[arg(long, default_value = DEFAULT_CHART_NAME)]
+    pub chart_name: String,
+
+    /// Flag a pool as full once it has used this fraction of its
+    /// capacity (0.0-1.0).
+    #[arg(long, default_value_t = 0.9)]
+    pub pool_full_threshold: f64,
+}
+
+pub async fn run(ctx: &Context, args: SuggestArgs) -> anyhow::Result<()> {
+    let mut suggestions = Vec::new();
+
+    degraded_volume_suggestions(ctx, &mut suggestions).await;
+    full_pool_suggestions(ctx, args.pool_full_threshold, &mut suggestions).await;
+    pending_pvc_suggestions(ctx, &mut suggestions).await;
+    failed_release_suggestions(ctx, &args.chart_name, &mut suggestions).await;
+
+    if suggestions.is_empty() {
+        println!(
+            "no issues found in volumes, pools, PVCs or the {:?} Helm release; \
+             run `kubectl openebs doctor` for a routine node-level check anyway.",
+            args.chart_name,
+        );
+    } else {
+        for s in &suggestions {
+            println!("- {s}");
+        }
+    }
+    Ok(())
+}
+
+async fn degraded_volume_suggestions(ctx: &Context, suggestions: &mut Vec<String>) {
+    match RestClient::for_context(ctx).list_volumes().await {
+        Ok(volumes) => {
+            for v in volumes.iter().filter(|v| v.state != "Online") {
+                suggestions.push(format!(
+                    "volume {} is {} -- run `kubectl openebs verify volume {}` to check replica \
+                     consistency, or `kubectl openebs dump volume {}` to collect a support bundle",
+                    v.uuid, v.state, v.uuid, v.uuid,
+                ));
+            }
+        }
+        Err(e) => suggestions.push(format!(
+            "api-rest unreachable ({e}) -- can't check volume health this way; run \
+             `kubectl openebs dump system` to collect what's reachable"
+        )),
+    }
+}
+
+async fn full_pool_suggestions(ctx: &Context, threshold: f64, suggestions: &mut Vec<String>) {
+    match RestClient::for_context(ctx).list_pools().await {
+        Ok(pools) => {
+            for p in &pools {
+                if p.capacity == 0 {
+                    continue;
+                }
+                let used_fraction = p.used as f64 / p.capacity as f64;
+                if used_fraction >= threshold {
+                    suggestions.push(format!(
+                        "pool {} on node {} is {:.0}% full -- run `kubectl openebs get pools` to \
+                         check the rest of the cluster, and plan to add capacity or rebalance \
+                         before it blocks new volume placement",
+                        p.name,
+                        p.node,
+                        used_fraction * 100.0,
+                    ));
+                }
+            }
+        }
+        Err(e) => suggestions.push(format!("api-rest unreachable ({e}) -- can't check pool capacity")),
+    }
+}
+
+async fn pending_pvc_suggestions(ctx: &Context, suggestions: &mut Vec<String>) {
+    if let Err(UpgradeError::ValidationFailed(message)) = validations::pending_pvc_validation(ctx).await {
+        suggestions.push(format!(
+            "{message} -- run `kubectl openebs doctor` to check whether a node issue is \
+             blocking the CSI controller"
+        ));
+    }
+}
+
+async fn failed_release_suggestions(ctx: &Context, chart_name: &str, suggestions: &mut Vec<String>) {
+    let release_name = match helm::helm_release_name(ctx, chart_name).await {
+        Ok(name) => name,
+        Err(_) => {
+            suggestions.push(format!(
+                "no {chart_name:?} Helm release found -- run `kubectl openebs setup` for a \
+                 guided first install"
+            ));
+            return;
+        }
+    };
+
+    let Ok(release) = helm::helm_release_data(ctx, &release_name).await else {
+        return;
+    };
+    if release.info.status.as_deref().is_some_and(|status| status.contains("failed")) {
+        suggestions.push(format!(
+            "Helm release {release_name:?} is in status {:?} -- run `kubectl openebs upgrade \
+             status --history` to see how the last upgrade Job got there",
+            release.info.status.unwrap_or_default(),
+        ));
+    }
+}
@@ -0,0 +1,170 @@
+//! `kubectl openebs version --check-updates`: lists chart versions newer
+//! than this plugin's own by consulting a Helm chart repository's
+//! `index.yaml`, the same file `helm repo update` fetches. The index is
+//! cached locally so a flaky connection (or a deliberately disconnected
+//! cluster) doesn't break an otherwise offline command -- `version`
+//! itself needs no cluster access at all, and this shouldn't be the
+//! thing that forces it to.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Deserialize;
+
+use crate::constants::{DEFAULT_CHART_NAME, DEFAULT_CHART_REPO_INDEX_URL};
+use crate::upgrade::version::ParsedVersion;
+
+#[derive(clap::Args)]
+pub struct CheckUpdatesArgs {
+    /// Chart repository index.yaml URL to consult.
+    #[arg(long, default_value = DEFAULT_CHART_REPO_INDEX_URL)]
+    pub chart_repo_index_url: String,
+
+    /// Name of the chart entry to look up in the index.
+    #[arg(long, default_value = DEFAULT_CHART_NAME)]
+    pub chart_name: String,
+
+    /// Where to cache the fetched index. Defaults to
+    /// `$XDG_CACHE_HOME/kubectl-openebs/chart-index.yaml`, or
+    /// `$HOME/.cache/...` if `XDG_CACHE_HOME` is unset.
+    #[arg(long)]
+    pub index_cache_path: Option<PathBuf>,
+
+    /// Use only the cached index, failing instead of reaching the
+    /// network. For disconnected environments that still want to check
+    /// a previously-cached index against the plugin version in use.
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Refetch the index even if a cached copy within
+    /// `--index-cache-max-age-seconds` already exists.
+    #[arg(long)]
+    pub refresh: bool,
+
+    /// Maximum age, in seconds, of a cached index before it's refetched
+    /// automatically.
+    #[arg(long, default_value_t = 86400)]
+    pub index_cache_max_age_seconds: u64,
+}
+
+#[derive(Deserialize)]
+struct RepoIndex {
+    entries: BTreeMap<String, Vec<ChartEntry>>,
+}
+
+#[derive(Deserialize)]
+struct ChartEntry {
+    version: String,
+}
+
+pub async fn run(args: &CheckUpdatesArgs) -> anyhow::Result<()> {
+    let current_version = env!("CARGO_PKG_VERSION");
+    let index = load_index(args).await?;
+    let available = available_versions(&index, &args.chart_name)?;
+    let newer = newer_versions(current_version, &available)?;
+
+    if newer.is_empty() {
+        println!("no newer {} chart versions available (current: {current_version})", args.chart_name);
+    } else {
+        println!("newer {} chart versions available:", args.chart_name);
+        for version in newer {
+            println!("  {version}");
+        }
+    }
+    Ok(())
+}
+
+fn default_cache_path() -> PathBuf {
+    let cache_home = std::env::var("XDG_CACHE_HOME").map(PathBuf::from).unwrap_or_else(|_| {
+        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        PathBuf::from(home).join(".cache")
+    });
+    cache_home.join("kubectl-openebs").join("chart-index.yaml")
+}
+
+async fn load_index(args: &CheckUpdatesArgs) -> anyhow::Result<Vec<u8>> {
+    let cache_path = args.index_cache_path.clone().unwrap_or_else(default_cache_path);
+    let cached = std::fs::read(&cache_path).ok();
+
+    if args.offline {
+        return cached.ok_or_else(|| {
+            anyhow::anyhow!(
+                "--offline was passed but no cached chart repo index exists at {}; \
+                 run `version --check-updates` without --offline at least once first",
+                cache_path.display()
+            )
+        });
+    }
+
+    if !args.refresh {
+        if let Some(cached) = &cached {
+            if cache_age_seconds(&cache_path)? < args.index_cache_max_age_seconds {
+                return Ok(cached.clone());
+            }
+        }
+    }
+
+    match fetch_index(&args.chart_repo_index_url).await {
+        Ok(bytes) => {
+            write_cache(&cache_path, &bytes)?;
+            Ok(bytes)
+        }
+        Err(e) => cached.ok_or_else(|| {
+            anyhow::anyhow!(
+                "fetching chart repo index from {}: {e} (no cached copy to fall back to; \
+                 pass --offline once one exists)",
+                args.chart_repo_index_url
+            )
+        }),
+    }
+}
+
+fn cache_age_seconds(cache_path: &Path) -> anyhow::Result<u64> {
+    let modified = std::fs::metadata(cache_path)?.modified()?;
+    Ok(SystemTime::now().duration_since(modified).unwrap_or_default().as_secs())
+}
+
+async fn fetch_index(url: &str) -> anyhow::Result<Vec<u8>> {
+    let response = reqwest::get(url).await?.error_for_status()?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+fn write_cache(cache_path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+    if let Some(parent) = cache_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(cache_path, bytes)?;
+    Ok(())
+}
+
+fn available_versions(index: &[u8], chart_name: &str) -> anyhow::Result<Vec<String>> {
+    let parsed: RepoIndex = serde_yaml::from_slice(index)?;
+    let entries = parsed
+        .entries
+        .get(chart_name)
+        .ok_or_else(|| anyhow::anyhow!("chart {chart_name:?} not found in repo index"))?;
+    Ok(entries.iter().map(|entry| entry.version.clone()).collect())
+}
+
+fn newer_versions(current_version: &str, available: &[String]) -> anyhow::Result<Vec<String>> {
+    let ParsedVersion::Release(current) = ParsedVersion::parse(current_version) else {
+        anyhow::bail!(
+            "can't compare available chart versions against a non-release plugin build ({current_version})"
+        );
+    };
+
+    let mut newer: Vec<String> = available
+        .iter()
+        .filter(|version| match ParsedVersion::parse(version) {
+            ParsedVersion::Release(candidate) => {
+                (candidate.major, candidate.minor, candidate.patch)
+                    > (current.major, current.minor, current.patch)
+            }
+            ParsedVersion::Dev(_) => false,
+        })
+        .cloned()
+        .collect();
+    newer.sort();
+    Ok(newer)
+}
@@ -0,0 +1,51 @@
+//! Packages a local Helm chart directory for `--offline-chart-dir`
+//! upgrades, so the upgrade Job never has to reach a chart repository or
+//! OCI registry over the network. Many OpenEBS clusters run disconnected
+//! from the internet.
+//!
+//! Pulling the chart from a *reachable* OCI registry instead
+//! (`--offline-chart-oci-ref`) doesn't go through this module at all --
+//! the Job image itself has a Helm OCI-aware client and is handed the
+//! reference (and, for private registries, the
+//! `--chart-oci-registry-secret` Secret name) as plain args; see
+//! [`super::job::build_upgrade_job`].
+
+use std::path::Path;
+
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::{Api, PostParams};
+
+use crate::archive;
+use crate::resources::Context;
+
+use super::configmap;
+use super::error::UpgradeError;
+
+/// Where the reassembled chart tarball is written inside the upgrade
+/// Job's container.
+pub const OFFLINE_CHART_MOUNT_ROOT: &str = "/etc/openebs-upgrade/offline-chart";
+
+/// Tars and gzips `chart_dir`, chunks the archive across ConfigMaps and
+/// creates them, returning the ConfigMap names in chunk order so the Job
+/// can mount and reassemble the chart before handing it to Helm.
+pub async fn package_offline_chart(
+    ctx: &Context,
+    chart_dir: &Path,
+) -> Result<Vec<String>, UpgradeError> {
+    let tarball = archive::tar_gz_bytes(chart_dir).map_err(|e| {
+        UpgradeError::ValidationFailed(format!(
+            "packaging --offline-chart-dir {}: {e}",
+            chart_dir.display()
+        ))
+    })?;
+
+    let config_maps = configmap::config_map_data_bytes("openebs-upgrade-offline-chart", &tarball)?;
+    let api: Api<ConfigMap> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+
+    let mut names = Vec::new();
+    for config_map in config_maps {
+        let created = api.create(&PostParams::default(), &config_map).await?;
+        names.push(created.metadata.name.unwrap_or_default());
+    }
+    Ok(names)
+}
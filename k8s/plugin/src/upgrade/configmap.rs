@@ -0,0 +1,154 @@
+//! Packages `--set-file` contents into one or more ConfigMaps instead of
+//! inlining them as upgrade Job container args.
+//!
+//! A single ConfigMap is capped at etcd's ~1MiB object size limit, so a
+//! `--set-file` value larger than that used to make the upgrade Job fail
+//! to even start. Values are now chunked across as many ConfigMaps as
+//! needed, each safely under the limit, with a mapping the Job uses to
+//! reassemble the original contents in order.
+
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::core::v1::ConfigMap;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+
+use crate::provenance;
+
+use super::error::UpgradeError;
+use super::naming;
+use super::values::SetValue;
+
+/// Kept comfortably under etcd's 1MiB object size limit once the
+/// ConfigMap's own metadata and key names are accounted for.
+const CONFIG_MAP_DATA_LIMIT: usize = 900 * 1024;
+
+/// Guardrail against shipping an unreasonably large `--set-file` payload
+/// through the apiserver one ConfigMap chunk at a time.
+const MAX_TOTAL_SET_FILE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Annotation recording the sha256 of the source the ConfigMap was built
+/// from, so the upgrade Job can verify a remote `--set-file`/`-f` fetch
+/// wasn't tampered with or truncated in transit.
+const SOURCE_CHECKSUM_ANNOTATION: &str = "openebs.io/source-sha256";
+
+/// Maps a `--set-file`/`-f` key to the ordered, still-to-be-named
+/// ConfigMap chunk keys that hold its contents, so the upgrade Job can
+/// reassemble it once the ConfigMap has been created. `key` is empty for
+/// a `-f` whole-values-file entry, which merges at the top level rather
+/// than under one dotted path.
+pub struct SetFileMapping {
+    pub key: String,
+    pub config_map_name: String,
+    pub chunk_keys: Vec<String>,
+}
+
+/// Builds one `ConfigMap` per `--set-file` value, splitting its contents
+/// across as many `chunk-N` keys as needed to stay under
+/// `CONFIG_MAP_DATA_LIMIT`. Each returned ConfigMap is paired with the
+/// `SetFileMapping` the job needs to reassemble it; `config_map_name` is
+/// left empty since the final name (via `generateName`) isn't known
+/// until the apiserver creates it.
+pub fn config_map_data(
+    name_prefix: &str,
+    set_files: &[SetValue],
+) -> Result<Vec<(ConfigMap, SetFileMapping)>, UpgradeError> {
+    let total: usize = set_files.iter().map(|v| value_str(v).len()).sum();
+    if total > MAX_TOTAL_SET_FILE_BYTES {
+        return Err(UpgradeError::ValidationFailed(format!(
+            "--set-file payload is {total} bytes, over the {MAX_TOTAL_SET_FILE_BYTES} byte guardrail; \
+             pass a smaller file or split the override across multiple upgrades"
+        )));
+    }
+
+    let mut out = Vec::new();
+    for set in set_files {
+        let contents = value_str(set);
+        let mut data = BTreeMap::new();
+        let mut chunk_keys = Vec::new();
+        for (i, chunk) in chunk_str(contents, CONFIG_MAP_DATA_LIMIT).enumerate() {
+            let chunk_key = format!("chunk-{i}");
+            data.insert(chunk_key.clone(), chunk.to_string());
+            chunk_keys.push(chunk_key);
+        }
+
+        let annotations = set.checksum.as_ref().map(|checksum| {
+            BTreeMap::from([(SOURCE_CHECKSUM_ANNOTATION.to_string(), checksum.clone())])
+        });
+        let config_map = ConfigMap {
+            metadata: ObjectMeta {
+                generate_name: Some(format!("{}-", naming::generate_name_prefix(&[name_prefix, &set.key]))),
+                annotations: provenance::annotate(annotations),
+                ..Default::default()
+            },
+            data: Some(data),
+            ..Default::default()
+        };
+        out.push((
+            config_map,
+            SetFileMapping {
+                key: set.key.clone(),
+                config_map_name: String::new(),
+                chunk_keys,
+            },
+        ));
+    }
+    Ok(out)
+}
+
+fn value_str(set: &SetValue) -> &str {
+    set.value.as_str().unwrap_or_default()
+}
+
+/// Base64-encodes `bytes` then chunks the result across ConfigMaps, the
+/// same strategy `--set-file` values use -- for binary payloads such as a
+/// packaged Helm chart tarball. Each returned ConfigMap holds a single
+/// `chunk` key; the caller creates them and records the assigned names in
+/// order to reassemble the payload.
+pub fn config_map_data_bytes(
+    name_prefix: &str,
+    bytes: &[u8],
+) -> Result<Vec<ConfigMap>, UpgradeError> {
+    if bytes.len() > MAX_TOTAL_SET_FILE_BYTES {
+        return Err(UpgradeError::ValidationFailed(format!(
+            "payload is {} bytes, over the {MAX_TOTAL_SET_FILE_BYTES} byte guardrail",
+            bytes.len()
+        )));
+    }
+
+    use base64::Engine;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+
+    let mut config_maps = Vec::new();
+    for chunk in chunk_str(&encoded, CONFIG_MAP_DATA_LIMIT) {
+        let mut data = BTreeMap::new();
+        data.insert("chunk".to_string(), chunk.to_string());
+        config_maps.push(ConfigMap {
+            metadata: ObjectMeta {
+                generate_name: Some(format!("{name_prefix}-")),
+                annotations: provenance::annotate(None),
+                ..Default::default()
+            },
+            data: Some(data),
+            ..Default::default()
+        });
+    }
+    Ok(config_maps)
+}
+
+/// Splits `s` into chunks of at most `max_bytes`, never inside a UTF-8
+/// character boundary.
+fn chunk_str(s: &str, max_bytes: usize) -> impl Iterator<Item = &str> {
+    let mut rest = s;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let mut split_at = rest.len().min(max_bytes);
+        while !rest.is_char_boundary(split_at) {
+            split_at -= 1;
+        }
+        let (chunk, remainder) = rest.split_at(split_at);
+        rest = remainder;
+        Some(chunk)
+    })
+}
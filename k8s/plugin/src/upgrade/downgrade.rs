@@ -0,0 +1,193 @@
+//! `kubectl openebs downgrade`: a deliberate, distinct path for moving an
+//! OpenEBS installation backwards. `upgrade apply` refuses a `--to-version`
+//! older than the current one outright (see [`super::validations::upgrade_path_validation`]);
+//! this command is the supported way to recover from a bad upgrade instead
+//! of reaching for `--skip-upgrade-path-validation`. It snapshots the
+//! current Helm release values into a ConfigMap before touching anything,
+//! blocks when the target's on-disk format differs from the current one,
+//! and otherwise reuses the same upgrade-job machinery as `upgrade apply`
+//! with a `--downgrade` flag so the Job runs the reverse migration.
+
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::{Api, PostParams};
+
+use crate::report::ReportFormat;
+use crate::resources::Context;
+
+use super::configmap;
+use super::error::UpgradeError;
+use super::helm;
+use super::job;
+use super::version::ParsedVersion;
+use super::{RestartStrategy, UpgradeArgs};
+
+#[derive(clap::Args)]
+pub struct DowngradeArgs {
+    /// Version to downgrade to. Must be strictly older than the
+    /// currently installed version; use `upgrade apply` to move forward.
+    #[arg(long = "to")]
+    pub target_version: String,
+
+    /// Name of the installed Helm release to snapshot and downgrade.
+    #[arg(long, default_value = "openebs")]
+    pub release_name: String,
+
+    /// Proceed even though the target's on-disk format version differs
+    /// from the current one. The downgrade Job still runs the reverse
+    /// migration, but it may not exist for this specific version pair --
+    /// only pass this once you've confirmed it does.
+    #[arg(long)]
+    pub skip_disk_format_check: bool,
+}
+
+/// Disk-format version known for each OpenEBS release line
+/// (`major.minor`). Downgrading across a disk-format boundary is
+/// blocked by default: the reverse on-disk migration for an arbitrary
+/// version pair isn't guaranteed to exist.
+const DISK_FORMAT_VERSIONS: &[((u64, u64), u32)] = &[
+    ((0, 1), 1),
+    ((0, 2), 1),
+    ((0, 3), 2),
+    ((0, 4), 2),
+    ((0, 5), 3),
+];
+
+fn disk_format_version(major: u64, minor: u64) -> Option<u32> {
+    DISK_FORMAT_VERSIONS
+        .iter()
+        .find(|((maj, min), _)| *maj == major && *min == minor)
+        .map(|(_, format)| *format)
+}
+
+/// Fails unless `target` is strictly older than `current`, by
+/// major.minor.patch precedence (prerelease/build metadata aren't
+/// considered -- this is a coarser check than [`super::version::check_upgrade_path`],
+/// deliberately, since a downgrade is already the unusual path).
+fn require_older(current: &str, target: &str) -> Result<(), UpgradeError> {
+    let (ParsedVersion::Release(from), ParsedVersion::Release(to)) =
+        (ParsedVersion::parse(current), ParsedVersion::parse(target))
+    else {
+        return Err(UpgradeError::ValidationFailed(format!(
+            "cannot compare {current:?} and {target:?}: one of them isn't a parsable release version"
+        )));
+    };
+    if (to.major, to.minor, to.patch) >= (from.major, from.minor, from.patch) {
+        return Err(UpgradeError::ValidationFailed(format!(
+            "--to {target} is not older than the current version {current}; use `upgrade apply` to move forward"
+        )));
+    }
+    Ok(())
+}
+
+/// Blocks the downgrade unless both versions share a known disk-format
+/// version, or the caller passed `--skip-disk-format-check`.
+fn check_disk_format(current: &str, target: &str, skip: bool) -> Result<(), UpgradeError> {
+    if skip {
+        return Ok(());
+    }
+    let (ParsedVersion::Release(from), ParsedVersion::Release(to)) =
+        (ParsedVersion::parse(current), ParsedVersion::parse(target))
+    else {
+        return Err(UpgradeError::ValidationFailed(format!(
+            "cannot check disk-format compatibility: {current:?} or {target:?} isn't a parsable release version"
+        )));
+    };
+    let from_format = disk_format_version(from.major, from.minor).ok_or_else(|| {
+        UpgradeError::ValidationFailed(format!("no known disk-format version for {current}"))
+    })?;
+    let to_format = disk_format_version(to.major, to.minor).ok_or_else(|| {
+        UpgradeError::ValidationFailed(format!("no known disk-format version for {target}"))
+    })?;
+    if from_format != to_format {
+        return Err(UpgradeError::ValidationFailed(format!(
+            "downgrading from {current} (disk format v{from_format}) to {target} (disk format v{to_format}) \
+             changes the on-disk format; pass --skip-disk-format-check only once you've verified the reverse \
+             migration exists for this pair"
+        )));
+    }
+    Ok(())
+}
+
+/// Snapshots the release's current `config` (the user-supplied values,
+/// not the merged chart defaults) into a ConfigMap, so it's recoverable
+/// if the downgrade itself needs rolling back.
+async fn snapshot_release_values(ctx: &Context, release_name: &str) -> Result<String, UpgradeError> {
+    let release = helm::helm_release_data(ctx, release_name).await?;
+    let snapshot = serde_json::to_vec_pretty(&release.config)
+        .map_err(|e| UpgradeError::ValidationFailed(format!("serializing release snapshot: {e}")))?;
+
+    let config_maps: Api<ConfigMap> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let mut names = Vec::new();
+    for config_map in configmap::config_map_data_bytes("openebs-downgrade-snapshot", &snapshot)? {
+        let created = config_maps.create(&PostParams::default(), &config_map).await?;
+        names.push(created.metadata.name.unwrap_or_default());
+    }
+    Ok(names.join(","))
+}
+
+pub(crate) async fn run(ctx: &Context, args: DowngradeArgs) -> anyhow::Result<()> {
+    let current_version = env!("CARGO_PKG_VERSION");
+    require_older(current_version, &args.target_version)?;
+    check_disk_format(current_version, &args.target_version, args.skip_disk_format_check)?;
+
+    let snapshot_config_maps = snapshot_release_values(ctx, &args.release_name).await?;
+    println!("snapshotted current release values to ConfigMap(s): {snapshot_config_maps}");
+
+    let upgrade_args = UpgradeArgs {
+        target_version: Some(args.target_version.clone()),
+        skip_pending_pvc_validation: false,
+        skip_node_health_validation: false,
+        skip_single_volume_replica_validation: false,
+        skip_cordoned_node_validation: Vec::new(),
+        skip_upgrade_path_validation: true,
+        set: Vec::new(),
+        set_file: Vec::new(),
+        values_files: Vec::new(),
+        chart_schema: None,
+        job_tolerations: Vec::new(),
+        job_node_selector: Vec::new(),
+        job_affinity: None,
+        upgrade_image_repo: None,
+        upgrade_image_tag: None,
+        upgrade_image: None,
+        offline_chart_dir: None,
+        offline_chart_oci_ref: None,
+        chart_oci_registry_secret: None,
+        job_ttl_seconds: None,
+        job_backoff_limit: 6,
+        job_deadline_seconds: None,
+        image_pull_secrets: Vec::new(),
+        verify_chart: false,
+        atomic: false,
+        timeout: None,
+        wait: false,
+        helm_retry_max_attempts: 3,
+        helm_retry_backoff_seconds: 5,
+        report_format: ReportFormat::Text,
+        repair_pending_release: false,
+        resume: false,
+        branding_product_name: None,
+        branding_event_reason: None,
+        branding_label_prefix: None,
+        restart_parallelism: 1,
+        rebuild_wait_timeout: "15m".to_string(),
+        restart_strategy: RestartStrategy::RollingUpdate,
+        max_unavailable: "1".to_string(),
+        skip_partial_rebuild_toggle: false,
+        skip_crd_apply: false,
+        job_http_proxy: None,
+        job_https_proxy: None,
+        job_no_proxy: None,
+        job_dns_nameserver: Vec::new(),
+        job_dns_search: Vec::new(),
+        event_wait_timeout_seconds: 60,
+        allow_implicit_destination_version: false,
+        release_name: args.release_name.clone(),
+        force_take_lock: false,
+    };
+
+    let job = job::downgradeable_job(ctx, &upgrade_args, &[], &[], true).await?;
+    let name = job.metadata.name.unwrap_or_default();
+    println!("created downgrade job {name}");
+    Ok(())
+}
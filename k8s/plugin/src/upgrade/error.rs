@@ -0,0 +1,10 @@
+//! Error type shared by the upgrade preflight validations and job driver.
+
+#[derive(thiserror::Error, Debug)]
+pub enum UpgradeError {
+    #[error("preflight validation failed: {0}")]
+    ValidationFailed(String),
+
+    #[error("kube API error: {0}")]
+    Kube(#[from] kube::Error),
+}
@@ -0,0 +1,68 @@
+//! `kubectl openebs upgrade health`: reduces [`status::get_upgrade_status`]'s
+//! richer, plugin-specific status down to the three-value verdict an
+//! external health gate actually acts on. Argo Rollouts AnalysisTemplates
+//! (the `job`/`web`/command-based providers) and Flux `HealthCheck`s both
+//! expect a `healthy`/`progressing`/`degraded` vocabulary, not this
+//! plugin's own phase names or status sources -- this is the adapter
+//! between the two during a progressive platform upgrade.
+
+use serde::Serialize;
+
+use crate::resources::Context;
+
+use super::error::UpgradeError;
+use super::status::{self, StatusSource, UpgradePhase};
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum HealthVerdict {
+    Healthy,
+    Progressing,
+    Degraded,
+}
+
+#[derive(Serialize)]
+pub struct HealthReport {
+    pub job_name: String,
+    pub verdict: HealthVerdict,
+    pub reason: String,
+}
+
+/// Resolves `job_name` (or the most recent upgrade Job) the same way
+/// [`status::get_upgrade_status`] does, then maps its status to a
+/// verdict: a failed Job condition or a pod stuck before it can run at
+/// all is `degraded`; a Job/Event/ConfigMap reporting `Completed` is
+/// `healthy`; anything else still in flight is `progressing`.
+pub async fn evaluate_health(
+    ctx: &Context,
+    job_name: Option<&str>,
+) -> Result<HealthReport, UpgradeError> {
+    let status = status::get_upgrade_status(ctx, job_name).await?;
+
+    let (verdict, reason) = match status.source {
+        StatusSource::JobConditions if status.phase == "Failed" => (
+            HealthVerdict::Degraded,
+            format!("upgrade job condition Failed: {}", status.message),
+        ),
+        StatusSource::PodState => (
+            HealthVerdict::Degraded,
+            format!("upgrade job pod stuck in {}: {}", status.phase, status.message),
+        ),
+        StatusSource::JobConditions if status.phase == "Complete" => {
+            (HealthVerdict::Healthy, "upgrade job condition Complete".to_string())
+        }
+        _ if matches!(status.detail.as_ref().map(|d| d.phase), Some(UpgradePhase::Completed)) => {
+            (HealthVerdict::Healthy, "upgrade phase detail reports Completed".to_string())
+        }
+        _ => (
+            HealthVerdict::Progressing,
+            format!("upgrade phase {}: {}", status.phase, status.message),
+        ),
+    };
+
+    Ok(HealthReport {
+        job_name: status.job_name,
+        verdict,
+        reason,
+    })
+}
@@ -0,0 +1,380 @@
+//! Helpers for reading Helm release state: decoding the release
+//! Secret/ConfigMap Helm itself writes, walking a chart's full release
+//! history, and resolving the container image a component actually runs
+//! with.
+
+use std::io::Read;
+use std::process::Command;
+
+use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+use kube::api::{Api, ListParams};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::resources::Context;
+
+use super::error::UpgradeError;
+
+/// A decoded Helm release record, as stored (base64 + gzip + JSON) in the
+/// release Secret/ConfigMap Helm manages.
+#[derive(Deserialize, Serialize)]
+pub struct Release {
+    pub name: String,
+    pub version: i64,
+    #[serde(default)]
+    pub info: ReleaseInfo,
+    /// The values the user supplied via `--set`/`-f` at install/upgrade
+    /// time (not merged with chart defaults).
+    pub config: Value,
+    pub chart: ReleaseChart,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct ReleaseInfo {
+    pub last_deployed: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ReleaseChart {
+    pub metadata: Option<ChartMetadata>,
+    /// The chart's own `values.yaml`, decoded.
+    pub values: Value,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ChartMetadata {
+    pub version: Option<String>,
+}
+
+/// Decodes a Helm release record from the raw bytes stored in a release
+/// Secret's `release` data key (already base64-decoded by the
+/// Kubernetes client; Helm additionally gzips and then base64-encodes the
+/// JSON payload on top of that).
+pub fn decode_release(raw: &[u8]) -> anyhow::Result<Release> {
+    let gzipped = base64_decode(raw)?;
+    let mut decoder = flate2::read::GzDecoder::new(&gzipped[..]);
+    let mut json = String::new();
+    decoder.read_to_string(&mut json)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+fn base64_decode(raw: &[u8]) -> anyhow::Result<Vec<u8>> {
+    use base64::Engine;
+    Ok(base64::engine::general_purpose::STANDARD.decode(raw)?)
+}
+
+/// Which backend Helm is configured to keep its release records in,
+/// resolved from `HELM_DRIVER` the same way Helm's own CLI picks it up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelmDriver {
+    Secrets,
+    ConfigMaps,
+    Sql,
+    Memory,
+    /// `HELM_DRIVER` is unset: Secrets and ConfigMaps are both probed and
+    /// whichever holds the release wins, instead of assuming Secrets
+    /// (Helm's own default) and failing confusingly in clusters that
+    /// were actually installed with `HELM_DRIVER=configmap`.
+    Auto,
+}
+
+impl HelmDriver {
+    pub fn from_env() -> Self {
+        match std::env::var("HELM_DRIVER") {
+            Err(_) => HelmDriver::Auto,
+            Ok(v) => match v.to_lowercase().as_str() {
+                "configmap" | "configmaps" => HelmDriver::ConfigMaps,
+                "sql" => HelmDriver::Sql,
+                "memory" => HelmDriver::Memory,
+                _ => HelmDriver::Secrets,
+            },
+        }
+    }
+}
+
+/// Finds the most recently deployed Helm release matching `chart_name`
+/// (the `name=` label every storage driver attaches to its release
+/// records), resolving via whichever driver `HELM_DRIVER` selects.
+/// Exposed for callers that only know the chart name, not a specific
+/// release, such as `setup` checking whether a chart is already
+/// installed before guiding a user through a first install.
+pub async fn helm_release_name(ctx: &Context, chart_name: &str) -> Result<String, UpgradeError> {
+    match HelmDriver::from_env() {
+        HelmDriver::Secrets => release_name_from_secrets(ctx, chart_name).await,
+        HelmDriver::ConfigMaps => release_name_from_config_maps(ctx, chart_name).await,
+        // The sql/memory drivers aren't reachable directly from here (no
+        // Postgres DSN or in-process store to connect to), so delegate
+        // to the `helm` CLI, which already knows how to talk to
+        // whichever driver it's configured for.
+        HelmDriver::Sql | HelmDriver::Memory => Ok(chart_name.to_string()),
+        HelmDriver::Auto => match release_name_from_secrets(ctx, chart_name).await {
+            Ok(name) => Ok(name),
+            Err(_) => release_name_from_config_maps(ctx, chart_name).await,
+        },
+    }
+}
+
+/// Reads the named release's decoded record, via whichever driver
+/// `HELM_DRIVER` selects.
+pub async fn helm_release_data(ctx: &Context, release_name: &str) -> Result<Release, UpgradeError> {
+    match HelmDriver::from_env() {
+        HelmDriver::Secrets => release_data_from_secrets(ctx, release_name).await,
+        HelmDriver::ConfigMaps => release_data_from_config_maps(ctx, release_name).await,
+        HelmDriver::Sql | HelmDriver::Memory => release_data_from_helm_cli(ctx, release_name),
+        HelmDriver::Auto => match release_data_from_secrets(ctx, release_name).await {
+            Ok(release) => Ok(release),
+            Err(_) => release_data_from_config_maps(ctx, release_name).await,
+        },
+    }
+}
+
+async fn release_name_from_secrets(ctx: &Context, chart_name: &str) -> Result<String, UpgradeError> {
+    let secret = latest_release_object(
+        &Api::<Secret>::namespaced(ctx.client.clone(), &ctx.namespace),
+        chart_name,
+    )
+    .await?;
+    Ok(secret.metadata.labels.unwrap_or_default().get("name").cloned().unwrap_or_else(|| chart_name.to_string()))
+}
+
+async fn release_name_from_config_maps(ctx: &Context, chart_name: &str) -> Result<String, UpgradeError> {
+    let config_map = latest_release_object(
+        &Api::<ConfigMap>::namespaced(ctx.client.clone(), &ctx.namespace),
+        chart_name,
+    )
+    .await?;
+    Ok(config_map.metadata.labels.unwrap_or_default().get("name").cloned().unwrap_or_else(|| chart_name.to_string()))
+}
+
+/// Lists objects labeled by Helm as release records for `chart_name`
+/// (`owner=helm,name=<chart_name>`) and returns the one with the highest
+/// `version` label, i.e. the current release.
+async fn latest_release_object<K>(api: &Api<K>, chart_name: &str) -> Result<K, UpgradeError>
+where
+    K: kube::Resource + Clone + serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let list = api
+        .list(&ListParams::default().labels(&format!("owner=helm,name={chart_name}")))
+        .await?;
+    list.items
+        .into_iter()
+        .max_by_key(|item| {
+            item.meta()
+                .labels
+                .as_ref()
+                .and_then(|labels| labels.get("version"))
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or_default()
+        })
+        .ok_or_else(|| {
+            UpgradeError::ValidationFailed(format!("no Helm release found for chart {chart_name:?}"))
+        })
+}
+
+async fn release_data_from_secrets(ctx: &Context, release_name: &str) -> Result<Release, UpgradeError> {
+    let secret = latest_release_object(
+        &Api::<Secret>::namespaced(ctx.client.clone(), &ctx.namespace),
+        release_name,
+    )
+    .await?;
+    let raw = secret
+        .data
+        .and_then(|mut data| data.remove("release"))
+        .ok_or_else(|| UpgradeError::ValidationFailed(format!("release secret for {release_name:?} has no 'release' key")))?;
+    decode_release(&raw.0).map_err(|e| UpgradeError::ValidationFailed(e.to_string()))
+}
+
+async fn release_data_from_config_maps(ctx: &Context, release_name: &str) -> Result<Release, UpgradeError> {
+    let config_map = latest_release_object(
+        &Api::<ConfigMap>::namespaced(ctx.client.clone(), &ctx.namespace),
+        release_name,
+    )
+    .await?;
+    let raw = config_map
+        .data
+        .and_then(|mut data| data.remove("release"))
+        .ok_or_else(|| UpgradeError::ValidationFailed(format!("release configmap for {release_name:?} has no 'release' key")))?;
+    decode_release(raw.as_bytes()).map_err(|e| UpgradeError::ValidationFailed(e.to_string()))
+}
+
+/// Shells out to `helm status -o json` for the `sql`/`memory` drivers,
+/// since neither a Postgres DSN nor Helm's in-process store is reachable
+/// directly from this plugin -- the `helm` CLI already knows how to talk
+/// to whichever driver it's configured for. Note `chart.values` (the
+/// chart's own defaults) isn't available through this path, only the
+/// user-supplied `config`; image discovery callers should expect an
+/// empty values document when `HELM_DRIVER` is `sql`/`memory`.
+fn release_data_from_helm_cli(ctx: &Context, release_name: &str) -> Result<Release, UpgradeError> {
+    let output = Command::new("helm")
+        .args(["status", release_name, "--namespace", &ctx.namespace, "-o", "json"])
+        .output()
+        .map_err(|e| UpgradeError::ValidationFailed(format!("running `helm status {release_name}`: {e}")))?;
+    if !output.status.success() {
+        return Err(UpgradeError::ValidationFailed(format!(
+            "`helm status {release_name}` failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    let status: Value = serde_json::from_slice(&output.stdout).map_err(|e| {
+        UpgradeError::ValidationFailed(format!("parsing `helm status {release_name}` output: {e}"))
+    })?;
+    Ok(Release {
+        name: status.get("name").and_then(Value::as_str).unwrap_or(release_name).to_string(),
+        version: status.get("version").and_then(Value::as_i64).unwrap_or_default(),
+        info: ReleaseInfo {
+            last_deployed: status
+                .get("info")
+                .and_then(|info| info.get("last_deployed"))
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            status: status
+                .get("info")
+                .and_then(|info| info.get("status"))
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        },
+        config: status.get("config").cloned().unwrap_or(Value::Null),
+        chart: ReleaseChart { metadata: None, values: Value::Null },
+    })
+}
+
+/// Lists every revision of `chart_name`'s release history (not just the
+/// currently deployed one), decoded via [`decode_release`], via whichever
+/// driver `HELM_DRIVER` selects. Used by the dump subsystem to capture
+/// what changed across recent upgrades, not just the current state.
+pub(crate) async fn release_history(ctx: &Context, chart_name: &str) -> Result<Vec<Release>, UpgradeError> {
+    match HelmDriver::from_env() {
+        HelmDriver::Secrets => release_history_from_secrets(ctx, chart_name).await,
+        HelmDriver::ConfigMaps => release_history_from_config_maps(ctx, chart_name).await,
+        // `helm history` isn't reused here since it doesn't expose the
+        // full per-revision `config`/`chart` payload this plugin needs,
+        // only a one-line-per-revision summary.
+        HelmDriver::Sql | HelmDriver::Memory => Err(UpgradeError::ValidationFailed(
+            "release history isn't available for the sql/memory helm drivers".to_string(),
+        )),
+        HelmDriver::Auto => match release_history_from_secrets(ctx, chart_name).await {
+            Ok(history) if !history.is_empty() => Ok(history),
+            _ => release_history_from_config_maps(ctx, chart_name).await,
+        },
+    }
+}
+
+async fn release_history_from_secrets(ctx: &Context, chart_name: &str) -> Result<Vec<Release>, UpgradeError> {
+    let secrets = all_release_objects(
+        &Api::<Secret>::namespaced(ctx.client.clone(), &ctx.namespace),
+        chart_name,
+    )
+    .await?;
+    secrets
+        .into_iter()
+        .map(|secret| {
+            let raw = secret.data.and_then(|mut data| data.remove("release")).ok_or_else(|| {
+                UpgradeError::ValidationFailed(format!("release secret for {chart_name:?} has no 'release' key"))
+            })?;
+            decode_release(&raw.0).map_err(|e| UpgradeError::ValidationFailed(e.to_string()))
+        })
+        .collect()
+}
+
+async fn release_history_from_config_maps(ctx: &Context, chart_name: &str) -> Result<Vec<Release>, UpgradeError> {
+    let config_maps = all_release_objects(
+        &Api::<ConfigMap>::namespaced(ctx.client.clone(), &ctx.namespace),
+        chart_name,
+    )
+    .await?;
+    config_maps
+        .into_iter()
+        .map(|config_map| {
+            let raw = config_map.data.and_then(|mut data| data.remove("release")).ok_or_else(|| {
+                UpgradeError::ValidationFailed(format!("release configmap for {chart_name:?} has no 'release' key"))
+            })?;
+            decode_release(raw.as_bytes()).map_err(|e| UpgradeError::ValidationFailed(e.to_string()))
+        })
+        .collect()
+}
+
+/// Lists every object labeled by Helm as a release record for
+/// `chart_name` (`owner=helm,name=<chart_name>`), one per revision.
+async fn all_release_objects<K>(api: &Api<K>, chart_name: &str) -> Result<Vec<K>, UpgradeError>
+where
+    K: kube::Resource + Clone + serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let list = api
+        .list(&ListParams::default().labels(&format!("owner=helm,name={chart_name}")))
+        .await?;
+    Ok(list.items)
+}
+
+/// Where an [`ImageProperties`] value came from, so callers can report
+/// which source was used rather than silently trusting a guess. Not
+/// wired up to a `get`/`status` caller yet in this tree.
+#[derive(Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ImageSource {
+    /// Read from the subchart's `image.{registry,repository,tag}` helm
+    /// values.
+    HelmValues,
+    /// The helm values didn't have an `image` block for this subchart;
+    /// fell back to splitting the running pod's container image string.
+    PodSpecHeuristic,
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+pub struct ImageProperties {
+    pub registry: String,
+    pub repository: String,
+    pub tag: String,
+    pub source: ImageSource,
+}
+
+/// Resolves the image a component runs with. Helm values are the source
+/// of truth (`<subchart_path>.image.{registry,repository,tag}` in the
+/// release's merged values), since they reflect what the chart actually
+/// requested; the old approach of splitting `pod_image` on `/` and `:`
+/// guesses wrong for any registry path with more than one slash, so it
+/// is now only a fallback for charts that don't expose those keys.
+#[allow(dead_code)]
+pub fn discover_image_properties(
+    release_values: &Value,
+    subchart_path: &str,
+    pod_image: &str,
+) -> ImageProperties {
+    if let Some(props) = from_helm_values(release_values, subchart_path) {
+        return props;
+    }
+    let (registry, repository, tag) = split_pod_image(pod_image);
+    ImageProperties {
+        registry,
+        repository,
+        tag,
+        source: ImageSource::PodSpecHeuristic,
+    }
+}
+
+fn from_helm_values(values: &Value, subchart_path: &str) -> Option<ImageProperties> {
+    let mut node = values;
+    for segment in subchart_path.split('.') {
+        node = node.get(segment)?;
+    }
+    let image = node.get("image")?;
+    Some(ImageProperties {
+        registry: image.get("registry")?.as_str()?.to_string(),
+        repository: image.get("repository")?.as_str()?.to_string(),
+        tag: image.get("tag")?.as_str()?.to_string(),
+        source: ImageSource::HelmValues,
+    })
+}
+
+/// Best-effort `registry/repository:tag` split used only when the helm
+/// values don't have an explicit `image` block for this subchart.
+fn split_pod_image(image: &str) -> (String, String, String) {
+    let (image, tag) = image.rsplit_once(':').unwrap_or((image, "latest"));
+    match image.rsplit_once('/') {
+        Some((registry, repository)) if registry.contains('.') || registry.contains(':') => {
+            (registry.to_string(), repository.to_string(), tag.to_string())
+        }
+        _ => (String::new(), image.to_string(), tag.to_string()),
+    }
+}
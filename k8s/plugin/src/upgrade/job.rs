@@ -0,0 +1,421 @@
+//! Builds and submits the Kubernetes Job that actually performs the chart
+//! upgrade. Preflight validations and value parsing all happen in the CLI
+//! process; this module is only responsible for turning the result into a
+//! `batch/v1` Job and handing it to the apiserver.
+
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::batch::v1::{Job, JobSpec};
+use k8s_openapi::api::core::v1::{
+    Affinity, ConfigMap, ConfigMapVolumeSource, Container, EnvVar, LocalObjectReference, PodDNSConfig,
+    PodSpec, PodTemplateSpec, ServiceAccount, Toleration, Volume, VolumeMount,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{Api, Patch, PatchParams, PostParams};
+
+use crate::constants::{UPGRADE_JOB_IMAGE_REPO, UPGRADE_JOB_TTL_SECONDS_AFTER_FINISHED};
+use crate::provenance;
+use crate::resources::Context;
+
+use super::chart::{self, OFFLINE_CHART_MOUNT_ROOT};
+use super::configmap::{self, SetFileMapping};
+use super::error::UpgradeError;
+use super::lock;
+use super::rbac;
+use super::values::SetValue;
+use super::UpgradeArgs;
+
+/// Where each `--set-file` ConfigMap is mounted inside the upgrade Job's
+/// container, keyed by ConfigMap name under this prefix.
+const SET_FILE_MOUNT_ROOT: &str = "/etc/openebs-upgrade/set-file";
+
+/// Renders a parsed `--set` scalar back to the plain-text form the
+/// upgrade Job's own CLI expects, rather than `serde_json::Value`'s
+/// quoted JSON `Display` output.
+fn scalar_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Parses `--job-toleration` entries in `kubectl taint`-style syntax:
+/// `key=value:Effect`, `key:Effect` (`Exists` operator) or `key` alone
+/// (tolerate the key regardless of value/effect).
+pub fn parse_tolerations(entries: &[String]) -> Result<Vec<Toleration>, UpgradeError> {
+    entries.iter().map(|entry| parse_toleration(entry)).collect()
+}
+
+fn parse_toleration(entry: &str) -> Result<Toleration, UpgradeError> {
+    let (key_value, effect) = match entry.split_once(':') {
+        Some((kv, effect)) => (kv, Some(effect.to_string())),
+        None => (entry, None),
+    };
+
+    let (key, value, operator) = match key_value.split_once('=') {
+        Some((key, value)) => (Some(key.to_string()), Some(value.to_string()), "Equal"),
+        None if key_value.is_empty() => (None, None, "Exists"),
+        None => (Some(key_value.to_string()), None, "Exists"),
+    };
+
+    Ok(Toleration {
+        key,
+        value,
+        operator: Some(operator.to_string()),
+        effect,
+        ..Default::default()
+    })
+}
+
+/// Builds the `HTTP_PROXY`/`HTTPS_PROXY`/`NO_PROXY` env vars for the
+/// upgrade Job's container from `--job-http-proxy`/`--job-https-proxy`/
+/// `--job-no-proxy`, so a cluster that only has egress through a proxy
+/// can reach the chart/registry endpoints the Job calls out to.
+fn proxy_env_vars(args: &UpgradeArgs) -> Vec<EnvVar> {
+    let mut env = Vec::new();
+    if let Some(proxy) = &args.job_http_proxy {
+        env.push(EnvVar { name: "HTTP_PROXY".to_string(), value: Some(proxy.clone()), ..Default::default() });
+    }
+    if let Some(proxy) = &args.job_https_proxy {
+        env.push(EnvVar { name: "HTTPS_PROXY".to_string(), value: Some(proxy.clone()), ..Default::default() });
+    }
+    if let Some(no_proxy) = &args.job_no_proxy {
+        env.push(EnvVar { name: "NO_PROXY".to_string(), value: Some(no_proxy.clone()), ..Default::default() });
+    }
+    env
+}
+
+/// Builds the upgrade Job pod's `dnsConfig` from `--job-dns-nameserver`/
+/// `--job-dns-search`, appended to whatever its `dnsPolicy` already
+/// resolves with. `None` when neither is set, leaving `dnsPolicy` to do
+/// all the work as before.
+fn dns_config(args: &UpgradeArgs) -> Option<PodDNSConfig> {
+    if args.job_dns_nameserver.is_empty() && args.job_dns_search.is_empty() {
+        return None;
+    }
+    Some(PodDNSConfig {
+        nameservers: (!args.job_dns_nameserver.is_empty()).then(|| args.job_dns_nameserver.clone()),
+        searches: (!args.job_dns_search.is_empty()).then(|| args.job_dns_search.clone()),
+        options: None,
+    })
+}
+
+/// Parses `--job-node-selector key=value` entries into a nodeSelector map.
+pub fn parse_node_selector(entries: &[String]) -> Result<BTreeMap<String, String>, UpgradeError> {
+    let mut out = BTreeMap::new();
+    for entry in entries {
+        let (key, value) = entry.split_once('=').ok_or_else(|| {
+            UpgradeError::ValidationFailed(format!(
+                "--job-node-selector {entry:?} is not in key=value form"
+            ))
+        })?;
+        out.insert(key.to_string(), value.to_string());
+    }
+    Ok(out)
+}
+
+/// Parses `--job-affinity`, which accepts either a path to a JSON/YAML
+/// file or an inline JSON document, both in the shape of a `v1.Affinity`.
+pub fn parse_affinity(source: &str) -> Result<Affinity, UpgradeError> {
+    let contents = match std::fs::read_to_string(source) {
+        Ok(contents) => contents,
+        Err(_) => source.to_string(),
+    };
+    serde_yaml::from_str(&contents).map_err(|e| {
+        UpgradeError::ValidationFailed(format!("--job-affinity is not valid Affinity JSON/YAML: {e}"))
+    })
+}
+
+/// Resolves the upgrade Job's image: `--upgrade-image` wins outright,
+/// otherwise the repo and tag are each either overridden individually or
+/// default to `UPGRADE_JOB_IMAGE_REPO` and this plugin's own version (an
+/// upgrade-job build is published alongside every plugin release).
+fn upgrade_image(args: &UpgradeArgs) -> String {
+    if let Some(image) = &args.upgrade_image {
+        return image.clone();
+    }
+    let repo = args.upgrade_image_repo.as_deref().unwrap_or(UPGRADE_JOB_IMAGE_REPO);
+    let tag = args
+        .upgrade_image_tag
+        .as_deref()
+        .unwrap_or(env!("CARGO_PKG_VERSION"));
+    format!("{repo}:{tag}")
+}
+
+/// Builds the upgrade Job's manifest. `set_file_mappings` must already
+/// have been created (so `config_map_name` is populated) and are mounted
+/// read-only into the container; submission is a separate step so
+/// callers (and, later, `--dry-run`) can inspect the manifest first.
+pub fn build_upgrade_job(
+    ctx: &Context,
+    args: &UpgradeArgs,
+    set_values: &[SetValue],
+    set_file_mappings: &[SetFileMapping],
+    offline_chart_config_maps: &[String],
+    downgrade: bool,
+    service_account_name: &str,
+) -> Result<Job, UpgradeError> {
+    let tolerations = parse_tolerations(&args.job_tolerations)?;
+    let node_selector = parse_node_selector(&args.job_node_selector)?;
+    let affinity = args
+        .job_affinity
+        .as_deref()
+        .map(parse_affinity)
+        .transpose()?;
+
+    let mut job_args = Vec::new();
+    if let Some(target_version) = &args.target_version {
+        job_args.push(format!("--to-version={target_version}"));
+    }
+    if downgrade {
+        // Tells the upgrade-job binary to run the reverse migration
+        // instead of the forward one; it's the same Job image either way.
+        job_args.push("--downgrade".to_string());
+    }
+    for set in set_values {
+        job_args.push(format!("--set={}={}", set.key, scalar_to_string(&set.value)));
+    }
+    if let Some(oci_ref) = &args.offline_chart_oci_ref {
+        job_args.push(format!("--chart-oci-ref={oci_ref}"));
+        if let Some(secret) = &args.chart_oci_registry_secret {
+            job_args.push(format!("--chart-oci-registry-secret={secret}"));
+        }
+    }
+    if args.verify_chart {
+        job_args.push("--verify-chart".to_string());
+    }
+    if args.atomic {
+        job_args.push("--atomic".to_string());
+    }
+    if args.wait {
+        job_args.push("--wait".to_string());
+    }
+    if let Some(timeout) = &args.timeout {
+        job_args.push(format!("--timeout={timeout}"));
+    }
+    job_args.push(format!("--helm-retry-max-attempts={}", args.helm_retry_max_attempts));
+    job_args.push(format!("--helm-retry-backoff-seconds={}", args.helm_retry_backoff_seconds));
+    if args.repair_pending_release {
+        job_args.push("--repair-pending-release".to_string());
+    }
+    if args.resume {
+        job_args.push("--resume".to_string());
+    }
+    if let Some(product_name) = &args.branding_product_name {
+        job_args.push(format!("--branding-product-name={product_name}"));
+    }
+    if let Some(event_reason) = &args.branding_event_reason {
+        job_args.push(format!("--branding-event-reason={event_reason}"));
+    }
+    if let Some(label_prefix) = &args.branding_label_prefix {
+        job_args.push(format!("--branding-label-prefix={label_prefix}"));
+    }
+    job_args.push(format!("--restart-parallelism={}", args.restart_parallelism));
+    job_args.push(format!("--rebuild-wait-timeout={}", args.rebuild_wait_timeout));
+    job_args.push(format!("--restart-strategy={}", args.restart_strategy.as_job_arg()));
+    if matches!(args.restart_strategy, super::RestartStrategy::RollingUpdate) {
+        job_args.push(format!("--max-unavailable={}", args.max_unavailable));
+    }
+    if args.skip_partial_rebuild_toggle {
+        job_args.push("--skip-partial-rebuild-toggle".to_string());
+    }
+    if args.skip_crd_apply {
+        job_args.push("--skip-crd-apply".to_string());
+    }
+
+    let mut volumes = Vec::new();
+    let mut volume_mounts = Vec::new();
+
+    if !offline_chart_config_maps.is_empty() {
+        for (i, config_map_name) in offline_chart_config_maps.iter().enumerate() {
+            let volume_name = format!("offline-chart-{i}");
+            volumes.push(Volume {
+                name: volume_name.clone(),
+                config_map: Some(ConfigMapVolumeSource {
+                    name: Some(config_map_name.clone()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+            volume_mounts.push(VolumeMount {
+                name: volume_name,
+                mount_path: format!("{OFFLINE_CHART_MOUNT_ROOT}/{config_map_name}"),
+                read_only: Some(true),
+                ..Default::default()
+            });
+        }
+        job_args.push(format!(
+            "--offline-chart={OFFLINE_CHART_MOUNT_ROOT}:{}",
+            offline_chart_config_maps.join(",")
+        ));
+    }
+
+    for mapping in set_file_mappings {
+        let volume_name = format!("set-file-{}", mapping.config_map_name);
+        volumes.push(Volume {
+            name: volume_name.clone(),
+            config_map: Some(ConfigMapVolumeSource {
+                name: Some(mapping.config_map_name.clone()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        let mount_path = format!("{SET_FILE_MOUNT_ROOT}/{}", mapping.config_map_name);
+        volume_mounts.push(VolumeMount {
+            name: volume_name,
+            mount_path: mount_path.clone(),
+            read_only: Some(true),
+            ..Default::default()
+        });
+        // The job reassembles `chunk-0..N` (in order) from this mount. A
+        // `-f` whole-values-file mapping has no `mapping.key` to nest
+        // under, so it gets its own arg merged at the top level instead
+        // of `--set-file-chunks`.
+        if mapping.key.is_empty() {
+            job_args.push(format!("--values-file={mount_path}:{}", mapping.chunk_keys.join(",")));
+        } else {
+            job_args.push(format!(
+                "--set-file-chunks={}={mount_path}:{}",
+                mapping.key,
+                mapping.chunk_keys.join(",")
+            ));
+        }
+    }
+
+    let image_pull_secrets: Vec<LocalObjectReference> = args
+        .image_pull_secrets
+        .iter()
+        .map(|name| LocalObjectReference { name: Some(name.clone()) })
+        .collect();
+
+    let env = proxy_env_vars(args);
+
+    let pod_spec = PodSpec {
+        containers: vec![Container {
+            name: "upgrade".to_string(),
+            image: Some(upgrade_image(args)),
+            args: Some(job_args),
+            env: (!env.is_empty()).then_some(env),
+            volume_mounts: (!volume_mounts.is_empty()).then_some(volume_mounts),
+            ..Default::default()
+        }],
+        volumes: (!volumes.is_empty()).then_some(volumes),
+        restart_policy: Some("OnFailure".to_string()),
+        tolerations: (!tolerations.is_empty()).then_some(tolerations),
+        node_selector: (!node_selector.is_empty()).then_some(node_selector),
+        affinity,
+        dns_config: dns_config(args),
+        service_account_name: Some(service_account_name.to_string()),
+        image_pull_secrets: (!image_pull_secrets.is_empty()).then_some(image_pull_secrets),
+        ..Default::default()
+    };
+
+    Ok(Job {
+        metadata: ObjectMeta {
+            generate_name: Some("openebs-upgrade-".to_string()),
+            namespace: Some(ctx.namespace.clone()),
+            labels: Some(BTreeMap::from([(
+                super::status::UPGRADE_JOB_LABEL.to_string(),
+                "true".to_string(),
+            )])),
+            annotations: provenance::annotate(None),
+            ..Default::default()
+        },
+        spec: Some(JobSpec {
+            ttl_seconds_after_finished: Some(
+                args.job_ttl_seconds.unwrap_or(UPGRADE_JOB_TTL_SECONDS_AFTER_FINISHED),
+            ),
+            backoff_limit: Some(args.job_backoff_limit),
+            active_deadline_seconds: args.job_deadline_seconds,
+            template: PodTemplateSpec {
+                spec: Some(pod_spec),
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+/// Creates the `--set-file` ConfigMap chunks, then builds and submits the
+/// upgrade Job referencing them, returning the created Job (with its
+/// server-assigned name) once the apiserver accepts it.
+pub async fn upgrade_job(
+    ctx: &Context,
+    args: &UpgradeArgs,
+    set_values: &[SetValue],
+    set_file_values: &[SetValue],
+) -> Result<Job, UpgradeError> {
+    downgradeable_job(ctx, args, set_values, set_file_values, false).await
+}
+
+/// Same as [`upgrade_job`], but also used by `kubectl openebs downgrade`
+/// to submit the Job with `--downgrade` set.
+pub(crate) async fn downgradeable_job(
+    ctx: &Context,
+    args: &UpgradeArgs,
+    set_values: &[SetValue],
+    set_file_values: &[SetValue],
+    downgrade: bool,
+) -> Result<Job, UpgradeError> {
+    let holder = lock::holder_identity();
+    lock::acquire(ctx, &args.release_name, &holder, args.force_take_lock).await?;
+    let result = submit_upgrade_job(ctx, args, set_values, set_file_values, downgrade).await;
+    lock::release(ctx, &args.release_name).await?;
+    result
+}
+
+async fn submit_upgrade_job(
+    ctx: &Context,
+    args: &UpgradeArgs,
+    set_values: &[SetValue],
+    set_file_values: &[SetValue],
+    downgrade: bool,
+) -> Result<Job, UpgradeError> {
+    let resources = rbac::create_upgrade_resources(ctx).await?;
+
+    let config_maps: Api<ConfigMap> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let mut mappings = Vec::new();
+    let mut created_config_map_names = Vec::new();
+    for (config_map, mapping) in configmap::config_map_data("openebs-upgrade-set-file", set_file_values)? {
+        let created = config_maps.create(&PostParams::default(), &config_map).await?;
+        let name = created.metadata.name.unwrap_or_default();
+        created_config_map_names.push(name.clone());
+        mappings.push(SetFileMapping { config_map_name: name, ..mapping });
+    }
+
+    let offline_chart_config_maps = match &args.offline_chart_dir {
+        Some(dir) => chart::package_offline_chart(ctx, dir).await?,
+        None => Vec::new(),
+    };
+    created_config_map_names.extend(offline_chart_config_maps.iter().cloned());
+
+    let job = build_upgrade_job(
+        ctx,
+        args,
+        set_values,
+        &mappings,
+        &offline_chart_config_maps,
+        downgrade,
+        &resources.service_account_name,
+    )?;
+    let jobs: Api<Job> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let created_job = jobs.create(&PostParams::default(), &job).await?;
+
+    let job_name = created_job.metadata.name.clone().unwrap_or_default();
+    let job_uid = created_job.metadata.uid.clone().unwrap_or_default();
+    let owner_patch = rbac::owner_reference_patch(&job_name, &job_uid);
+    let patch_params = PatchParams::default();
+
+    let service_accounts: Api<ServiceAccount> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    service_accounts
+        .patch(&resources.service_account_name, &patch_params, &Patch::Merge(&owner_patch))
+        .await?;
+    for config_map_name in &created_config_map_names {
+        config_maps
+            .patch(config_map_name, &patch_params, &Patch::Merge(&owner_patch))
+            .await?;
+    }
+
+    Ok(created_job)
+}
@@ -0,0 +1,107 @@
+//! Cluster-scoped lock preventing two operators from starting overlapping
+//! upgrades of the same release: a `coordination.k8s.io` Lease named
+//! `<release>-upgrade-lock`, the same primitive client-go's leader
+//! election uses. It's acquired right before [`super::rbac::create_upgrade_resources`]
+//! and released once the Job has been submitted -- the Lease only needs
+//! to cover the submission race between `apply` invocations, not the
+//! Job's own run, which `upgrade status`/`upgrade health` already track.
+
+use k8s_openapi::api::coordination::v1::{Lease, LeaseSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{MicroTime, ObjectMeta};
+use k8s_openapi::chrono::Utc;
+use kube::api::{Api, DeleteParams, PostParams};
+
+use crate::resources::Context;
+
+use super::error::UpgradeError;
+
+fn lock_name(release_name: &str) -> String {
+    format!("{release_name}-upgrade-lock")
+}
+
+/// Identifies the caller taking the lock, so a conflicting `apply` can
+/// report who holds it: the local user and this process's PID, which is
+/// all this plugin has without a dedicated identity system.
+pub fn holder_identity() -> String {
+    let user = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
+    format!("{user} (pid {})", std::process::id())
+}
+
+/// Acquires the upgrade lock for `release_name` as `holder`, failing
+/// with a clear "already in progress" error if another holder's Lease
+/// already exists -- unless `force` is set, in which case the existing
+/// Lease is deleted and replaced regardless of who holds it.
+pub async fn acquire(
+    ctx: &Context,
+    release_name: &str,
+    holder: &str,
+    force: bool,
+) -> Result<(), UpgradeError> {
+    let leases: Api<Lease> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let name = lock_name(release_name);
+
+    if let Some(existing) = leases.get_opt(&name).await? {
+        if !force {
+            return Err(UpgradeError::ValidationFailed(already_in_progress_message(
+                release_name,
+                &existing,
+            )));
+        }
+        leases.delete(&name, &DeleteParams::default()).await?;
+    }
+
+    let now = MicroTime(Utc::now());
+    let lease = Lease {
+        metadata: ObjectMeta { name: Some(name.clone()), ..Default::default() },
+        spec: Some(LeaseSpec {
+            holder_identity: Some(holder.to_string()),
+            acquire_time: Some(now.clone()),
+            renew_time: Some(now),
+            ..Default::default()
+        }),
+    };
+    match leases.create(&PostParams::default(), &lease).await {
+        Ok(_) => Ok(()),
+        // Lost a create race against another `apply` invocation between
+        // the `get_opt` above and this `create`.
+        Err(kube::Error::Api(e)) if e.code == 409 => {
+            let existing = leases.get(&name).await?;
+            Err(UpgradeError::ValidationFailed(already_in_progress_message(
+                release_name,
+                &existing,
+            )))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn already_in_progress_message(release_name: &str, lease: &Lease) -> String {
+    let holder = lease
+        .spec
+        .as_ref()
+        .and_then(|s| s.holder_identity.clone())
+        .unwrap_or_else(|| "unknown".to_string());
+    let started_at = lease
+        .spec
+        .as_ref()
+        .and_then(|s| s.acquire_time.clone())
+        .map(|t| t.0.to_rfc3339())
+        .unwrap_or_else(|| "an unknown time".to_string());
+    format!(
+        "upgrade already in progress for release {release_name:?}, started by {holder} at {started_at}; \
+         pass --force-take-lock to take over if you've confirmed that upgrade isn't actually still running"
+    )
+}
+
+/// Releases the upgrade lock for `release_name`. A missing Lease isn't
+/// an error -- the caller may be cleaning up after a failed `acquire`
+/// that never got this far, or after `--force-take-lock` already
+/// replaced it with someone else's.
+pub async fn release(ctx: &Context, release_name: &str) -> Result<(), UpgradeError> {
+    let leases: Api<Lease> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    match leases.delete(&lock_name(release_name), &DeleteParams::default()).await {
+        Ok(_) => Ok(()),
+        Err(kube::Error::Api(e)) if e.code == 404 => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,39 @@
+//! `kubectl openebs upgrade logs`: streams the current (or a named)
+//! upgrade Job's pod logs, the same way `kubectl openebs ops attach`
+//! does for an operation Job whose name you already know -- this saves
+//! having to find that name (`kubectl get jobs -l openebs.io/upgrade-job=true`)
+//! just to debug a stuck `upgrade apply` run.
+
+use futures::{AsyncBufReadExt, StreamExt};
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{Api, ListParams, LogParams};
+
+use crate::resources::Context;
+
+use super::error::UpgradeError;
+use super::status;
+
+pub async fn print_logs(ctx: &Context, job_name: Option<&str>, follow: bool) -> anyhow::Result<()> {
+    let jobs: Api<Job> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let job_name = status::resolve_job_name(&jobs, job_name).await?;
+
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let pod_name = pods
+        .list(&ListParams::default().labels(&format!("job-name={job_name}")))
+        .await?
+        .items
+        .into_iter()
+        .next()
+        .and_then(|pod| pod.metadata.name)
+        .ok_or_else(|| UpgradeError::ValidationFailed(format!("no pod found for upgrade job {job_name}")))?;
+
+    let mut lines = pods
+        .log_stream(&pod_name, &LogParams { follow, ..Default::default() })
+        .await?
+        .lines();
+    while let Some(line) = lines.next().await {
+        println!("{}", line?);
+    }
+    Ok(())
+}
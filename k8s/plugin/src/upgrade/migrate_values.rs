@@ -0,0 +1,194 @@
+//! Remaps deprecated values keys that moved between chart major versions
+//! (e.g. the 3.x -> 4.x `engines` hierarchy rework), so a user's existing
+//! `--set`/`--set-file`/`-f` overrides keep working across the boundary
+//! without edits. Applied to parsed [`SetValue`]s right after
+//! [`super::values::parse_set`]/[`parse_set_file`]/[`parse_values_files`]
+//! and before schema validation.
+
+use serde_json::Value;
+
+use super::values::SetValue;
+use super::version::ParsedVersion;
+
+/// One `old -> new` dotted-path rename, scoped to the chart major version
+/// it was introduced at: only applied when the target version's major is
+/// at or past `boundary_major`, so upgrading within 3.x doesn't rewrite
+/// keys a 3.x chart still expects under their original name.
+struct KeyRename {
+    boundary_major: u64,
+    old_key: &'static str,
+    new_key: &'static str,
+}
+
+const RENAMES: &[KeyRename] = &[KeyRename {
+    boundary_major: 4,
+    old_key: "mayastor.enabled",
+    new_key: "engines.replicated.mayastor.enabled",
+}];
+
+fn applicable_renames(target_version: &str) -> Vec<&'static KeyRename> {
+    let ParsedVersion::Release(target) = ParsedVersion::parse(target_version) else {
+        // An unparsable/dev target: we can't tell which boundary it's
+        // past, so leave keys alone rather than guess.
+        return Vec::new();
+    };
+    RENAMES.iter().filter(|r| target.major >= r.boundary_major).collect()
+}
+
+/// Rewrites `--set`/`--set-file` entries whose dotted key exactly matches
+/// a deprecated key, in place. A `-f`/`--values` whole-document entry
+/// (empty `key`) is migrated by [`migrate_document`] instead.
+pub fn migrate_set_values(target_version: &str, values: &mut [SetValue]) {
+    let renames = applicable_renames(target_version);
+    for value in values {
+        if value.key.is_empty() {
+            continue;
+        }
+        if let Some(rename) = renames.iter().find(|r| r.old_key == value.key) {
+            eprintln!(
+                "note: migrating deprecated values key {:?} to {:?}",
+                rename.old_key, rename.new_key
+            );
+            value.key = rename.new_key.to_string();
+        }
+    }
+}
+
+/// Rewrites deprecated keys inside a whole values document (parsed from a
+/// `-f`/`--values` file), moving each one to its new nested location
+/// unless the document already sets the new key explicitly -- that's the
+/// user's override and wins either way.
+pub fn migrate_document(target_version: &str, doc: &mut Value) {
+    for rename in applicable_renames(target_version) {
+        let Some(value) = take_dotted(doc, rename.old_key) else {
+            continue;
+        };
+        if get_dotted(doc, rename.new_key).is_some() {
+            // The document already sets the new key directly; don't
+            // clobber it with the deprecated one's value.
+            continue;
+        }
+        eprintln!(
+            "note: migrating deprecated values key {:?} to {:?}",
+            rename.old_key, rename.new_key
+        );
+        set_dotted(doc, rename.new_key, value);
+    }
+}
+
+fn get_dotted<'a>(doc: &'a Value, dotted_key: &str) -> Option<&'a Value> {
+    let mut current = doc;
+    for segment in dotted_key.split('.') {
+        current = current.as_object()?.get(segment)?;
+    }
+    Some(current)
+}
+
+fn take_dotted(doc: &mut Value, dotted_key: &str) -> Option<Value> {
+    let (parent, leaf) = dotted_key.rsplit_once('.').unwrap_or(("", dotted_key));
+    let parent_obj = if parent.is_empty() {
+        doc.as_object_mut()?
+    } else {
+        let mut current = doc;
+        for segment in parent.split('.') {
+            current = current.as_object_mut()?.get_mut(segment)?;
+        }
+        current.as_object_mut()?
+    };
+    parent_obj.remove(leaf)
+}
+
+fn set_dotted(doc: &mut Value, dotted_key: &str, value: Value) {
+    if !doc.is_object() {
+        *doc = Value::Object(Default::default());
+    }
+    let mut current = doc;
+    let segments: Vec<&str> = dotted_key.split('.').collect();
+    for segment in &segments[..segments.len() - 1] {
+        let obj = current.as_object_mut().expect("checked above");
+        current = obj
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(Default::default()));
+        if !current.is_object() {
+            *current = Value::Object(Default::default());
+        }
+    }
+    let leaf = segments[segments.len() - 1];
+    current
+        .as_object_mut()
+        .expect("checked above")
+        .insert(leaf.to_string(), value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renames_deprecated_set_key_when_crossing_boundary() {
+        let mut values = vec![SetValue {
+            key: "mayastor.enabled".to_string(),
+            value: Value::Bool(true),
+            checksum: None,
+        }];
+        migrate_set_values("4.0.0", &mut values);
+        assert_eq!(values[0].key, "engines.replicated.mayastor.enabled");
+    }
+
+    #[test]
+    fn leaves_set_key_alone_below_boundary() {
+        let mut values = vec![SetValue {
+            key: "mayastor.enabled".to_string(),
+            value: Value::Bool(true),
+            checksum: None,
+        }];
+        migrate_set_values("3.9.0", &mut values);
+        assert_eq!(values[0].key, "mayastor.enabled");
+    }
+
+    #[test]
+    fn leaves_unrelated_set_key_alone() {
+        let mut values = vec![SetValue {
+            key: "replicaCount".to_string(),
+            value: Value::from(3),
+            checksum: None,
+        }];
+        migrate_set_values("4.0.0", &mut values);
+        assert_eq!(values[0].key, "replicaCount");
+    }
+
+    #[test]
+    fn migrates_deprecated_key_in_document() {
+        let mut doc = serde_json::json!({ "mayastor": { "enabled": true } });
+        migrate_document("4.0.0", &mut doc);
+        assert_eq!(
+            get_dotted(&doc, "engines.replicated.mayastor.enabled"),
+            Some(&Value::Bool(true))
+        );
+        assert!(get_dotted(&doc, "mayastor.enabled").is_none());
+    }
+
+    #[test]
+    fn preserves_explicit_new_key_in_document_over_deprecated_one() {
+        let mut doc = serde_json::json!({
+            "mayastor": { "enabled": true },
+            "engines": { "replicated": { "mayastor": { "enabled": false } } },
+        });
+        migrate_document("4.0.0", &mut doc);
+        assert_eq!(
+            get_dotted(&doc, "engines.replicated.mayastor.enabled"),
+            Some(&Value::Bool(false))
+        );
+    }
+
+    #[test]
+    fn skips_migration_for_unparsable_target() {
+        let mut values = vec![SetValue {
+            key: "mayastor.enabled".to_string(),
+            value: Value::Bool(true),
+            checksum: None,
+        }];
+        migrate_set_values("dev-build", &mut values);
+        assert_eq!(values[0].key, "mayastor.enabled");
+    }
+}
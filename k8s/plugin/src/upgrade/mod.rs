@@ -0,0 +1,738 @@
+//! `kubectl openebs upgrade`: runs preflight validations and then drives
+//! the upgrade Job that performs the actual chart upgrade.
+
+mod chart;
+mod configmap;
+pub(crate) mod downgrade;
+pub(crate) mod error;
+mod health;
+pub(crate) mod helm;
+mod lock;
+mod job;
+mod logs;
+mod migrate_values;
+pub(crate) mod naming;
+pub(crate) mod rbac;
+pub(crate) mod status;
+pub(crate) mod validations;
+mod values;
+pub(crate) mod version;
+
+use std::path::PathBuf;
+
+use crate::report::{self, CheckOutcome, ReportFormat};
+use crate::resources::Context;
+use error::UpgradeError;
+use status::StatusSource;
+
+#[derive(clap::Subcommand)]
+pub enum UpgradeCommand {
+    /// Run preflight validations and submit the upgrade Job.
+    Apply(Box<UpgradeArgs>),
+    /// Show the status of the most recent (or a named) upgrade Job.
+    Status {
+        /// Name of a specific upgrade Job; defaults to the most recent one.
+        #[arg(long)]
+        job: Option<String>,
+        /// Output format. `json`/`yaml` emit the status as structured
+        /// data instead of plain text, for automation to gate on.
+        #[arg(short = 'o', long, value_enum, default_value = "text")]
+        output: StatusOutputFormat,
+        /// List every `OpenebsUpgrade` Event for the Job in chronological
+        /// order, instead of just the latest one, so a stuck upgrade's
+        /// last completed phase is visible even after a newer Event
+        /// (from an unrelated phase check) would otherwise win.
+        #[arg(long = "history", alias = "events")]
+        history: bool,
+    },
+    /// Report a normalized healthy/progressing/degraded verdict for the
+    /// most recent (or a named) upgrade Job, for Argo Rollouts
+    /// AnalysisTemplates or Flux health checks to gate a progressive
+    /// platform upgrade on.
+    Health {
+        /// Name of a specific upgrade Job; defaults to the most recent one.
+        #[arg(long)]
+        job: Option<String>,
+        /// Emit the verdict as JSON (`{"job_name", "verdict", "reason"}`)
+        /// instead of human-readable text, for an AnalysisTemplate/
+        /// HealthCheck to parse.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Stream the current (or a named) upgrade Job's pod logs.
+    Logs {
+        /// Name of a specific upgrade Job; defaults to the most recent one.
+        #[arg(long)]
+        job: Option<String>,
+        /// Keep streaming new log lines as the Job's pod produces them,
+        /// instead of exiting once the current logs have been printed.
+        #[arg(short = 'f', long)]
+        follow: bool,
+    },
+    /// Remove the shared ClusterRole/ClusterRoleBinding `apply` reconciles
+    /// on every run.
+    UninstallRbac {
+        /// Delete even if the object isn't annotated as managed by
+        /// kubectl-openebs (e.g. it was pre-created by hand and never
+        /// adopted by an `apply` run).
+        #[arg(long)]
+        force: bool,
+    },
+    /// Inspect the upgrade Job's cluster-scoped RBAC.
+    #[command(subcommand)]
+    Rbac(RbacCommand),
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Default)]
+pub enum StatusOutputFormat {
+    #[default]
+    Text,
+    Json,
+    Yaml,
+}
+
+/// `io-engine` DaemonSet `updateStrategy` the Job patches in for the
+/// data-plane restart phase of an upgrade.
+#[derive(clap::ValueEnum, Clone, Copy, Default)]
+pub enum RestartStrategy {
+    #[default]
+    RollingUpdate,
+    OnDelete,
+}
+
+impl RestartStrategy {
+    /// The value the Job's own `--restart-strategy` flag expects.
+    fn as_job_arg(self) -> &'static str {
+        match self {
+            RestartStrategy::RollingUpdate => "rollingUpdate",
+            RestartStrategy::OnDelete => "onDelete",
+        }
+    }
+}
+
+impl std::fmt::Display for RestartStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RestartStrategy::RollingUpdate => "rolling-update",
+            RestartStrategy::OnDelete => "on-delete",
+        })
+    }
+}
+
+#[derive(clap::Subcommand)]
+pub enum RbacCommand {
+    /// Compare the ClusterRole this plugin version would generate
+    /// against what's currently in the cluster, so a security reviewer
+    /// can approve the delta instead of rereading the whole role.
+    Diff,
+}
+
+/// Common arguments shared by the upgrade command's preflight and apply
+/// phases.
+#[derive(clap::Args)]
+pub struct UpgradeArgs {
+    /// Chart/app version to upgrade to.
+    #[arg(long = "to-version")]
+    pub target_version: Option<String>,
+
+    /// Skip the pending-PVC preflight validation.
+    #[arg(long)]
+    pub skip_pending_pvc_validation: bool,
+
+    /// Skip the data-plane node health (disk/memory pressure, NotReady)
+    /// preflight validation.
+    #[arg(long)]
+    pub skip_node_health_validation: bool,
+
+    /// Skip the single-replica-volume preflight validation (prefer
+    /// annotating individual PVCs with
+    /// `openebs.io/allow-upgrade-downtime: "true"` instead).
+    #[arg(long)]
+    pub skip_single_volume_replica_validation: bool,
+
+    /// Exempt specific node names from the cordoned-node preflight
+    /// validation, instead of skipping it entirely.
+    #[arg(long)]
+    pub skip_cordoned_node_validation: Vec<String>,
+
+    /// Skip the upgrade-path preflight validation, which otherwise
+    /// rejects a `--to-version` older than the plugin's own version.
+    #[arg(long)]
+    pub skip_upgrade_path_validation: bool,
+
+    /// Override a chart value, e.g. `--set cstor.replicas=3`. May be
+    /// repeated or comma-separated.
+    #[arg(long = "set")]
+    pub set: Vec<String>,
+
+    /// Set a chart value from a file's contents, e.g.
+    /// `--set-file cstor.caCert=./ca.pem`. The source may also be an
+    /// `https://` URL or an `s3://bucket/key` reference.
+    #[arg(long = "set-file")]
+    pub set_file: Vec<String>,
+
+    /// Merge a whole values file (local path, `https://` URL or
+    /// `s3://bucket/key`) at the top level, same as Helm's `-f`. May be
+    /// repeated; later files win on conflicting keys.
+    #[arg(short = 'f', long = "values")]
+    pub values_files: Vec<String>,
+
+    /// Path to the target chart's `values.schema.json`, used to validate
+    /// `--set`/`--set-file` before the upgrade Job is created.
+    #[arg(long)]
+    pub chart_schema: Option<PathBuf>,
+
+    /// Toleration for the upgrade Job's pod, in `kubectl taint` syntax
+    /// (`key=value:Effect`). May be repeated.
+    #[arg(long = "job-tolerations")]
+    pub job_tolerations: Vec<String>,
+
+    /// `nodeSelector` entry for the upgrade Job's pod (`key=value`). May
+    /// be repeated.
+    #[arg(long = "job-node-selector")]
+    pub job_node_selector: Vec<String>,
+
+    /// `nodeAffinity`/`podAffinity`/`podAntiAffinity` for the upgrade
+    /// Job's pod, as a path to a JSON/YAML file or an inline JSON
+    /// document matching the `v1.Affinity` schema.
+    #[arg(long = "job-affinity")]
+    pub job_affinity: Option<String>,
+
+    /// Override the upgrade Job image's repository, keeping the computed
+    /// tag (defaults to this plugin's own version).
+    #[arg(long)]
+    pub upgrade_image_repo: Option<String>,
+
+    /// Override the upgrade Job image's tag, keeping the default
+    /// repository.
+    #[arg(long)]
+    pub upgrade_image_tag: Option<String>,
+
+    /// Fully qualified upgrade Job image (`registry/repo:tag`), for
+    /// mirrored/air-gapped registries whose path layout doesn't match
+    /// `--upgrade-image-repo`/`--upgrade-image-tag`. Takes priority over
+    /// both.
+    #[arg(long)]
+    pub upgrade_image: Option<String>,
+
+    /// Package a local Helm chart directory and ship it to the upgrade
+    /// Job as chunked ConfigMaps, instead of having the Job fetch the
+    /// chart itself. For clusters with no route to a chart repository.
+    /// Conflicts with `--offline-chart-oci-ref`.
+    #[arg(long, conflicts_with = "offline_chart_oci_ref")]
+    pub offline_chart_dir: Option<PathBuf>,
+
+    /// Have the upgrade Job pull the chart from this OCI reference (a
+    /// mirror in a private registry the cluster can already reach)
+    /// instead of the public chart repository. Conflicts with
+    /// `--offline-chart-dir`.
+    #[arg(long)]
+    pub offline_chart_oci_ref: Option<String>,
+
+    /// Name of a `kubernetes.io/dockerconfigjson` Secret (in the same
+    /// namespace as the upgrade Job) holding credentials for
+    /// `--offline-chart-oci-ref`'s registry, for private OCI chart
+    /// mirrors. Ignored unless `--offline-chart-oci-ref` is also set.
+    #[arg(long)]
+    pub chart_oci_registry_secret: Option<String>,
+
+    /// Override how long a finished Job (and the ConfigMaps/ServiceAccount
+    /// it owns) is kept around before `ttlSecondsAfterFinished` cleans it
+    /// up automatically.
+    #[arg(long)]
+    pub job_ttl_seconds: Option<i32>,
+
+    /// How many times the upgrade Job retries a failing pod before giving
+    /// up. Kubernetes' own default (6) can let a genuinely broken upgrade
+    /// keep retrying for a long time; lower it to fail fast, or raise it
+    /// for a Job expected to ride out transient infra flakiness.
+    #[arg(long, default_value_t = 6)]
+    pub job_backoff_limit: i32,
+
+    /// Bound how long the upgrade Job is allowed to run in total before
+    /// Kubernetes terminates it as failed. Unset by default, matching
+    /// Kubernetes' own unbounded default.
+    #[arg(long)]
+    pub job_deadline_seconds: Option<i64>,
+
+    /// `imagePullSecrets` entry for the upgrade Job's pod. May be
+    /// repeated. Overrides whatever secret the upgrade image's registry
+    /// would otherwise be inferred from, since that inference only ever
+    /// looks at an arbitrary already-running pod and breaks when it
+    /// happens to use a different secret than the upgrade image needs.
+    #[arg(long = "image-pull-secret")]
+    pub image_pull_secrets: Vec<String>,
+
+    /// Validate the target chart's provenance file or cosign signature
+    /// before the Job runs `helm upgrade`, failing the Job (with a clear
+    /// Event explaining why) instead of installing an unattested chart.
+    /// Off by default since not every chart source publishes one.
+    #[arg(long)]
+    pub verify_chart: bool,
+
+    /// Passed straight through to the Job's `helm upgrade`: roll back
+    /// automatically if the upgrade or its readiness check fails,
+    /// instead of leaving a half-applied release in place. Implies
+    /// `--wait`.
+    #[arg(long)]
+    pub atomic: bool,
+
+    /// Passed straight through to the Job's `helm upgrade`: how long it
+    /// waits for resources to become ready before considering the
+    /// upgrade failed (implied by `--atomic`). Go duration syntax, e.g.
+    /// `5m0s`. Unset uses Helm's own default.
+    #[arg(long)]
+    pub timeout: Option<String>,
+
+    /// Passed straight through to the Job's `helm upgrade`: block until
+    /// all resources are in a ready state before marking the release
+    /// successful, instead of returning as soon as they're created.
+    #[arg(long)]
+    pub wait: bool,
+
+    /// How many times the Job retries a `helm upgrade` invocation that
+    /// fails with a transient error ("another operation in progress",
+    /// apiserver timeouts), distinguished from permanent template/values
+    /// errors by the Job itself. Separate from `--job-backoff-limit`,
+    /// which restarts the whole pod; this retries within a single run so
+    /// a flaky cluster doesn't burn the pod's own restart budget.
+    #[arg(long, default_value_t = 3)]
+    pub helm_retry_max_attempts: u32,
+
+    /// Base backoff between `--helm-retry-max-attempts` retries; doubles
+    /// each attempt.
+    #[arg(long, default_value_t = 5)]
+    pub helm_retry_backoff_seconds: u32,
+
+    /// Render preflight validation results as SARIF or JUnit XML instead
+    /// of failing fast on the first one, for ingestion by CI dashboards
+    /// and code-scanning UIs that gate cluster changes. With the default
+    /// `text` format, preflight still stops at the first failing
+    /// validation as before.
+    #[arg(long, value_enum, default_value = "text")]
+    pub report_format: ReportFormat,
+
+    /// Passed straight through to the Job's helm client: if the release
+    /// is found stuck in `pending-upgrade`/`pending-install`/
+    /// `pending-rollback` (typically left behind by a Job pod that was
+    /// killed mid-release), roll it back or delete the stuck revision
+    /// record so `helm upgrade` can proceed, instead of failing with
+    /// Helm's own "another operation (...) in progress" error. Off by
+    /// default since repairing a pending release discards whatever that
+    /// interrupted operation was in the middle of doing.
+    #[arg(long)]
+    pub repair_pending_release: bool,
+
+    /// Passed straight through to the Job's own runtime: before running
+    /// any step, check the `<job name>-checkpoint` ConfigMap it persists
+    /// progress to (helm phase done, CRDs applied, data-plane nodes
+    /// already restarted) and resume from the last completed step
+    /// instead of re-running everything. Only useful when re-submitting
+    /// a Job whose pod was killed mid-upgrade with the same Job name
+    /// (e.g. via `kubectl apply -f` on a saved manifest); a fresh
+    /// `upgrade apply` run always gets a new, checkpoint-less Job name.
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Passed straight through to the Job: product name substituted into
+    /// its log lines and `helm upgrade --description`, for distributions
+    /// that rebrand OpenEBS components. Unset keeps the Job's own
+    /// default.
+    #[arg(long)]
+    pub branding_product_name: Option<String>,
+
+    /// Passed straight through to the Job: Event `reason` it records
+    /// instead of its own default (`"OpenebsUpgrade"`), for distributions
+    /// whose alerting/tooling matches on Event reason. Unset keeps the
+    /// Job's own default.
+    #[arg(long)]
+    pub branding_event_reason: Option<String>,
+
+    /// Passed straight through to the Job: prefix substituted for
+    /// `openebs.io/` in the labels it sets on objects it creates, for
+    /// distributions that rebrand their own label namespace. Unset keeps
+    /// the Job's own default (`openebs.io/`).
+    #[arg(long)]
+    pub branding_label_prefix: Option<String>,
+
+    /// Passed straight through to the Job: how many data-plane nodes'
+    /// `io-engine` pods it restarts concurrently, instead of strictly one
+    /// at a time. Raising this speeds up the data-plane restart phase at
+    /// the cost of more volumes rebuilding replicas simultaneously.
+    #[arg(long, default_value_t = 1)]
+    pub restart_parallelism: u32,
+
+    /// Passed straight through to the Job: how long it waits for a
+    /// replica rebuild triggered by an `io-engine` restart to finish
+    /// (polling the REST volumes API) before moving on to the next node
+    /// anyway. Go duration syntax, e.g. `15m`.
+    #[arg(long, default_value = "15m")]
+    pub rebuild_wait_timeout: String,
+
+    /// Passed straight through to the Job: the `io-engine` DaemonSet
+    /// `updateStrategy` it patches in for the data-plane restart phase.
+    /// `rolling-update` (the default) lets the DaemonSet controller
+    /// restart up to `--max-unavailable` nodes' pods at once;
+    /// `on-delete` instead restarts pods only as the Job itself deletes
+    /// them one at a time, for clusters where even the controller's own
+    /// surge is too much concurrent rebuild.
+    #[arg(long, value_enum, default_value_t = RestartStrategy::RollingUpdate)]
+    pub restart_strategy: RestartStrategy,
+
+    /// Passed straight through to the Job: `maxUnavailable` for the
+    /// `rolling-update` restart strategy. Ignored under `on-delete`.
+    /// Accepts an absolute node count or a percentage (e.g. `25%`), the
+    /// same syntax the DaemonSet's `updateStrategy` field itself takes.
+    #[arg(long, default_value = "1")]
+    pub max_unavailable: String,
+
+    /// Passed straight through to the Job: skip automatically disabling
+    /// partial rebuild on the agent-core before the data-plane restart
+    /// (and re-enabling it afterwards) when upgrading from a version
+    /// known to need it. On by default since leaving partial rebuild
+    /// enabled across one of those upgrades risks a corrupted replica;
+    /// only skip it if that automation itself is causing trouble.
+    #[arg(long)]
+    pub skip_partial_rebuild_toggle: bool,
+
+    /// Passed straight through to the Job: skip the CRD pre-apply phase it
+    /// otherwise runs before `helm upgrade` (applying the target chart's
+    /// CRDs directly, with conversion/retained-field handling, since
+    /// `helm upgrade` never updates CRDs on its own). Only skip this if
+    /// you're applying CRDs through some other channel yourself.
+    ///
+    /// The Job's own value-toggling for this phase is expected to stay a
+    /// pure in-process YAML edit (e.g. via `serde_yaml`) rather than
+    /// shelling out to an external tool like `yq` -- doing the latter
+    /// would add a binary dependency to the Job image for no benefit this
+    /// plugin can see from the outside, and would turn its errors opaque.
+    #[arg(long)]
+    pub skip_crd_apply: bool,
+
+    /// `HTTP_PROXY` for the upgrade Job's pod, for clusters whose egress
+    /// to the chart repository/registry only works through a proxy.
+    #[arg(long)]
+    pub job_http_proxy: Option<String>,
+
+    /// `HTTPS_PROXY` for the upgrade Job's pod. See `--job-http-proxy`.
+    #[arg(long)]
+    pub job_https_proxy: Option<String>,
+
+    /// `NO_PROXY` for the upgrade Job's pod, e.g. the in-cluster
+    /// hostnames/CIDRs that shouldn't go through `--job-http-proxy`/
+    /// `--job-https-proxy`. Ignored unless one of those is also set.
+    #[arg(long)]
+    pub job_no_proxy: Option<String>,
+
+    /// Extra DNS nameserver for the upgrade Job's pod, appended to the
+    /// ones its `dnsPolicy` would already resolve with. May be repeated.
+    #[arg(long = "job-dns-nameserver")]
+    pub job_dns_nameserver: Vec<String>,
+
+    /// Extra DNS search domain for the upgrade Job's pod, appended to the
+    /// ones its `dnsPolicy` would already resolve with. May be repeated.
+    #[arg(long = "job-dns-search")]
+    pub job_dns_search: Vec<String>,
+
+    /// How long `apply` watches for the upgrade Job's first Event after
+    /// creating it, before giving up and returning without one. Watches
+    /// rather than polls, so a fast failure (e.g. an unpullable image) is
+    /// reported immediately instead of waiting out a fixed poll interval,
+    /// and a slow-but-healthy start isn't mistaken for a timeout.
+    #[arg(long, default_value_t = 60)]
+    pub event_wait_timeout_seconds: u64,
+
+    /// Allow `apply` with no `--to-version`, letting the upgrade Job
+    /// resolve its own destination chart tag instead of refusing the
+    /// ambiguous default outright. Off by default: see
+    /// `destination_version_validation`.
+    #[arg(long)]
+    pub allow_implicit_destination_version: bool,
+
+    /// Name of the Helm release being upgraded, used to name the
+    /// cluster-scoped upgrade lock (see [`lock`]) so two releases can be
+    /// upgraded concurrently without contending on the same lock.
+    #[arg(long, default_value = "openebs")]
+    pub release_name: String,
+
+    /// Take the upgrade lock even though another operator's lock on this
+    /// release already exists, instead of refusing to start. Only pass
+    /// this once you've confirmed the other upgrade is actually gone
+    /// (crashed client, abandoned terminal) and not genuinely in
+    /// progress.
+    #[arg(long)]
+    pub force_take_lock: bool,
+}
+
+pub async fn run(ctx: &Context, cmd: UpgradeCommand) -> anyhow::Result<()> {
+    match cmd {
+        UpgradeCommand::Apply(args) => apply(ctx, *args).await,
+        UpgradeCommand::Status { job, output, history } => {
+            if history {
+                print_event_history(ctx, job.as_deref(), output).await
+            } else {
+                print_status(ctx, job.as_deref(), output).await
+            }
+        }
+        UpgradeCommand::Health { job, json } => print_health(ctx, job.as_deref(), json).await,
+        UpgradeCommand::Logs { job, follow } => logs::print_logs(ctx, job.as_deref(), follow).await,
+        UpgradeCommand::UninstallRbac { force } => Ok(rbac::delete_upgrade_resources(ctx, force).await?),
+        UpgradeCommand::Rbac(RbacCommand::Diff) => print_rbac_diff(ctx).await,
+    }
+}
+
+async fn print_rbac_diff(ctx: &Context) -> anyhow::Result<()> {
+    let delta = rbac::diff_cluster_role(ctx).await?;
+    if delta.added.is_empty() && delta.removed.is_empty() {
+        println!("no change: in-cluster ClusterRole matches this plugin version");
+        return Ok(());
+    }
+    for rule in &delta.added {
+        println!("+ {rule}");
+    }
+    for rule in &delta.removed {
+        println!("- {rule}");
+    }
+    Ok(())
+}
+
+async fn apply(ctx: &Context, args: UpgradeArgs) -> anyhow::Result<()> {
+    run_preflight(ctx, &args).await?;
+
+    let mut set_values = values::parse_set(&args.set)?;
+    let mut set_file_values = values::parse_set_file(&args.set_file).await?;
+    set_file_values.extend(values::parse_values_files(&args.values_files).await?);
+    if let Some(target_version) = &args.target_version {
+        migrate_values::migrate_set_values(target_version, &mut set_values);
+        migrate_values::migrate_set_values(target_version, &mut set_file_values);
+        for set in &mut set_file_values {
+            if !set.key.is_empty() {
+                continue;
+            }
+            let Some(yaml) = set.value.as_str() else { continue };
+            let Ok(mut doc) = serde_yaml::from_str::<serde_json::Value>(yaml) else { continue };
+            migrate_values::migrate_document(target_version, &mut doc);
+            if let Ok(rewritten) = serde_yaml::to_string(&doc) {
+                set.value = serde_json::Value::String(rewritten);
+            }
+        }
+    }
+    if let Some(schema) = &args.chart_schema {
+        values::validate_against_schema(&set_values, schema)?;
+        values::validate_against_schema(&set_file_values, schema)?;
+    }
+
+    let job = job::upgrade_job(ctx, &args, &set_values, &set_file_values).await?;
+    let name = job.metadata.name.unwrap_or_default();
+    println!("created upgrade job {name}");
+
+    let timeout = std::time::Duration::from_secs(args.event_wait_timeout_seconds);
+    match status::wait_for_first_event(ctx, &name, timeout).await? {
+        Some(event) => {
+            println!("{}: {}", event.phase, event.message);
+            if let Some(detail) = &event.detail {
+                print_phase_detail(detail);
+            }
+        }
+        None => println!("no event seen within {}s; check `upgrade status` for progress", timeout.as_secs()),
+    }
+    Ok(())
+}
+
+async fn print_status(
+    ctx: &Context,
+    job_name: Option<&str>,
+    output: StatusOutputFormat,
+) -> anyhow::Result<()> {
+    let status = status::get_upgrade_status(ctx, job_name).await?;
+    match output {
+        StatusOutputFormat::Json => println!("{}", serde_json::to_string_pretty(&status)?),
+        StatusOutputFormat::Yaml => print!("{}", serde_yaml::to_string(&status)?),
+        StatusOutputFormat::Text => {
+            let source = match status.source {
+                StatusSource::Event => "event",
+                StatusSource::JobConditions => "job-conditions",
+                StatusSource::PodState => "pod-state",
+                StatusSource::ConfigMap => "status-configmap",
+            };
+            println!(
+                "job: {}\nphase: {}\nsource: {source}\nmessage: {}",
+                status.job_name, status.phase, status.message
+            );
+            if let Some(timestamp) = &status.timestamp {
+                println!("timestamp: {timestamp}");
+            }
+            if let Some(detail) = &status.detail {
+                print_phase_detail(detail);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints the health verdict and, for `degraded`, also fails the command
+/// (non-zero exit) so a command-based AnalysisTemplate/HealthCheck that
+/// just checks the exit code -- not just the ones that parse `--json` --
+/// still gates correctly.
+async fn print_health(ctx: &Context, job_name: Option<&str>, json: bool) -> anyhow::Result<()> {
+    let report = health::evaluate_health(ctx, job_name).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        let verdict = match report.verdict {
+            health::HealthVerdict::Healthy => "healthy",
+            health::HealthVerdict::Progressing => "progressing",
+            health::HealthVerdict::Degraded => "degraded",
+        };
+        println!("job: {}\nverdict: {verdict}\nreason: {}", report.job_name, report.reason);
+    }
+
+    if report.verdict == health::HealthVerdict::Degraded {
+        return Err(UpgradeError::ValidationFailed(report.reason).into());
+    }
+    Ok(())
+}
+
+fn print_phase_detail(detail: &status::UpgradePhaseDetail) {
+    let phase = match detail.phase {
+        status::UpgradePhase::Validating => "validating",
+        status::UpgradePhase::HelmUpgrade => "helm-upgrade",
+        status::UpgradePhase::CrdUpdate => "crd-update",
+        status::UpgradePhase::DataPlaneRestart => "data-plane-restart",
+        status::UpgradePhase::Completed => "completed",
+    };
+    print!("step: {phase}");
+    match detail.percent {
+        Some(percent) => println!(" ({percent}%)"),
+        None => println!(),
+    }
+    for (node, status) in &detail.node_restart_status {
+        println!("  {node}: {status}");
+    }
+}
+
+async fn print_event_history(
+    ctx: &Context,
+    job_name: Option<&str>,
+    output: StatusOutputFormat,
+) -> anyhow::Result<()> {
+    let history = status::event_history(ctx, job_name).await?;
+    match output {
+        StatusOutputFormat::Json => println!("{}", serde_json::to_string_pretty(&history)?),
+        StatusOutputFormat::Yaml => print!("{}", serde_yaml::to_string(&history)?),
+        StatusOutputFormat::Text => {
+            if history.is_empty() {
+                println!("no events recorded for this upgrade job");
+            }
+            for event in &history {
+                let timestamp = event.timestamp.as_deref().unwrap_or("unknown");
+                println!("{timestamp}  {:<20}  {}", event.phase, event.message);
+                if let Some(detail) = &event.detail {
+                    print_phase_detail(detail);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_preflight(ctx: &Context, args: &UpgradeArgs) -> Result<(), UpgradeError> {
+    if matches!(args.report_format, ReportFormat::Text) {
+        return run_preflight_fast(ctx, args).await;
+    }
+
+    let outcomes = run_preflight_collecting(ctx, args).await?;
+    println!("{}", report::render(args.report_format, "upgrade-preflight", &outcomes));
+
+    let failed: Vec<&str> = outcomes
+        .iter()
+        .filter(|o| !o.passed)
+        .map(|o| o.name.as_str())
+        .collect();
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(UpgradeError::ValidationFailed(format!(
+            "preflight validation(s) failed: {}",
+            failed.join(", ")
+        )))
+    }
+}
+
+/// The default `--report-format text` path: stops at the first failing
+/// validation, same as before `--report-format` existed.
+async fn run_preflight_fast(ctx: &Context, args: &UpgradeArgs) -> Result<(), UpgradeError> {
+    if !args.skip_pending_pvc_validation {
+        validations::pending_pvc_validation(ctx).await?;
+    }
+    if !args.skip_node_health_validation {
+        validations::node_health_validation(ctx).await?;
+    }
+    if !args.skip_single_volume_replica_validation {
+        validations::single_volume_replica_validation(ctx).await?;
+    }
+    validations::already_cordoned_nodes_validation(ctx, &args.skip_cordoned_node_validation).await?;
+    if !args.skip_upgrade_path_validation {
+        if let Some(target_version) = &args.target_version {
+            validations::upgrade_path_validation(target_version)?;
+        }
+    }
+    validations::destination_version_validation(
+        args.target_version.as_deref(),
+        args.allow_implicit_destination_version,
+    )?;
+    Ok(())
+}
+
+/// The `--report-format sarif|junit` path: runs every non-skipped
+/// validation regardless of earlier failures, so the report covers all
+/// of them instead of stopping at the first one.
+async fn run_preflight_collecting(ctx: &Context, args: &UpgradeArgs) -> Result<Vec<CheckOutcome>, UpgradeError> {
+    let mut outcomes = Vec::new();
+    if !args.skip_pending_pvc_validation {
+        outcomes.push(preflight_outcome("pending-pvc", validations::pending_pvc_validation(ctx).await));
+    }
+    if !args.skip_node_health_validation {
+        outcomes.push(preflight_outcome("node-health", validations::node_health_validation(ctx).await));
+    }
+    if !args.skip_single_volume_replica_validation {
+        outcomes.push(preflight_outcome(
+            "single-volume-replica",
+            validations::single_volume_replica_validation(ctx).await,
+        ));
+    }
+    outcomes.push(preflight_outcome(
+        "cordoned-nodes",
+        validations::already_cordoned_nodes_validation(ctx, &args.skip_cordoned_node_validation).await,
+    ));
+    if !args.skip_upgrade_path_validation {
+        if let Some(target_version) = &args.target_version {
+            outcomes.push(preflight_outcome("upgrade-path", validations::upgrade_path_validation(target_version)));
+        }
+    }
+    outcomes.push(preflight_outcome(
+        "destination-version",
+        validations::destination_version_validation(
+            args.target_version.as_deref(),
+            args.allow_implicit_destination_version,
+        ),
+    ));
+    Ok(outcomes)
+}
+
+fn preflight_outcome(name: &str, result: Result<(), UpgradeError>) -> CheckOutcome {
+    match result {
+        Ok(()) => CheckOutcome {
+            suite: "preflight".to_string(),
+            name: name.to_string(),
+            passed: true,
+            message: "ok".to_string(),
+        },
+        Err(e) => CheckOutcome {
+            suite: "preflight".to_string(),
+            name: name.to_string(),
+            passed: false,
+            message: e.to_string(),
+        },
+    }
+}
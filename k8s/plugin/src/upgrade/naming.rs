@@ -0,0 +1,96 @@
+//! Keeps generated object names within Kubernetes' length and DNS-1123
+//! limits. `--set-file` keys (and other caller-supplied path segments
+//! folded into a `generateName` prefix) aren't bounded in length or
+//! character set, so a long or oddly-charactered one used to risk an
+//! `generateName` the apiserver rejects outright instead of failing with
+//! a useful message at CLI time.
+
+use sha2::{Digest, Sha256};
+
+/// Kubernetes object names must fit a DNS subdomain: at most 253
+/// characters. This plugin's own `generateName` values end in a single
+/// trailing `-`, to which the apiserver appends its own 5-character
+/// random suffix, so the prefix itself needs to leave room for that.
+const MAX_GENERATE_NAME_PREFIX_LENGTH: usize = 253 - 6;
+
+/// Builds a `generateName` prefix (without the trailing `-`, which the
+/// caller appends) from `parts`, sanitized to DNS-1123 and truncated with
+/// a stable hash suffix if the joined result would be too long.
+pub fn generate_name_prefix(parts: &[&str]) -> String {
+    sanitize_name_segment(&parts.join("-"), MAX_GENERATE_NAME_PREFIX_LENGTH)
+}
+
+/// Sanitizes `s` into a valid DNS-1123 subdomain segment (lowercase
+/// alphanumerics, `-` and `.` only, no leading/trailing `-`), then bounds
+/// it to `max_len` via [`truncate_with_hash`].
+pub fn sanitize_name_segment(s: &str, max_len: usize) -> String {
+    let sanitized: String = s
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let trimmed = sanitized.trim_matches('-');
+    let non_empty = if trimmed.is_empty() { "x" } else { trimmed };
+    truncate_with_hash(non_empty, max_len)
+}
+
+/// Truncates `name` to `max_len`, replacing the truncated tail with a
+/// short stable hash of the *full* original name so two long names that
+/// happen to share a prefix don't collide once both are shortened to the
+/// same length -- the cross-namespace/cross-release collision this
+/// module exists to guard against.
+pub fn truncate_with_hash(name: &str, max_len: usize) -> String {
+    if name.len() <= max_len {
+        return name.to_string();
+    }
+    let hash = sha256_hex8(name.as_bytes());
+    let keep = max_len.saturating_sub(1 + hash.len()).min(name.len());
+    let mut prefix_end = keep;
+    while !name.is_char_boundary(prefix_end) {
+        prefix_end -= 1;
+    }
+    format!("{}-{hash}", &name[..prefix_end])
+        .trim_end_matches('-')
+        .to_string()
+}
+
+fn sha256_hex8(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())[..8].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_short_names_untouched() {
+        assert_eq!(sanitize_name_segment("my-release", 253), "my-release");
+    }
+
+    #[test]
+    fn lowercases_and_replaces_invalid_characters() {
+        assert_eq!(sanitize_name_segment("My.Release_v2", 253), "my.release-v2");
+    }
+
+    #[test]
+    fn truncates_long_names_with_a_stable_hash_suffix() {
+        let long = "a".repeat(300);
+        let truncated = truncate_with_hash(&long, 63);
+        assert!(truncated.len() <= 63);
+        assert_eq!(truncated, truncate_with_hash(&long, 63));
+    }
+
+    #[test]
+    fn distinguishes_two_long_names_sharing_a_prefix() {
+        let a = format!("{}-a", "x".repeat(300));
+        let b = format!("{}-b", "x".repeat(300));
+        assert_ne!(truncate_with_hash(&a, 63), truncate_with_hash(&b, 63));
+    }
+
+    #[test]
+    fn generate_name_prefix_joins_and_bounds_parts() {
+        let prefix = generate_name_prefix(&["openebs-upgrade-set-file", &"k".repeat(300)]);
+        assert!(prefix.len() <= MAX_GENERATE_NAME_PREFIX_LENGTH);
+    }
+}
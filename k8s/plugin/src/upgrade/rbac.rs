@@ -0,0 +1,295 @@
+//! The ServiceAccount and cluster-scoped RBAC the upgrade Job's pod runs
+//! as. The ClusterRole/ClusterRoleBinding are cluster-wide singletons
+//! reused by every upgrade run under a fixed, well-known name, reconciled
+//! via [`reconcile_cluster_resource`] (built on [`idempotent_create_resource`])
+//! so upgrading this plugin to a version with different RBAC requirements
+//! converges the cluster to the new rules instead of leaving whatever an
+//! older run created in place. Reconciling also adopts a copy a security
+//! review pre-created by hand: both objects are tagged with
+//! [`MANAGED_BY_ANNOTATION`] on every reconcile, and [`delete_upgrade_resources`]
+//! refuses to remove either one unless it carries that annotation (or the
+//! caller passes `force`), so a manually authored RBAC object is never
+//! deleted out from under whoever created it. The ServiceAccount is
+//! namespaced and created fresh per run (so it can be owned by, and
+//! cleaned up with, the Job -- see [`super::job`]).
+
+use k8s_openapi::api::core::v1::ServiceAccount;
+use k8s_openapi::api::rbac::v1::{ClusterRole, ClusterRoleBinding, PolicyRule, RoleRef, Subject};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{Api, DeleteParams, PostParams};
+
+use crate::provenance;
+use crate::resources::{idempotent_create_resource, Context};
+
+use super::error::UpgradeError;
+
+/// Fixed name shared by every upgrade run's ClusterRole/ClusterRoleBinding.
+pub(crate) const CLUSTER_ROLE_NAME: &str = "openebs-upgrade";
+
+/// Annotation [`reconcile_cluster_resource`] sets on every ClusterRole/
+/// ClusterRoleBinding it manages, so a later [`delete_upgrade_resources`]
+/// call can tell an object it (or an earlier run) created or adopted
+/// apart from one a security review pre-created for its own reasons and
+/// never handed to this plugin.
+const MANAGED_BY_ANNOTATION: &str = "openebs.io/managed-by";
+const MANAGED_BY_VALUE: &str = "kubectl-openebs";
+
+/// Names of the RBAC objects the upgrade Job's pod runs as. The
+/// ClusterRole/ClusterRoleBinding are always [`CLUSTER_ROLE_NAME`], so only
+/// the per-run ServiceAccount name needs to be threaded through to the Job.
+pub struct UpgradeResources {
+    pub service_account_name: String,
+}
+
+/// Creates the per-run ServiceAccount and reconciles the shared
+/// ClusterRole/ClusterRoleBinding the upgrade Job's pod runs as.
+pub async fn create_upgrade_resources(ctx: &Context) -> Result<UpgradeResources, UpgradeError> {
+    let service_accounts: Api<ServiceAccount> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let service_account = service_accounts
+        .create(
+            &PostParams::default(),
+            &ServiceAccount {
+                metadata: ObjectMeta {
+                    generate_name: Some("openebs-upgrade-".to_string()),
+                    namespace: Some(ctx.namespace.clone()),
+                    annotations: provenance::annotate(None),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .await?;
+    let service_account_name = service_account.metadata.name.unwrap_or_default();
+
+    let cluster_roles: Api<ClusterRole> = Api::all(ctx.client.clone());
+    reconcile_cluster_resource(&cluster_roles, CLUSTER_ROLE_NAME, &desired_cluster_role()).await?;
+
+    let cluster_role_bindings: Api<ClusterRoleBinding> = Api::all(ctx.client.clone());
+    let desired_cluster_role_binding = ClusterRoleBinding {
+        metadata: ObjectMeta {
+            name: Some(CLUSTER_ROLE_NAME.to_string()),
+            annotations: Some(managed_by_annotation()),
+            ..Default::default()
+        },
+        role_ref: RoleRef {
+            api_group: "rbac.authorization.k8s.io".to_string(),
+            kind: "ClusterRole".to_string(),
+            name: CLUSTER_ROLE_NAME.to_string(),
+        },
+        subjects: Some(vec![Subject {
+            kind: "ServiceAccount".to_string(),
+            name: service_account_name.clone(),
+            namespace: Some(ctx.namespace.clone()),
+            ..Default::default()
+        }]),
+    };
+    reconcile_cluster_resource(
+        &cluster_role_bindings,
+        CLUSTER_ROLE_NAME,
+        &desired_cluster_role_binding,
+    )
+    .await?;
+
+    Ok(UpgradeResources { service_account_name })
+}
+
+/// `get`/`list`/`watch`/`update`/`patch`: every kind the upgrade Job's
+/// pod reconciles in place, never deletes or creates on its own, so
+/// there's no need for `create`/`delete`/`deletecollection`.
+fn reconcile_verbs() -> Vec<String> {
+    vec!["get".to_string(), "list".to_string(), "watch".to_string(), "update".to_string(), "patch".to_string()]
+}
+
+/// The permissions this plugin version's upgrade Job needs, independent
+/// of any particular run (no ServiceAccount name is baked in). The sole
+/// source of truth both [`create_upgrade_resources`] and
+/// [`diff_cluster_role`] reconcile/compare against. Scoped to exactly
+/// what a chart upgrade touches -- the OpenEBS CRs it installs/updates,
+/// the Deployments/DaemonSets/StatefulSets running the engines, the Jobs
+/// a chart hook may run, and the ConfigMaps holding chart values/config
+/// -- rather than a blanket grant across every kind in these api groups
+/// (in particular, no access to Secrets, Pods, Services or
+/// ServiceAccounts, none of which the upgrade itself needs to touch).
+fn desired_cluster_role() -> ClusterRole {
+    ClusterRole {
+        metadata: ObjectMeta {
+            name: Some(CLUSTER_ROLE_NAME.to_string()),
+            annotations: Some(managed_by_annotation()),
+            ..Default::default()
+        },
+        rules: Some(vec![
+            PolicyRule {
+                api_groups: Some(vec!["openebs.io".to_string()]),
+                resources: Some(vec!["*".to_string()]),
+                verbs: reconcile_verbs(),
+                ..Default::default()
+            },
+            PolicyRule {
+                api_groups: Some(vec!["apps".to_string()]),
+                resources: Some(vec!["deployments".to_string(), "daemonsets".to_string(), "statefulsets".to_string()]),
+                verbs: reconcile_verbs(),
+                ..Default::default()
+            },
+            PolicyRule {
+                api_groups: Some(vec!["batch".to_string()]),
+                resources: Some(vec!["jobs".to_string()]),
+                verbs: reconcile_verbs(),
+                ..Default::default()
+            },
+            PolicyRule {
+                api_groups: Some(vec!["".to_string()]),
+                resources: Some(vec!["configmaps".to_string()]),
+                verbs: reconcile_verbs(),
+                ..Default::default()
+            },
+        ]),
+        ..Default::default()
+    }
+}
+
+/// The annotations every reconcile of the shared ClusterRole/
+/// ClusterRoleBinding carries: [`MANAGED_BY_ANNOTATION`] plus a fresh
+/// [`provenance::annotate`] stamp, so `provenance cluster-role[-binding]`
+/// reflects whichever run most recently reconciled it.
+fn managed_by_annotation() -> std::collections::BTreeMap<String, String> {
+    let managed = std::collections::BTreeMap::from([(
+        MANAGED_BY_ANNOTATION.to_string(),
+        MANAGED_BY_VALUE.to_string(),
+    )]);
+    provenance::annotate(Some(managed)).unwrap_or_default()
+}
+
+fn is_managed<K: kube::Resource>(object: &K) -> bool {
+    object
+        .meta()
+        .annotations
+        .as_ref()
+        .and_then(|a| a.get(MANAGED_BY_ANNOTATION))
+        .map(|v| v == MANAGED_BY_VALUE)
+        .unwrap_or(false)
+}
+
+/// Reconciles a cluster-scoped RBAC singleton to `desired` via
+/// [`idempotent_create_resource`], logging whether this run created it,
+/// adopted a copy a security review pre-created by hand (one without
+/// [`MANAGED_BY_ANNOTATION`] yet), or simply reconciled one it already
+/// manages. Either way the annotation ends up set, so a subsequent
+/// [`delete_upgrade_resources`] knows it's now safe to remove.
+async fn reconcile_cluster_resource<K>(
+    api: &Api<K>,
+    name: &str,
+    desired: &K,
+) -> Result<(), UpgradeError>
+where
+    K: kube::Resource + Clone + serde::de::DeserializeOwned + serde::Serialize + std::fmt::Debug,
+{
+    let previously_existed = api.get(name).await.ok();
+    idempotent_create_resource(api, name, desired).await?;
+    match previously_existed {
+        None => println!("created cluster RBAC object {name}"),
+        Some(existing) if !is_managed(&existing) => {
+            println!("adopted pre-existing cluster RBAC object {name} (not previously managed by kubectl-openebs)")
+        }
+        Some(_) => {}
+    }
+    Ok(())
+}
+
+/// Deletes the shared ClusterRole/ClusterRoleBinding, refusing unless
+/// each one carries [`MANAGED_BY_ANNOTATION`] (i.e. this plugin created
+/// or adopted it) or `force` is set, so a manually authored RBAC object
+/// that happens to share [`CLUSTER_ROLE_NAME`] is never deleted out from
+/// under whoever created it.
+pub async fn delete_upgrade_resources(ctx: &Context, force: bool) -> Result<(), UpgradeError> {
+    let cluster_roles: Api<ClusterRole> = Api::all(ctx.client.clone());
+    delete_if_managed(&cluster_roles, CLUSTER_ROLE_NAME, force).await?;
+
+    let cluster_role_bindings: Api<ClusterRoleBinding> = Api::all(ctx.client.clone());
+    delete_if_managed(&cluster_role_bindings, CLUSTER_ROLE_NAME, force).await?;
+
+    Ok(())
+}
+
+async fn delete_if_managed<K>(api: &Api<K>, name: &str, force: bool) -> Result<(), UpgradeError>
+where
+    K: kube::Resource + Clone + serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let existing = match api.get(name).await {
+        Ok(existing) => existing,
+        Err(kube::Error::Api(e)) if e.code == 404 => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    if !force && !is_managed(&existing) {
+        return Err(UpgradeError::ValidationFailed(format!(
+            "{name} isn't managed by kubectl-openebs (missing {MANAGED_BY_ANNOTATION} annotation); \
+             pass --force to delete it anyway"
+        )));
+    }
+    api.delete(name, &DeleteParams::default()).await?;
+    println!("deleted {name}");
+    Ok(())
+}
+
+/// One rule added or removed between the in-cluster ClusterRole and what
+/// this plugin version would generate, in a human-readable canonical
+/// form (`apiGroups=[...] resources=[...] verbs=[...]`).
+pub struct PermissionDelta {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Compares the in-cluster `openebs-upgrade` ClusterRole's rules against
+/// what this plugin version would generate, so a security reviewer can
+/// approve the delta instead of rereading the whole role on every
+/// upgrade. Rules are compared as an unordered set, since Kubernetes
+/// doesn't attach meaning to rule order.
+pub async fn diff_cluster_role(ctx: &Context) -> Result<PermissionDelta, UpgradeError> {
+    let cluster_roles: Api<ClusterRole> = Api::all(ctx.client.clone());
+    let existing_rules: std::collections::BTreeSet<String> = cluster_roles
+        .get_opt(CLUSTER_ROLE_NAME)
+        .await?
+        .and_then(|role| role.rules)
+        .unwrap_or_default()
+        .iter()
+        .map(canonical_rule)
+        .collect();
+    let desired_rules: std::collections::BTreeSet<String> = desired_cluster_role()
+        .rules
+        .unwrap_or_default()
+        .iter()
+        .map(canonical_rule)
+        .collect();
+
+    Ok(PermissionDelta {
+        added: desired_rules.difference(&existing_rules).cloned().collect(),
+        removed: existing_rules.difference(&desired_rules).cloned().collect(),
+    })
+}
+
+fn canonical_rule(rule: &PolicyRule) -> String {
+    format!(
+        "apiGroups=[{}] resources=[{}] verbs=[{}]",
+        rule.api_groups.clone().unwrap_or_default().join(","),
+        rule.resources.clone().unwrap_or_default().join(","),
+        rule.verbs.join(","),
+    )
+}
+
+/// Builds the `ownerReference` patch body pointing at the created Job,
+/// applied to the ServiceAccount and the upgrade's ConfigMaps so deleting
+/// the Job cascades to them instead of leaving them to a later manual
+/// `kubectl delete` pass.
+pub fn owner_reference_patch(job_name: &str, job_uid: &str) -> serde_json::Value {
+    serde_json::json!({
+        "metadata": {
+            "ownerReferences": [{
+                "apiVersion": "batch/v1",
+                "kind": "Job",
+                "name": job_name,
+                "uid": job_uid,
+                "controller": true,
+                "blockOwnerDeletion": true,
+            }]
+        }
+    })
+}
@@ -0,0 +1,388 @@
+//! Reads back the outcome of an upgrade Job after the fact.
+//!
+//! Kubernetes Events expire (1h by default), so `upgrade status` used to
+//! report "no upgrade event present" hours after a Job had actually
+//! finished. The upgrade Job persists its final phase into a `<job
+//! name>-status` ConfigMap that outlives the Event, so status lookups
+//! fall back to it -- and to the Job's own `status.conditions`, which
+//! also outlive the Event -- once the Event is gone. A Job stuck before
+//! it can emit any Event or condition at all (an unpullable image, a
+//! crashing container) has neither, so status also falls back further
+//! still to the Job pod's own container statuses.
+//!
+//! Kubernetes also truncates an Event's `message` once it gets too long,
+//! which can land mid-JSON for a Job that packs a large
+//! [`UpgradePhaseDetail`] (many nodes restarting) into it. The Job
+//! mirrors that same detail into the status ConfigMap too, so a
+//! truncated Event still resolves to the right `detail`, just from the
+//! ConfigMap instead.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use futures::StreamExt;
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::{ConfigMap, Event, Pod};
+use kube::api::{Api, ListParams, WatchEvent, WatchParams};
+
+use serde::{Deserialize, Serialize};
+
+use crate::resources::Context;
+
+use super::error::UpgradeError;
+
+/// Coarse-grained step an upgrade Job goes through, as reported in
+/// [`UpgradePhaseDetail::phase`].
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum UpgradePhase {
+    Validating,
+    HelmUpgrade,
+    CrdUpdate,
+    DataPlaneRestart,
+    Completed,
+}
+
+/// Structured detail an upgrade Job may pack into an Event's `message`
+/// field as JSON, on top of the plain-text summary every Job version
+/// still sends. A Job build that doesn't emit this (or an Event from any
+/// other source) simply has no detail, which the plugin treats the same
+/// as before this existed.
+#[derive(Serialize, Deserialize)]
+pub struct UpgradePhaseDetail {
+    pub phase: UpgradePhase,
+    /// 0-100. `None` for phases the Job can't meaningfully estimate
+    /// progress for (e.g. `Validating`).
+    pub percent: Option<u8>,
+    /// Per data-plane node restart status during `DataPlaneRestart`
+    /// (e.g. `"node-1" -> "restarted"`, `"node-2" -> "waiting"`). Empty
+    /// outside that phase.
+    #[serde(default)]
+    pub node_restart_status: BTreeMap<String, String>,
+    /// Plain-text summary, the same text a Job version without
+    /// structured detail would have sent as the Event's whole message.
+    pub message: String,
+}
+
+/// Parses `message` as a JSON-encoded [`UpgradePhaseDetail`], falling
+/// back to treating it as the plain-text message verbatim (with no
+/// detail) if it isn't one -- the common case, for Job versions that
+/// predate this or Events from any other source. The third element is
+/// true when `message` looks like it *was* meant to be one (starts with
+/// `{`) but failed to parse anyway -- the telltale sign of a Kubernetes
+/// Event truncating a payload that was too long, rather than a Job
+/// version that simply never sent structured detail.
+fn parse_phase_detail(message: String) -> (String, Option<UpgradePhaseDetail>, bool) {
+    match serde_json::from_str::<UpgradePhaseDetail>(&message) {
+        Ok(detail) => {
+            let summary = detail.message.clone();
+            (summary, Some(detail), false)
+        }
+        Err(_) => {
+            let truncated = message.trim_start().starts_with('{');
+            (message, None, truncated)
+        }
+    }
+}
+
+/// Label set on every upgrade Job this plugin creates, so the most
+/// recent one can be found without the caller naming it.
+pub const UPGRADE_JOB_LABEL: &str = "openebs.io/upgrade-job";
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatusSource {
+    Event,
+    JobConditions,
+    PodState,
+    ConfigMap,
+}
+
+#[derive(Serialize)]
+pub struct UpgradeStatus {
+    pub job_name: String,
+    pub phase: String,
+    pub message: String,
+    pub source: StatusSource,
+    /// When the source reported this status, if it carries a timestamp
+    /// (an Event's `lastTimestamp`; the status ConfigMap's own
+    /// `timestamp` key, for the Job versions that set one). `None` for
+    /// Job conditions, which don't carry one this plugin can read.
+    pub timestamp: Option<String>,
+    /// Structured progress detail, if the source packed one into its
+    /// message (currently only Events do). `None` otherwise.
+    pub detail: Option<UpgradePhaseDetail>,
+}
+
+/// Resolves `job_name` (or the most recently created upgrade Job, if
+/// `None`) and reports its status from the freshest source available:
+/// the Job's own Event, then its `status.conditions`, then its pod's
+/// container statuses, then the `<job>-status` ConfigMap it persists on
+/// completion. An Event whose structured detail got truncated by
+/// Kubernetes' Event size limit still wins on `phase`/`message` (it's
+/// still the freshest plain-text summary there is), but its `detail` is
+/// patched in from the ConfigMap instead, since that copy isn't subject
+/// to the same truncation.
+pub async fn get_upgrade_status(
+    ctx: &Context,
+    job_name: Option<&str>,
+) -> Result<UpgradeStatus, UpgradeError> {
+    let jobs: Api<Job> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let job_name = resolve_job_name(&jobs, job_name).await?;
+
+    if let Some((mut status, truncated)) = status_from_event(ctx, &job_name).await? {
+        if truncated {
+            status.detail = config_map_detail(ctx, &job_name).await?;
+        }
+        return Ok(status);
+    }
+    if let Some(status) = status_from_job_conditions(&jobs, &job_name).await? {
+        return Ok(status);
+    }
+    if let Some(status) = status_from_pod(ctx, &job_name).await? {
+        return Ok(status);
+    }
+    status_from_config_map(ctx, &job_name).await?.ok_or_else(|| {
+        UpgradeError::ValidationFailed(format!(
+            "no Event, Job condition, pod state or status ConfigMap found for upgrade job \
+             {job_name} (it may have been created by an older plugin version, or already \
+             garbage collected)"
+        ))
+    })
+}
+
+pub(crate) async fn resolve_job_name(
+    jobs: &Api<Job>,
+    job_name: Option<&str>,
+) -> Result<String, UpgradeError> {
+    match job_name {
+        Some(name) => Ok(name.to_string()),
+        None => most_recent_upgrade_job(jobs).await,
+    }
+}
+
+/// One `OpenebsUpgrade` Event in an upgrade Job's history, as reported by
+/// [`event_history`].
+#[derive(Serialize)]
+pub struct UpgradeEventRecord {
+    pub phase: String,
+    pub message: String,
+    pub timestamp: Option<String>,
+    pub detail: Option<UpgradePhaseDetail>,
+}
+
+/// Lists every Event recorded against `job_name` (or the most recent
+/// upgrade Job, if `None`) in chronological order, unlike
+/// [`get_upgrade_status`] which only ever reports the latest one. Useful
+/// for seeing which phase a stuck upgrade got through before it stalled,
+/// not just where it currently is.
+pub async fn event_history(
+    ctx: &Context,
+    job_name: Option<&str>,
+) -> Result<Vec<UpgradeEventRecord>, UpgradeError> {
+    let jobs: Api<Job> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let job_name = resolve_job_name(&jobs, job_name).await?;
+
+    let events: Api<Event> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let params = ListParams::default().fields(&format!(
+        "involvedObject.name={job_name},involvedObject.kind=Job"
+    ));
+    let mut list = events.list(&params).await?.items;
+    list.sort_by_key(|e| e.last_timestamp.clone().map(|t| t.0));
+
+    Ok(list
+        .into_iter()
+        .map(|event| {
+            let (message, detail, _truncated) = parse_phase_detail(event.message.unwrap_or_default());
+            UpgradeEventRecord {
+                phase: event.reason.unwrap_or_else(|| "Unknown".to_string()),
+                message,
+                timestamp: event.last_timestamp.map(|t| t.0.to_rfc3339()),
+                detail,
+            }
+        })
+        .collect())
+}
+
+/// Watches for the first `OpenebsUpgrade` Event against `job_name`,
+/// bounded by `timeout`, instead of polling on a fixed interval: a fast
+/// failure (e.g. an unpullable image) is reported as soon as its Event
+/// lands, and a slow-but-healthy start isn't mistaken for a timeout just
+/// because it took longer than one poll interval. Returns `None` if no
+/// Event arrives before `timeout` elapses; that's not itself an error,
+/// since the Job may simply still be starting.
+pub async fn wait_for_first_event(
+    ctx: &Context,
+    job_name: &str,
+    timeout: Duration,
+) -> Result<Option<UpgradeEventRecord>, UpgradeError> {
+    let events: Api<Event> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let watch_params = WatchParams::default()
+        .fields(&format!("involvedObject.name={job_name},involvedObject.kind=Job"))
+        .timeout(timeout.as_secs().clamp(1, 290) as u32);
+    let mut stream = Box::pin(events.watch(&watch_params, "0").await?);
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            return Ok(None);
+        }
+        let next = match tokio::time::timeout(remaining, stream.next()).await {
+            Ok(next) => next,
+            Err(_) => return Ok(None),
+        };
+        let event = match next {
+            Some(Ok(WatchEvent::Added(event))) | Some(Ok(WatchEvent::Modified(event))) => event,
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(e.into()),
+            None => return Ok(None),
+        };
+        let (message, detail, _truncated) = parse_phase_detail(event.message.unwrap_or_default());
+        return Ok(Some(UpgradeEventRecord {
+            phase: event.reason.unwrap_or_else(|| "Unknown".to_string()),
+            message,
+            timestamp: event.last_timestamp.map(|t| t.0.to_rfc3339()),
+            detail,
+        }));
+    }
+}
+
+async fn most_recent_upgrade_job(jobs: &Api<Job>) -> Result<String, UpgradeError> {
+    let params = ListParams::default().labels(&format!("{UPGRADE_JOB_LABEL}=true"));
+    let mut list = jobs.list(&params).await?.items;
+    list.sort_by_key(|job| job.metadata.creation_timestamp.clone().map(|t| t.0));
+    list.pop()
+        .and_then(|job| job.metadata.name)
+        .ok_or_else(|| UpgradeError::ValidationFailed("no upgrade Job found".to_string()))
+}
+
+/// Reports the latest Event's status, plus whether its message looked
+/// like a structured payload that Kubernetes had truncated (see
+/// [`parse_phase_detail`]) -- [`get_upgrade_status`] uses that to decide
+/// whether the Event is trustworthy enough to stop at, or whether it
+/// should keep falling through to the status ConfigMap for the detail
+/// the Event lost.
+async fn status_from_event(
+    ctx: &Context,
+    job_name: &str,
+) -> Result<Option<(UpgradeStatus, bool)>, UpgradeError> {
+    let events: Api<Event> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let params = ListParams::default().fields(&format!(
+        "involvedObject.name={job_name},involvedObject.kind=Job"
+    ));
+    let mut list = events.list(&params).await?.items;
+    list.sort_by_key(|e| e.last_timestamp.clone().map(|t| t.0));
+
+    Ok(list.pop().map(|event| {
+        let (message, detail, truncated) = parse_phase_detail(event.message.unwrap_or_default());
+        (
+            UpgradeStatus {
+                job_name: job_name.to_string(),
+                phase: event.reason.unwrap_or_else(|| "Unknown".to_string()),
+                message,
+                timestamp: event.last_timestamp.map(|t| t.0.to_rfc3339()),
+                source: StatusSource::Event,
+                detail,
+            },
+            truncated,
+        )
+    }))
+}
+
+async fn status_from_job_conditions(
+    jobs: &Api<Job>,
+    job_name: &str,
+) -> Result<Option<UpgradeStatus>, UpgradeError> {
+    let job = match jobs.get_opt(job_name).await? {
+        Some(job) => job,
+        None => return Ok(None),
+    };
+
+    let condition = job
+        .status
+        .as_ref()
+        .and_then(|s| s.conditions.as_ref())
+        .and_then(|conditions| conditions.iter().find(|c| c.status == "True"));
+
+    Ok(condition.map(|c| UpgradeStatus {
+        job_name: job_name.to_string(),
+        phase: c.type_.clone(),
+        message: c.message.clone().unwrap_or_default(),
+        timestamp: None,
+        source: StatusSource::JobConditions,
+        detail: None,
+    }))
+}
+
+/// Reports a stuck-before-any-Event-or-condition container state
+/// (`ImagePullBackOff`, `CrashLoopBackOff`, ...) off the Job's pod, since
+/// a Job that never gets its container running emits neither an Event
+/// the plugin recognizes nor a `status.conditions` entry.
+async fn status_from_pod(ctx: &Context, job_name: &str) -> Result<Option<UpgradeStatus>, UpgradeError> {
+    let pods: Api<Pod> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let params = ListParams::default().labels(&format!("job-name={job_name}"));
+    let pod = match pods.list(&params).await?.items.into_iter().next() {
+        Some(pod) => pod,
+        None => return Ok(None),
+    };
+
+    let waiting = pod
+        .status
+        .as_ref()
+        .and_then(|s| s.container_statuses.as_ref())
+        .and_then(|statuses| statuses.iter().find_map(|cs| cs.state.as_ref()?.waiting.as_ref()));
+
+    Ok(waiting.map(|w| UpgradeStatus {
+        job_name: job_name.to_string(),
+        phase: w.reason.clone().unwrap_or_else(|| "Waiting".to_string()),
+        message: w.message.clone().unwrap_or_default(),
+        timestamp: None,
+        source: StatusSource::PodState,
+        detail: None,
+    }))
+}
+
+/// Looks up just the status ConfigMap's `detail` key, for patching into
+/// an Event-sourced [`UpgradeStatus`] whose own detail got truncated.
+/// `Ok(None)` (no ConfigMap, or no `detail` key on it) is not an error --
+/// the Event's plain-text message still stands on its own.
+async fn config_map_detail(
+    ctx: &Context,
+    job_name: &str,
+) -> Result<Option<UpgradePhaseDetail>, UpgradeError> {
+    let config_maps: Api<ConfigMap> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let config_map = match config_maps.get_opt(&format!("{job_name}-status")).await? {
+        Some(config_map) => config_map,
+        None => return Ok(None),
+    };
+    Ok(config_map_phase_detail(&config_map.data.unwrap_or_default()))
+}
+
+async fn status_from_config_map(
+    ctx: &Context,
+    job_name: &str,
+) -> Result<Option<UpgradeStatus>, UpgradeError> {
+    let config_maps: Api<ConfigMap> = Api::namespaced(ctx.client.clone(), &ctx.namespace);
+    let config_map = match config_maps.get_opt(&format!("{job_name}-status")).await? {
+        Some(config_map) => config_map,
+        None => return Ok(None),
+    };
+
+    let data = config_map.data.unwrap_or_default();
+    Ok(Some(UpgradeStatus {
+        job_name: job_name.to_string(),
+        phase: data.get("phase").cloned().unwrap_or_else(|| "Unknown".to_string()),
+        message: data.get("message").cloned().unwrap_or_default(),
+        timestamp: data.get("timestamp").cloned(),
+        source: StatusSource::ConfigMap,
+        detail: config_map_phase_detail(&data),
+    }))
+}
+
+/// Parses the status ConfigMap's own `detail` key the same way an
+/// Event's message is parsed, for the same structured-progress payload
+/// mirrored there -- this is the one copy of it that Kubernetes can't
+/// truncate out from under a large `node_restart_status` map.
+fn config_map_phase_detail(data: &BTreeMap<String, String>) -> Option<UpgradePhaseDetail> {
+    data.get("detail").and_then(|raw| serde_json::from_str(raw).ok())
+}
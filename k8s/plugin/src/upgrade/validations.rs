@@ -0,0 +1,339 @@
+//! Preflight validations run before `upgrade` creates the upgrade Job.
+//! Each validation inspects live cluster state and returns a
+//! human-readable failure through [`UpgradeError::ValidationFailed`];
+//! callers typically expose a dedicated `--skip-*-validation` flag for
+//! each one so a known-bad check doesn't block an otherwise-safe upgrade.
+
+use k8s_openapi::api::core::v1::{Node, PersistentVolumeClaim, Pod};
+use kube::api::Api;
+
+use crate::resources::list_all;
+
+use crate::resources::Context;
+
+use super::error::UpgradeError;
+use super::version::check_upgrade_path;
+
+/// Provisioners openebs StorageClasses are built on; kept in one place so
+/// new engines only need to be added here.
+const OPENEBS_PROVISIONERS: &[&str] = &[
+    "openebs.io/provisioner-iscsi",
+    "cstor.csi.openebs.io",
+    "zfs.csi.openebs.io",
+    "local.csi.openebs.io",
+    "lvm.csi.openebs.io",
+];
+
+/// Fails the upgrade if any PVC bound to an openebs StorageClass is stuck
+/// `Pending`: the CSI controller restarts an upgrade causes would only
+/// delay their provisioning further, so it is better to surface them up
+/// front rather than leave the user guessing mid-upgrade.
+pub async fn pending_pvc_validation(ctx: &Context) -> Result<(), UpgradeError> {
+    let classes = openebs_storage_class_names(ctx).await?;
+
+    let pvcs: Api<PersistentVolumeClaim> = Api::all(ctx.client.clone());
+    let pending: Vec<String> = list_all(&pvcs, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|pvc| {
+            let sc = pvc.spec.as_ref().and_then(|s| s.storage_class_name.as_deref());
+            let is_pending = pvc
+                .status
+                .as_ref()
+                .and_then(|s| s.phase.as_deref())
+                .map(|p| p == "Pending")
+                .unwrap_or(false);
+            is_pending && sc.map(|sc| classes.contains(sc)).unwrap_or(false)
+        })
+        .map(|pvc| {
+            format!(
+                "{}/{}",
+                pvc.metadata.namespace.unwrap_or_default(),
+                pvc.metadata.name.unwrap_or_default()
+            )
+        })
+        .collect();
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+    Err(UpgradeError::ValidationFailed(format!(
+        "PVC(s) stuck Pending on an openebs StorageClass: {}",
+        pending.join(", ")
+    )))
+}
+
+/// DaemonSet containers that identify a node as part of the data plane:
+/// running an engine's node agent or io-engine pod. Also used by
+/// `kubectl openebs dump volume` to find the node agent pod to collect
+/// logs from for the node hosting a given volume.
+pub(crate) const DATA_PLANE_CONTAINERS: &[&str] = &["io-engine", "openebs-lvm-plugin", "openebs-zfs-plugin"];
+
+/// Node conditions that indicate a data-plane node is not healthy enough
+/// to safely ride out an upgrade's pod restarts.
+const UNHEALTHY_CONDITIONS: &[(&str, &str)] = &[
+    ("DiskPressure", "True"),
+    ("MemoryPressure", "True"),
+    ("Ready", "False"),
+];
+
+/// Fails the upgrade when a node running an openebs data-plane agent
+/// reports DiskPressure, MemoryPressure or NotReady: restarting
+/// components on an already-unhealthy node during an upgrade tends to
+/// turn a short blip into an extended outage.
+pub async fn node_health_validation(ctx: &Context) -> Result<(), UpgradeError> {
+    let data_plane_nodes = data_plane_node_names(ctx).await?;
+
+    let nodes: Api<Node> = Api::all(ctx.client.clone());
+    let mut unhealthy = Vec::new();
+    for node in list_all(&nodes, ctx.page_size).await? {
+        let Some(name) = node.metadata.name.clone() else {
+            continue;
+        };
+        if !data_plane_nodes.contains(&name) {
+            continue;
+        }
+        let conditions = node
+            .status
+            .as_ref()
+            .and_then(|s| s.conditions.as_ref())
+            .cloned()
+            .unwrap_or_default();
+        for condition in conditions {
+            if UNHEALTHY_CONDITIONS
+                .iter()
+                .any(|(kind, bad)| condition.type_ == *kind && condition.status == *bad)
+            {
+                unhealthy.push(format!("{name} ({}={})", condition.type_, condition.status));
+            }
+        }
+    }
+
+    if unhealthy.is_empty() {
+        return Ok(());
+    }
+    Err(UpgradeError::ValidationFailed(format!(
+        "data-plane node(s) unhealthy: {}",
+        unhealthy.join(", ")
+    )))
+}
+
+/// Returns the set of node names running an openebs data-plane container,
+/// derived from the running Pods rather than a fixed DaemonSet list so it
+/// works regardless of which engines are installed.
+async fn data_plane_node_names(
+    ctx: &Context,
+) -> Result<std::collections::HashSet<String>, UpgradeError> {
+    let pods: Api<Pod> = Api::all(ctx.client.clone());
+    Ok(list_all(&pods, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|pod| {
+            pod.spec
+                .as_ref()
+                .map(|spec| {
+                    spec.containers
+                        .iter()
+                        .any(|c| DATA_PLANE_CONTAINERS.contains(&c.name.as_str()))
+                })
+                .unwrap_or(false)
+        })
+        .filter_map(|pod| pod.spec.and_then(|s| s.node_name))
+        .collect())
+}
+
+/// Label node-drain automation (e.g. a cluster's `kubectl drain` wrapper)
+/// is expected to set while actively evicting a node's pods, as opposed
+/// to a node that is merely cordoned (`spec.unschedulable`) with no
+/// drain in progress.
+const DRAIN_IN_PROGRESS_LABEL: &str = "openebs.io/drain-in-progress";
+
+/// Whether a cordoned node is mid-drain or simply marked unschedulable
+/// with no eviction happening.
+pub enum CordonDrainState {
+    /// `spec.unschedulable` is set but no drain automation is running.
+    Cordoned,
+    /// Drain automation is actively evicting pods, identified by
+    /// [`DRAIN_IN_PROGRESS_LABEL`].
+    Draining { drain_label: String },
+}
+
+/// A cordoned node found by [`already_cordoned_nodes_validation`].
+pub struct CordonedNode {
+    pub name: String,
+    pub state: CordonDrainState,
+}
+
+/// Fails the upgrade if any node is cordoned, since the upgrade may
+/// itself need to reschedule pods onto it. Cordoned nodes are reported
+/// separately from nodes that are actively draining (so the operator
+/// knows whether to wait or intervene), and individual nodes can be
+/// exempted by name via `skip_nodes` instead of disabling the whole
+/// check.
+pub async fn already_cordoned_nodes_validation(
+    ctx: &Context,
+    skip_nodes: &[String],
+) -> Result<(), UpgradeError> {
+    let nodes: Api<Node> = Api::all(ctx.client.clone());
+    let mut cordoned = Vec::new();
+
+    for node in list_all(&nodes, ctx.page_size).await? {
+        let name = node.metadata.name.clone().unwrap_or_default();
+        if skip_nodes.iter().any(|n| n == &name) {
+            continue;
+        }
+        let is_cordoned = node
+            .spec
+            .as_ref()
+            .and_then(|s| s.unschedulable)
+            .unwrap_or(false);
+        if !is_cordoned {
+            continue;
+        }
+        let drain_label = node
+            .metadata
+            .labels
+            .as_ref()
+            .and_then(|l| l.get(DRAIN_IN_PROGRESS_LABEL))
+            .cloned();
+        let state = match drain_label {
+            Some(drain_label) => CordonDrainState::Draining { drain_label },
+            None => CordonDrainState::Cordoned,
+        };
+        cordoned.push(CordonedNode { name, state });
+    }
+
+    if cordoned.is_empty() {
+        return Ok(());
+    }
+
+    let described = cordoned
+        .iter()
+        .map(|n| match &n.state {
+            CordonDrainState::Cordoned => format!("{} (cordoned)", n.name),
+            CordonDrainState::Draining { drain_label } => {
+                format!("{} (draining, {DRAIN_IN_PROGRESS_LABEL}={drain_label})", n.name)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(UpgradeError::ValidationFailed(format!(
+        "node(s) cordoned: {described} (use --skip-cordoned-node-validation=<node> to exempt specific nodes)"
+    )))
+}
+
+/// PVC annotation that exempts a specific volume from
+/// [`single_volume_replica_validation`], for users who have accepted the
+/// downtime risk of upgrading a single-replica volume.
+pub const ALLOW_UPGRADE_DOWNTIME_ANNOTATION: &str = "openebs.io/allow-upgrade-downtime";
+
+/// Fails the upgrade if any PVC is bound to a single-replica openebs
+/// StorageClass, since restarting a 1-replica volume's target during
+/// upgrade causes an I/O outage. PVCs annotated with
+/// [`ALLOW_UPGRADE_DOWNTIME_ANNOTATION`] are excluded so the check only
+/// fails for the non-exempt remainder.
+pub async fn single_volume_replica_validation(ctx: &Context) -> Result<(), UpgradeError> {
+    let single_replica_classes = single_replica_storage_classes(ctx).await?;
+
+    let pvcs: Api<PersistentVolumeClaim> = Api::all(ctx.client.clone());
+    let offending: Vec<String> = list_all(&pvcs, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|pvc| {
+            let sc = pvc.spec.as_ref().and_then(|s| s.storage_class_name.as_deref());
+            let is_single_replica = sc.map(|sc| single_replica_classes.contains(sc)).unwrap_or(false);
+            let exempt = pvc
+                .metadata
+                .annotations
+                .as_ref()
+                .and_then(|a| a.get(ALLOW_UPGRADE_DOWNTIME_ANNOTATION))
+                .map(|v| v == "true")
+                .unwrap_or(false);
+            is_single_replica && !exempt
+        })
+        .map(|pvc| {
+            format!(
+                "{}/{}",
+                pvc.metadata.namespace.unwrap_or_default(),
+                pvc.metadata.name.unwrap_or_default()
+            )
+        })
+        .collect();
+
+    if offending.is_empty() {
+        return Ok(());
+    }
+    Err(UpgradeError::ValidationFailed(format!(
+        "single-replica volume(s) would lose I/O during upgrade (annotate the PVC with \
+         {ALLOW_UPGRADE_DOWNTIME_ANNOTATION}=\"true\" to proceed anyway): {}",
+        offending.join(", ")
+    )))
+}
+
+async fn single_replica_storage_classes(
+    ctx: &Context,
+) -> Result<std::collections::HashSet<String>, UpgradeError> {
+    use k8s_openapi::api::storage::v1::StorageClass;
+    let scs: Api<StorageClass> = Api::all(ctx.client.clone());
+    Ok(list_all(&scs, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|sc| {
+            sc.parameters
+                .as_ref()
+                .and_then(|p| p.get("replicaCount"))
+                .map(|v| v == "1")
+                .unwrap_or(false)
+        })
+        .filter_map(|sc| sc.metadata.name)
+        .collect())
+}
+
+/// Fails the upgrade if `target_version` is older than the plugin's own
+/// version, since the upgrade Job assumes it's only ever moving forward.
+/// A dev/unparsable version on either side is now reported in the
+/// returned error's reasoning rather than silently letting the check
+/// pass with no indication it had been skipped.
+pub fn upgrade_path_validation(target_version: &str) -> Result<(), UpgradeError> {
+    let current_version = env!("CARGO_PKG_VERSION");
+    let check = check_upgrade_path(current_version, target_version);
+    if check.allowed {
+        return Ok(());
+    }
+    Err(UpgradeError::ValidationFailed(check.reason))
+}
+
+/// Fails the upgrade unless `target_version` is explicitly set, instead
+/// of letting the upgrade Job fall back to resolving its own destination
+/// chart tag (historically `develop` when none was given), which can
+/// silently point the upgrade at a non-existent or unintended chart.
+/// `allow_implicit_destination` is the explicit, named opt-in for the
+/// rare case where that fallback is actually wanted.
+pub fn destination_version_validation(
+    target_version: Option<&str>,
+    allow_implicit_destination: bool,
+) -> Result<(), UpgradeError> {
+    if target_version.is_some() || allow_implicit_destination {
+        return Ok(());
+    }
+    Err(UpgradeError::ValidationFailed(
+        "no --to-version given; the upgrade Job would otherwise resolve its own destination \
+         chart tag, which can silently target the wrong (or a non-existent) chart. Pass \
+         --to-version explicitly, or --allow-implicit-destination-version to keep the Job's \
+         own fallback."
+            .to_string(),
+    ))
+}
+
+async fn openebs_storage_class_names(
+    ctx: &Context,
+) -> Result<std::collections::HashSet<String>, UpgradeError> {
+    use k8s_openapi::api::storage::v1::StorageClass;
+    let scs: Api<StorageClass> = Api::all(ctx.client.clone());
+    Ok(list_all(&scs, ctx.page_size)
+        .await?
+        .into_iter()
+        .filter(|sc| OPENEBS_PROVISIONERS.contains(&sc.provisioner.as_str()))
+        .filter_map(|sc| sc.metadata.name)
+        .collect())
+}
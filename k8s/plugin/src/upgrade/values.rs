@@ -0,0 +1,212 @@
+//! Parsing and schema validation for the `--set` / `--set-file` / `-f`
+//! values passed to `upgrade`. Catching typos here, against the target
+//! chart's `values.schema.json`, means they fail fast in the CLI instead
+//! of surfacing an hour later inside the upgrade Job.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::error::UpgradeError;
+
+/// A single `--set key=value`, `--set-file key=source` or `-f source`
+/// entry, parsed but not yet merged into the chart values tree. A `-f`
+/// whole-values-file entry is represented with an empty `key`, since it
+/// merges at the top level rather than under one dotted path.
+pub struct SetValue {
+    pub key: String,
+    pub value: Value,
+    /// sha256 of the source bytes, recorded for `--set-file`/`-f`
+    /// entries (`None` for inline `--set` scalars) so a remote fetch's
+    /// integrity can be checked once it lands in a ConfigMap.
+    pub checksum: Option<String>,
+}
+
+/// Parses `--set key.path=value` entries using Helm's dotted-path,
+/// comma-separated convention (`a.b=1,c=true`).
+pub fn parse_set(entries: &[String]) -> Result<Vec<SetValue>, UpgradeError> {
+    let mut out = Vec::new();
+    for entry in entries {
+        for pair in entry.split(',') {
+            let (key, raw) = pair.split_once('=').ok_or_else(|| {
+                UpgradeError::ValidationFailed(format!("--set {pair:?} is not in key=value form"))
+            })?;
+            out.push(SetValue {
+                key: key.to_string(),
+                value: infer_scalar(raw),
+                checksum: None,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// Parses `--set-file key=source`, fetching each source's raw contents
+/// as a string value (Helm's own semantics for `--set-file`). `source`
+/// may be a local path, an `https://` URL or an `s3://bucket/key`
+/// reference, so values already living in a central artifact store can
+/// be pulled in directly. Contents are syntax-checked as YAML/JSON up
+/// front, since malformed input otherwise only surfaces once it fails
+/// deep inside Helm, on the job's side.
+pub async fn parse_set_file(entries: &[String]) -> Result<Vec<SetValue>, UpgradeError> {
+    let mut out = Vec::new();
+    for entry in entries {
+        let (key, source) = entry.split_once('=').ok_or_else(|| {
+            UpgradeError::ValidationFailed(format!("--set-file {entry:?} is not in key=source form"))
+        })?;
+        let (contents, checksum) = fetch_source(source).await?;
+        validate_yaml_syntax(source, &contents)?;
+        out.push(SetValue {
+            key: key.to_string(),
+            value: Value::String(contents),
+            checksum: Some(checksum),
+        });
+    }
+    Ok(out)
+}
+
+/// Parses `-f`/`--values source` entries: like `--set-file`, but the
+/// fetched contents are a whole values document merged at the top level
+/// rather than under one dotted key.
+pub async fn parse_values_files(sources: &[String]) -> Result<Vec<SetValue>, UpgradeError> {
+    let mut out = Vec::new();
+    for source in sources {
+        let (contents, checksum) = fetch_source(source).await?;
+        validate_yaml_syntax(source, &contents)?;
+        out.push(SetValue {
+            key: String::new(),
+            value: Value::String(contents),
+            checksum: Some(checksum),
+        });
+    }
+    Ok(out)
+}
+
+/// Fetches `source`'s raw bytes -- from the local filesystem, an
+/// `https://` URL, or an `s3://bucket/key` reference -- and returns them
+/// alongside a sha256 checksum for later integrity verification.
+async fn fetch_source(source: &str) -> Result<(String, String), UpgradeError> {
+    let bytes = if let Some(url) = source.strip_prefix("s3://") {
+        fetch_s3_anonymous(url).await?
+    } else if source.starts_with("https://") {
+        fetch_https(source).await?
+    } else {
+        std::fs::read(source)
+            .map_err(|e| UpgradeError::ValidationFailed(format!("reading {source:?}: {e}")))?
+    };
+
+    let checksum = sha256_hex(&bytes);
+    let contents = String::from_utf8(bytes)
+        .map_err(|e| UpgradeError::ValidationFailed(format!("{source:?} is not valid UTF-8: {e}")))?;
+    Ok((contents, checksum))
+}
+
+async fn fetch_https(url: &str) -> Result<Vec<u8>, UpgradeError> {
+    let response = reqwest::get(url)
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| UpgradeError::ValidationFailed(format!("fetching {url}: {e}")))?;
+    response
+        .bytes()
+        .await
+        .map(|b| b.to_vec())
+        .map_err(|e| UpgradeError::ValidationFailed(format!("reading {url}: {e}")))
+}
+
+/// `s3://bucket/key` is fetched via the bucket's public virtual-hosted
+/// HTTPS endpoint. Only unauthenticated/public objects are supported for
+/// now -- there's no AWS credential chain wired into this plugin yet.
+async fn fetch_s3_anonymous(bucket_and_key: &str) -> Result<Vec<u8>, UpgradeError> {
+    let (bucket, key) = bucket_and_key.split_once('/').ok_or_else(|| {
+        UpgradeError::ValidationFailed(format!("s3://{bucket_and_key} is missing an object key"))
+    })?;
+    fetch_https(&format!("https://{bucket}.s3.amazonaws.com/{key}")).await
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Parses `contents` as YAML (a strict superset of JSON), surfacing a
+/// line/column-level syntax error at CLI time.
+fn validate_yaml_syntax(source: &str, contents: &str) -> Result<(), UpgradeError> {
+    serde_yaml::from_str::<serde_yaml::Value>(contents)
+        .map(|_| ())
+        .map_err(|e| {
+            UpgradeError::ValidationFailed(format!("{source:?} is not valid YAML/JSON: {e}"))
+        })
+}
+
+fn infer_scalar(raw: &str) -> Value {
+    if let Ok(b) = raw.parse::<bool>() {
+        return Value::Bool(b);
+    }
+    if let Ok(n) = raw.parse::<i64>() {
+        return Value::Number(n.into());
+    }
+    if let Ok(n) = raw.parse::<f64>() {
+        if let Some(n) = serde_json::Number::from_f64(n) {
+            return Value::Number(n);
+        }
+    }
+    Value::String(raw.to_string())
+}
+
+/// Validates `values` against `schema_path` (a chart's
+/// `values.schema.json`), failing with the first key that either doesn't
+/// exist in the schema or has the wrong JSON type. A missing schema file
+/// is not an error: not every chart ships one.
+pub fn validate_against_schema(
+    values: &[SetValue],
+    schema_path: &Path,
+) -> Result<(), UpgradeError> {
+    let Ok(schema_text) = std::fs::read_to_string(schema_path) else {
+        return Ok(());
+    };
+    let schema: Value = serde_json::from_str(&schema_text).map_err(|e| {
+        UpgradeError::ValidationFailed(format!("parsing {}: {e}", schema_path.display()))
+    })?;
+    let compiled = jsonschema::JSONSchema::compile(&schema).map_err(|e| {
+        UpgradeError::ValidationFailed(format!("invalid values.schema.json: {e}"))
+    })?;
+
+    for set in values {
+        let probe = if set.key.is_empty() {
+            // A `-f` whole-values file: validate its parsed contents
+            // directly, rather than nesting them under a dotted key.
+            let yaml = set.value.as_str().unwrap_or_default();
+            serde_yaml::from_str::<Value>(yaml).map_err(|e| {
+                UpgradeError::ValidationFailed(format!("parsing values file for schema check: {e}"))
+            })?
+        } else {
+            build_nested(&set.key, set.value.clone())
+        };
+        let first_error = compiled
+            .validate(&probe)
+            .err()
+            .and_then(|mut errors| errors.next().map(|e| e.to_string()));
+        if let Some(first) = first_error {
+            let label = if set.key.is_empty() {
+                "-f values file".to_string()
+            } else {
+                format!("--set {}", set.key)
+            };
+            return Err(UpgradeError::ValidationFailed(format!("{label}: {first}")));
+        }
+    }
+    Ok(())
+}
+
+/// Builds `{a: {b: value}}` from the dotted path `a.b`, so a single
+/// `--set` entry can be checked against the whole-values schema without
+/// requiring every other field to be present.
+fn build_nested(dotted_key: &str, value: Value) -> Value {
+    let mut out = value;
+    for segment in dotted_key.rsplit('.') {
+        out = serde_json::json!({ segment: out });
+    }
+    out
+}
@@ -0,0 +1,180 @@
+//! Parses and compares the semver-ish version tags (`vX.Y.Z[-pre][+build]`)
+//! used to validate `--to-version` against the plugin's own version before
+//! the upgrade Job is ever created.
+
+use std::cmp::Ordering;
+
+/// A parsed version tag, explicit about whether it's a properly formed
+/// release tag or a dev/unparsable build, rather than silently treating
+/// the latter as "nothing to check" the way the original comparison did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ParsedVersion {
+    Release(SemVer),
+    /// Didn't parse as `vX.Y.Z`: a local dev build, a dirty checkout
+    /// (`-dirty` suffix on some build pipelines), or a malformed tag.
+    /// Carries the original string so callers can still report it.
+    Dev(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SemVer {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub prerelease: Option<String>,
+    /// Build metadata (the `+...` suffix). Carried through for display
+    /// only -- per semver it never affects precedence.
+    pub build: Option<String>,
+}
+
+impl ParsedVersion {
+    pub(crate) fn parse(raw: &str) -> Self {
+        let trimmed = raw.strip_prefix('v').unwrap_or(raw);
+        let (core, build) = match trimmed.split_once('+') {
+            Some((core, build)) => (core, Some(build.to_string())),
+            None => (trimmed, None),
+        };
+        let (core, prerelease) = match core.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (core, None),
+        };
+
+        let mut parts = core.split('.');
+        let parsed = (|| {
+            let major = parts.next()?.parse().ok()?;
+            let minor = parts.next()?.parse().ok()?;
+            let patch = parts.next()?.parse().ok()?;
+            if parts.next().is_some() {
+                return None;
+            }
+            Some((major, minor, patch))
+        })();
+
+        match parsed {
+            Some((major, minor, patch)) => ParsedVersion::Release(SemVer {
+                major,
+                minor,
+                patch,
+                prerelease,
+                build,
+            }),
+            None => ParsedVersion::Dev(raw.to_string()),
+        }
+    }
+}
+
+impl SemVer {
+    /// Precedence compare per semver: build metadata never affects it,
+    /// and a prerelease always has lower precedence than its release.
+    fn precedence_cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.prerelease, &other.prerelease) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+/// The outcome of [`check_upgrade_path`], carrying the reasoning shown to
+/// the user either way rather than just a pass/fail bit.
+pub(crate) struct UpgradePathCheck {
+    pub allowed: bool,
+    pub reason: String,
+}
+
+/// Validates that `target` is not a downgrade from `current`. Dev/
+/// unparsable versions are now reported explicitly -- instead of
+/// silently passing the check the way an unparsable tag used to -- so a
+/// dirty dev build skipping validation is visible in the reasoning.
+pub(crate) fn check_upgrade_path(current: &str, target: &str) -> UpgradePathCheck {
+    match (ParsedVersion::parse(current), ParsedVersion::parse(target)) {
+        (ParsedVersion::Dev(raw), _) => UpgradePathCheck {
+            allowed: true,
+            reason: format!(
+                "current version {raw:?} is a dev/unparsable build; skipping the upgrade-path \
+                 check against target {target:?}"
+            ),
+        },
+        (_, ParsedVersion::Dev(raw)) => UpgradePathCheck {
+            allowed: true,
+            reason: format!(
+                "target version {raw:?} is a dev/unparsable build; skipping the upgrade-path check"
+            ),
+        },
+        (ParsedVersion::Release(from), ParsedVersion::Release(to)) => match to.precedence_cmp(&from) {
+            Ordering::Less => UpgradePathCheck {
+                allowed: false,
+                reason: format!(
+                    "target {target} is older than the current version {current}; \
+                     downgrades are not supported"
+                ),
+            },
+            Ordering::Equal => UpgradePathCheck {
+                allowed: true,
+                reason: format!("target {target} is the same version as {current}; no-op upgrade"),
+            },
+            Ordering::Greater => UpgradePathCheck {
+                allowed: true,
+                reason: format!("target {target} is newer than the current version {current}"),
+            },
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prerelease_has_lower_precedence_than_its_own_release() {
+        let pre = ParsedVersion::parse("1.2.3-rc1");
+        let release = ParsedVersion::parse("1.2.3");
+        match (pre, release) {
+            (ParsedVersion::Release(pre), ParsedVersion::Release(release)) => {
+                assert_eq!(pre.precedence_cmp(&release), Ordering::Less);
+            }
+            _ => panic!("expected both to parse as releases"),
+        }
+    }
+
+    #[test]
+    fn build_metadata_does_not_affect_precedence() {
+        let a = ParsedVersion::parse("1.2.3+build1");
+        let b = ParsedVersion::parse("1.2.3+build2");
+        match (a, b) {
+            (ParsedVersion::Release(a), ParsedVersion::Release(b)) => {
+                assert_eq!(a.precedence_cmp(&b), Ordering::Equal);
+            }
+            _ => panic!("expected both to parse as releases"),
+        }
+    }
+
+    #[test]
+    fn check_upgrade_path_rejects_a_real_downgrade() {
+        let check = check_upgrade_path("1.3.0", "1.2.0");
+        assert!(!check.allowed);
+    }
+
+    #[test]
+    fn check_upgrade_path_allows_a_real_upgrade() {
+        let check = check_upgrade_path("1.2.0", "1.3.0");
+        assert!(check.allowed);
+    }
+
+    #[test]
+    fn check_upgrade_path_allows_dev_current_regardless_of_target() {
+        let check = check_upgrade_path("dev-abc123", "1.0.0");
+        assert!(check.allowed);
+        assert!(check.reason.contains("dev-abc123"));
+    }
+
+    #[test]
+    fn check_upgrade_path_allows_dev_target_regardless_of_current() {
+        let check = check_upgrade_path("1.0.0", "dev-abc123");
+        assert!(check.allowed);
+        assert!(check.reason.contains("dev-abc123"));
+    }
+}
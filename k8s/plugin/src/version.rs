@@ -0,0 +1,48 @@
+//! `kubectl openebs version`: prints the plugin's own version and, with
+//! `--check-upgrade-path`, validates a candidate `--to-version` against
+//! it standalone, with the same verbose reasoning `upgrade apply` uses,
+//! without needing cluster access or submitting an upgrade Job.
+//!
+//! `--check-updates` additionally lists chart versions newer than the
+//! plugin's own from a (locally cached) chart repository index; see
+//! [`crate::updates`].
+
+use crate::updates::{self, CheckUpdatesArgs};
+use crate::upgrade::version::check_upgrade_path;
+
+#[derive(clap::Args)]
+pub struct VersionArgs {
+    /// Validate `<target>` as an upgrade target for the plugin's current
+    /// version, printing the reasoning, instead of just printing the
+    /// current version.
+    #[arg(long)]
+    pub check_upgrade_path: Option<String>,
+
+    /// List chart versions newer than the plugin's own, instead of just
+    /// printing the current version.
+    #[arg(long)]
+    pub check_updates: bool,
+
+    #[command(flatten)]
+    pub check_updates_args: CheckUpdatesArgs,
+}
+
+pub async fn run(args: VersionArgs) -> anyhow::Result<()> {
+    let current_version = env!("CARGO_PKG_VERSION");
+
+    if args.check_updates {
+        return updates::run(&args.check_updates_args).await;
+    }
+
+    let Some(target_version) = args.check_upgrade_path else {
+        println!("{current_version}");
+        return Ok(());
+    };
+
+    let check = check_upgrade_path(current_version, &target_version);
+    println!("{}", check.reason);
+    if !check.allowed {
+        anyhow::bail!("upgrade path {current_version} -> {target_version} is not allowed");
+    }
+    Ok(())
+}
@@ -0,0 +1,170 @@
+//! `kubectl openebs webhook`: an optional validating-webhook server that
+//! enforces the same guardrails as the offline audit ruleset
+//! ([`crate::audit`]) live, via a ValidatingWebhookConfiguration.
+
+use axum::{extract::State, routing::post, Json, Router};
+use axum_server::tls_rustls::RustlsConfig;
+use kube::core::admission::{AdmissionRequest, AdmissionResponse, AdmissionReview};
+use kube::api::DynamicObject;
+
+use crate::audit;
+
+/// Where `generate_manifests`'s Deployment mounts the TLS secret, and
+/// `serve`'s own `--tls-cert`/`--tls-key` defaults -- a cluster running
+/// the generated manifests as-is needs no extra flags.
+const TLS_CERT_PATH: &str = "/etc/openebs-webhook/tls/tls.crt";
+const TLS_KEY_PATH: &str = "/etc/openebs-webhook/tls/tls.key";
+
+#[derive(clap::Subcommand)]
+pub enum WebhookCommand {
+    /// Run the validating-webhook HTTPS server. A
+    /// ValidatingWebhookConfiguration can only call a webhook over TLS,
+    /// so this always terminates TLS itself rather than offering a
+    /// plaintext mode.
+    Serve {
+        /// Address to listen on for AdmissionReview requests.
+        #[arg(long, default_value = "0.0.0.0:8443")]
+        addr: String,
+
+        /// PEM-encoded TLS certificate (chain) to serve.
+        #[arg(long, default_value = TLS_CERT_PATH)]
+        tls_cert: String,
+
+        /// PEM-encoded TLS private key matching `--tls-cert`.
+        #[arg(long, default_value = TLS_KEY_PATH)]
+        tls_key: String,
+    },
+    /// Print the Deployment/Service/ValidatingWebhookConfiguration
+    /// manifests needed to run the webhook in-cluster.
+    GenerateManifests {
+        /// Namespace the webhook Deployment/Service are created in.
+        #[arg(long, default_value = "openebs")]
+        namespace: String,
+    },
+}
+
+pub async fn run(cmd: WebhookCommand) -> anyhow::Result<()> {
+    match cmd {
+        WebhookCommand::Serve { addr, tls_cert, tls_key } => serve(&addr, &tls_cert, &tls_key).await,
+        WebhookCommand::GenerateManifests { namespace } => {
+            print!("{}", generate_manifests(&namespace));
+            Ok(())
+        }
+    }
+}
+
+async fn serve(addr: &str, tls_cert: &str, tls_key: &str) -> anyhow::Result<()> {
+    let app = Router::new()
+        .route("/validate", post(validate))
+        .with_state(());
+
+    let tls_config = RustlsConfig::from_pem_file(tls_cert, tls_key).await?;
+    let addr: std::net::SocketAddr = addr.parse()?;
+    tracing::info!(%addr, "openebs validating webhook listening");
+    axum_server::bind_rustls(addr, tls_config)
+        .serve(app.into_make_service())
+        .await?;
+    Ok(())
+}
+
+async fn validate(
+    State(()): State<()>,
+    Json(review): Json<AdmissionReview<DynamicObject>>,
+) -> Json<AdmissionReview<DynamicObject>> {
+    let request: AdmissionRequest<DynamicObject> = match review.request {
+        Some(req) => req,
+        None => return Json(AdmissionResponse::invalid("missing AdmissionRequest").into_review()),
+    };
+
+    let response = match &request.object {
+        Some(obj) => {
+            let violations = audit::check(obj);
+            if violations.is_empty() {
+                AdmissionResponse::from(&request)
+            } else {
+                let reason = violations
+                    .iter()
+                    .map(|v| format!("[{}] {}", v.rule, v.message))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                AdmissionResponse::from(&request).deny(reason)
+            }
+        }
+        None => AdmissionResponse::from(&request),
+    };
+
+    Json(response.into_review())
+}
+
+/// Renders the Deployment, Service and ValidatingWebhookConfiguration
+/// needed to run `webhook serve` in-cluster; operators are expected to
+/// supply their own TLS secret, named `openebs-validating-webhook-tls`
+/// and shaped like the usual cert-manager `kubernetes.io/tls` Secret
+/// (`tls.crt`/`tls.key`), via the usual cert-manager/openebs conventions
+/// before applying these. It's mounted at `TLS_CERT_PATH`/`TLS_KEY_PATH`,
+/// `serve`'s own `--tls-cert`/`--tls-key` defaults.
+fn generate_manifests(namespace: &str) -> String {
+    format!(
+        r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: openebs-validating-webhook
+  namespace: {namespace}
+spec:
+  replicas: 1
+  selector:
+    matchLabels:
+      app: openebs-validating-webhook
+  template:
+    metadata:
+      labels:
+        app: openebs-validating-webhook
+    spec:
+      containers:
+        - name: webhook
+          image: openebs/kubectl-openebs:latest
+          args: ["webhook", "serve", "--addr=0.0.0.0:8443"]
+          ports:
+            - containerPort: 8443
+          volumeMounts:
+            - name: tls
+              mountPath: /etc/openebs-webhook/tls
+              readOnly: true
+      volumes:
+        - name: tls
+          secret:
+            secretName: openebs-validating-webhook-tls
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: openebs-validating-webhook
+  namespace: {namespace}
+spec:
+  selector:
+    app: openebs-validating-webhook
+  ports:
+    - port: 443
+      targetPort: 8443
+---
+apiVersion: admissionregistration.k8s.io/v1
+kind: ValidatingWebhookConfiguration
+metadata:
+  name: openebs-validating-webhook
+webhooks:
+  - name: validate.openebs.io
+    clientConfig:
+      service:
+        name: openebs-validating-webhook
+        namespace: {namespace}
+        path: /validate
+    rules:
+      - apiGroups: ["storage.k8s.io"]
+        apiVersions: ["v1"]
+        operations: ["CREATE", "UPDATE"]
+        resources: ["storageclasses"]
+    sideEffects: None
+    admissionReviewVersions: ["v1"]
+"#
+    )
+}